@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lobsterchat::lobster;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const MESSAGE: &str =
+    "<red>Red text <green>Green text <italic><yellow>Yellow italic text. <bold>BOLD. <red>Red text";
+const SEGMENT: &str =
+    "<red>Red text <green>Green text <italic><yellow>Yellow italic text. <bold>BOLD. ";
+
+/// Allocator wrapper counting every allocation, used to report
+/// how many allocations a single `lobster` call performs.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn benchmark_lobster(c: &mut Criterion) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let component = lobster(MESSAGE);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    drop(component);
+    println!("lobster: {} allocations per call", allocations);
+
+    c.bench_function("lobster", |b| b.iter(|| lobster(black_box(MESSAGE))));
+}
+
+/// Parsing time should grow linearly with the message length, so the
+/// throughput reported for every size should stay roughly the same.
+fn benchmark_lobster_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lobster_scaling");
+    for segments in [4, 8, 16, 32] {
+        let msg = SEGMENT.repeat(segments);
+        group.throughput(Throughput::Bytes(msg.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(msg.len()), &msg, |b, msg| {
+            b.iter(|| lobster(black_box(msg.as_str())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_lobster, benchmark_lobster_scaling);
+criterion_main!(benches);
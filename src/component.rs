@@ -3,11 +3,58 @@
 //! Main module containing all the component related things
 //!
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use serde_with::skip_serializing_none;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use uuid::Uuid;
 
+mod book;
+mod builder;
+mod canonical;
+mod compare;
+pub(crate) mod distance;
+pub(crate) mod gradient;
+mod histogram;
+#[cfg(feature = "html")]
+mod html;
+mod item;
+mod iter;
+mod keybind;
+pub(crate) mod legacy;
+mod limits;
+mod metrics;
+#[cfg(feature = "minimessage")]
+mod minimessage;
+mod resource;
+mod runs;
+mod sanitize;
+mod snbt;
+mod space;
+mod style;
+#[cfg(feature = "valence")]
+mod valence;
+mod version;
+
+pub use book::{Book, TooManyPages, MAX_PAGES, MAX_PAGE_CHARS};
+pub use builder::ComponentBuilder;
+pub use gradient::ColorSpace;
+pub use histogram::StyleHistogram;
+pub use item::DisplayItemDataBuilder;
+pub use iter::Descendants;
+pub use keybind::{UnknownKeybind, VANILLA_KEYBINDS};
+pub use legacy::SECTION_SIGN;
+pub use metrics::FontMetrics;
+pub use resource::{InvalidIdentifier, ResourceLocation, DEFAULT_NAMESPACE};
+pub use runs::ResolvedStyle;
+pub use sanitize::SanitizePolicy;
+pub use space::{SPACE_CHARACTERS, SPACE_FONT};
+pub use style::Style;
+pub use version::ProtocolVersion;
+
 /// This trait allows you to convert an object into a component
 /// by passing it as reference
 pub trait AsComponent {
@@ -25,21 +72,61 @@ where
     }
 }
 
+/// Creates a plain text component from the string, without parsing any tags.
+/// Use [`str::parse`] to parse the string as minimessage instead.
 impl From<&str> for Component {
     fn from(str: &str) -> Self {
         Component::text(str)
     }
 }
 
+/// Parses the string as a minimessage string in strict mode, so that malformed
+/// messages are reported instead of being silently truncated.
+/// Use [`From<&str>`](Component::from) to create a plain text component instead.
+///
+/// ```rust
+/// use lobsterchat::component::Component;
+///
+/// let mut parsed: Component = "<red>Hello, <bold>World!".parse().unwrap();
+/// assert_eq!(parsed.flatten(), "Hello, World!");
+///
+/// assert!("<unknown>Hello".parse::<Component>().is_err());
+/// ```
+#[cfg(feature = "minimessage")]
+impl FromStr for Component {
+    type Err = crate::message::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::message::MiniMessageParser::new()
+            .strict(true)
+            .parse(s)
+    }
+}
+
 impl From<String> for Component {
     fn from(value: String) -> Self {
         Component::text(value)
     }
 }
 
+/// Constructs an empty text component with the provided children. The parent carries
+/// no style of its own, so the children are displayed exactly as they are.
+///
+/// ```rust
+/// use lobsterchat::component::Component;
+///
+/// let parent = Component::from(vec![Component::text("a"), Component::text("b")]);
+/// assert_eq!(parent, Component::text("").append("a").append("b"));
+/// ```
+impl From<Vec<Component>> for Component {
+    fn from(children: Vec<Component>) -> Self {
+        Component::default().with_extra(children)
+    }
+}
+
 /// A container for item data to be displayed
 /// See [wiki.vg](https://wiki.vg/Chat#Schema) for more info.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[skip_serializing_none]
 pub struct DisplayItemData {
     /// Namespaced ID of this item. Stored in format of
@@ -54,7 +141,7 @@ pub struct DisplayItemData {
 
 /// A container for entity data to be displayed
 /// See [wiki.vg](https://wiki.vg/Chat#Schema) for more info.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 #[skip_serializing_none]
 pub struct DisplayEntityData {
     /// Optional display name of entity
@@ -68,9 +155,53 @@ pub struct DisplayEntityData {
     pub id: Uuid,
 }
 
+impl DisplayItemData {
+    /// Constructs new item data, validating that the item ID is a [ResourceLocation].
+    ///
+    /// The fields can also be assigned directly to skip the validation.
+    pub fn try_new<S>(
+        id: S,
+        count: Option<i32>,
+        tag: Option<String>,
+    ) -> Result<Self, InvalidIdentifier>
+    where
+        S: Into<String>,
+    {
+        let id = id.into();
+        ResourceLocation::from_str(&id)?;
+        Ok(Self { id, count, tag })
+    }
+}
+
+impl DisplayEntityData {
+    /// Constructs new entity data, validating that the entity type is a [ResourceLocation].
+    ///
+    /// The fields can also be assigned directly to skip the validation.
+    pub fn try_new<S>(
+        name: Option<Component>,
+        entity_type: S,
+        id: Uuid,
+    ) -> Result<Self, InvalidIdentifier>
+    where
+        S: Into<String>,
+    {
+        let entity_type = entity_type.into();
+        ResourceLocation::from_str(&entity_type)?;
+        Ok(Self {
+            name,
+            entity_type,
+            id,
+        })
+    }
+}
+
 /// Container for component hover events.
 /// See [wiki.vg](https://wiki.vg/Chat#Schema) for more info.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+///
+/// Hover events are always serialized in the modern `contents` format, but the legacy
+/// `value` format sent by pre-1.16 servers is accepted on deserialization as well.
+/// Legacy achievement hover events only ever had the `value` format, so they keep it.
+#[derive(Debug, Clone, Serialize, PartialEq, PartialOrd, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "action")]
 pub enum HoverEvent {
@@ -91,6 +222,12 @@ pub enum HoverEvent {
         /// Boxed to avoid possible recursion problems.
         contents: Box<DisplayEntityData>,
     },
+    /// Displays an achievement to player. Only supported before Minecraft 1.12,
+    /// where achievements were replaced with advancements, and ignored by current clients.
+    ShowAchievement {
+        /// The ID of the achievement, e.g. `achievement.openInventory`
+        value: String,
+    },
 }
 
 impl HoverEvent {
@@ -114,11 +251,149 @@ impl HoverEvent {
             contents: Box::new(entity_data),
         }
     }
+
+    /// Shows the achievement with the provided ID on hover, for legacy servers only.
+    /// See [`HoverEvent::ShowAchievement`]
+    pub fn show_achievement<S: Into<String>>(id: S) -> HoverEvent {
+        HoverEvent::ShowAchievement { value: id.into() }
+    }
+}
+
+impl<'de> Deserialize<'de> for HoverEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        enum Action {
+            #[serde(rename = "show_text")]
+            Text,
+            #[serde(rename = "show_item")]
+            Item,
+            #[serde(rename = "show_entity")]
+            Entity,
+            #[serde(rename = "show_achievement")]
+            Achievement,
+        }
+
+        #[derive(Deserialize)]
+        struct RawHoverEvent {
+            action: Action,
+            contents: Option<Value>,
+            value: Option<Value>,
+        }
+
+        let raw = RawHoverEvent::deserialize(deserializer)?;
+        match (raw.action, raw.contents, raw.value) {
+            // achievements only ever had the legacy format, with the ID as the value
+            (Action::Achievement, contents, value) => {
+                let value = value
+                    .or(contents)
+                    .ok_or_else(|| D::Error::missing_field("value"))?;
+                component_from_value(value).map(|mut id| HoverEvent::show_achievement(id.flatten()))
+            }
+            (Action::Text, Some(contents), _) => {
+                component_from_value(contents).map(HoverEvent::show_text)
+            }
+            (Action::Item, Some(contents), _) => {
+                serde_json::from_value(contents).map(HoverEvent::show_item)
+            }
+            (Action::Entity, Some(contents), _) => {
+                serde_json::from_value(contents).map(HoverEvent::show_entity)
+            }
+            (Action::Text, None, Some(value)) => {
+                component_from_value(value).map(HoverEvent::show_text)
+            }
+            // legacy items and entities are stored as SNBT, either as a
+            // plain string or as the text of a component
+            (Action::Item, None, Some(value)) => {
+                let snbt = component_from_value(value)
+                    .map_err(D::Error::custom)?
+                    .flatten();
+                return legacy_item(&snbt)
+                    .map(HoverEvent::show_item)
+                    .ok_or_else(|| D::Error::custom(format!("invalid legacy item: {}", snbt)));
+            }
+            (Action::Entity, None, Some(value)) => {
+                let snbt = component_from_value(value)
+                    .map_err(D::Error::custom)?
+                    .flatten();
+                return legacy_entity(&snbt)
+                    .map(HoverEvent::show_entity)
+                    .ok_or_else(|| D::Error::custom(format!("invalid legacy entity: {}", snbt)));
+            }
+            (_, None, None) => return Err(D::Error::missing_field("contents")),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+/// Reads a component from a JSON value, which may also be a plain string,
+/// or an array of components where the first one is the parent of the rest
+fn component_from_value(value: Value) -> serde_json::Result<Component> {
+    match value {
+        Value::String(text) => Ok(Component::text(text)),
+        Value::Array(values) => {
+            let mut values = values.into_iter().map(component_from_value);
+            let mut parent = values.next().transpose()?.unwrap_or_default();
+            for child in values {
+                parent.push_extra(child?);
+            }
+            Ok(parent)
+        }
+        value => serde_json::from_value(value),
+    }
+}
+
+/// Reads item data from a legacy SNBT string, e.g. `{id:"minecraft:stone",Count:1b}`
+fn legacy_item(snbt: &str) -> Option<DisplayItemData> {
+    let mut item = DisplayItemData {
+        id: String::new(),
+        count: None,
+        tag: None,
+    };
+    for (key, value) in snbt::read_compound(snbt)? {
+        match key.as_str() {
+            "id" => item.id = value,
+            "Count" => {
+                let count = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                item.count = count.parse().ok();
+            }
+            "tag" => item.tag = Some(value),
+            _ => {}
+        }
+    }
+    Some(item).filter(|it| !it.id.is_empty())
+}
+
+/// Reads entity data from a legacy SNBT string, e.g. `{type:"minecraft:pig",id:"<uuid>",name:"<json>"}`.
+/// Malformed UUIDs are replaced with a nil UUID, and names that are not valid JSON are kept as plain text.
+fn legacy_entity(snbt: &str) -> Option<DisplayEntityData> {
+    let mut entity = DisplayEntityData {
+        name: None,
+        entity_type: String::new(),
+        id: Uuid::nil(),
+    };
+    for (key, value) in snbt::read_compound(snbt)? {
+        match key.as_str() {
+            "type" => entity.entity_type = value,
+            "id" => entity.id = Uuid::parse_str(&value).unwrap_or_default(),
+            "name" => {
+                entity.name = Some(
+                    serde_json::from_str(&value)
+                        .and_then(component_from_value)
+                        .unwrap_or_else(|_| Component::text(value)),
+                )
+            }
+            _ => {}
+        }
+    }
+    Some(entity).filter(|it| !it.entity_type.is_empty())
 }
 
 /// Container for click events
 /// See [wiki.vg](https://wiki.vg/Chat#Schema) for more info.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "action", content = "value")]
 pub enum ClickEvent {
@@ -165,7 +440,7 @@ impl ClickEvent {
 /// Note that the components are *immutable*, an they are cloned
 /// each time they are modified.
 #[skip_serializing_none]
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 pub struct Component {
     extra: Option<Vec<Component>>,
     bold: Option<bool>,
@@ -175,6 +450,8 @@ pub struct Component {
     underlined: Option<bool>,
     reset: Option<bool>,
     color: Option<TextColor>,
+    font: Option<String>,
+    shadow_color: Option<u32>,
     #[serde(flatten)]
     contents: MessageContents,
     insertion: Option<String>,
@@ -184,9 +461,9 @@ pub struct Component {
     hover_event: Option<HoverEvent>,
 }
 
-impl ToString for Component {
-    fn to_string(&self) -> String {
-        serde_json::to_string(self).unwrap()
+impl Display for Component {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
     }
 }
 
@@ -227,8 +504,7 @@ pub trait Colored<C> {
 
 impl Colored<u32> for Component {
     fn color(&mut self, color: u32) -> Self {
-        let str = format!("#{:2X}", color);
-        self.color = Some(TextColor::Hex(str));
+        self.color = Some(color.into());
         self.clone()
     }
 }
@@ -247,6 +523,15 @@ impl Colored<TextColor> for Component {
     }
 }
 
+/// Parses the string as a hex or named color, e.g. from a config file, leaving the
+/// component unchanged if the color is invalid. See [`Component::try_color()`] to
+/// handle invalid colors instead.
+impl Colored<&str> for Component {
+    fn color(&mut self, color: &str) -> Self {
+        self.try_color(color).unwrap_or_else(|_| self.clone())
+    }
+}
+
 impl Component {
     /// Constructs a new literal text component.
     pub fn text<S>(msg: S) -> Self
@@ -258,6 +543,236 @@ impl Component {
         df
     }
 
+    /// Constructs a new literal text component consisting of `n` spaces,
+    /// e.g. for aligning text in columns.
+    pub fn space(n: usize) -> Self {
+        Self::text(" ".repeat(n))
+    }
+
+    /// Constructs a new literal text component with the text repeated `n` times,
+    /// see [`Self::repeat()`] for repeating styled components.
+    pub fn repeat_text(text: &str, n: usize) -> Self {
+        Self::text(text.repeat(n))
+    }
+
+    /// Constructs a new literal text component with the provided color.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// assert_eq!(Component::colored("Hi", NamedColor::Red), Component::text("Hi").color(NamedColor::Red));
+    /// assert_eq!(Component::colored("Hi", 0xFF5555), Component::text("Hi").color(0xFF5555));
+    /// ```
+    pub fn colored<S, C>(msg: S, color: C) -> Self
+    where
+        S: Into<String>,
+        C: Into<TextColor>,
+    {
+        let mut df = Self::text(msg);
+        df.color = Some(color.into());
+        df
+    }
+
+    /// Constructs a new bold literal text component.
+    pub fn text_bold<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut df = Self::text(msg);
+        df.bold = Some(true);
+        df
+    }
+
+    /// Constructs a new italic literal text component.
+    pub fn text_italic<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut df = Self::text(msg);
+        df.italic = Some(true);
+        df
+    }
+
+    /// Constructs a new literal text component, coloring each of its characters
+    /// with a gradient going through all the provided RGB colors.
+    ///
+    /// The resulting component has an empty text, and contains a child
+    /// component for each character of the message.
+    pub fn gradient<S>(msg: S, stops: &[u32]) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::gradient_in(msg, stops, ColorSpace::default())
+    }
+
+    /// Same as [`Self::gradient()`], but interpolates the colors in the provided [ColorSpace].
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{ColorSpace, Component};
+    ///
+    /// let mut vivid = Component::gradient_in("Rainbow", &[0xFF0000, 0x0000FF], ColorSpace::Oklab);
+    /// assert_eq!(vivid.flatten(), "Rainbow");
+    /// ```
+    pub fn gradient_in<S>(msg: S, stops: &[u32], space: ColorSpace) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let colors = gradient::colors(stops, msg.chars().count(), space);
+        Self::colored_chars(&msg, colors)
+    }
+
+    /// Same as [`Self::gradient()`], but starts at the `phase` of the gradient, so that the
+    /// colors can be scrolled by rendering the component again with an increasing phase.
+    ///
+    /// The phase is a fraction of the length of the gradient, and it wraps around, so `0.0`
+    /// and `1.0` are both the same as [`Self::gradient()`], while `0.25` starts a quarter
+    /// of the way through the gradient. The colors past its end start over from the first stop.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let stops = [0xFF0000, 0x0000FF];
+    /// let plain = Component::gradient("abc", &stops);
+    /// let shifted = Component::gradient_with_phase("abc", &stops, 0.5);
+    /// // starting at the middle of the gradient
+    /// let color = |comp: &Component, n| comp.children().nth(n).unwrap().get_color_opt().cloned();
+    /// assert_eq!(color(&shifted, 0), color(&plain, 1));
+    /// ```
+    pub fn gradient_with_phase<S>(msg: S, stops: &[u32], phase: f32) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let len = msg.chars().count();
+        let colors = gradient::colors_phased(stops, len, ColorSpace::default(), phase);
+        Self::colored_chars(&msg, colors)
+    }
+
+    /// Same as [`Self::gradient()`], but goes through the stops in reverse order,
+    /// so that the gradient starts with the last stop and ends with the first one.
+    pub fn gradient_rev<S>(msg: S, stops: &[u32]) -> Self
+    where
+        S: Into<String>,
+    {
+        let reversed = stops.iter().rev().copied().collect::<Vec<_>>();
+        Self::gradient(msg, &reversed)
+    }
+
+    /// Constructs a new literal text component, coloring each of its characters
+    /// so that the message goes once around the hue circle, starting with red.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let mut rainbow = Component::rainbow("Rainbow");
+    /// assert_eq!(rainbow.flatten(), "Rainbow");
+    /// ```
+    pub fn rainbow<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let colors = gradient::rainbow(msg.chars().count());
+        Self::colored_chars(&msg, colors)
+    }
+
+    /// Same as [`Self::rainbow()`], but goes around the hue circle in the opposite direction,
+    /// so that the message ends with red instead.
+    pub fn rainbow_rev<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let colors = gradient::rainbow(msg.chars().count()).rev();
+        Self::colored_chars(&msg, colors)
+    }
+
+    /// Same as [`Self::rainbow()`], but with the provided HSV saturation and value (brightness)
+    /// of the colors, e.g. lower saturation for pastel colors. Both are clamped to the range
+    /// of `0.0..=1.0`, where `1.0` for both is the same as the plain rainbow.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let pastel = Component::rainbow_hsv("Pastel", 0.5, 1.0);
+    /// assert_eq!(pastel.children().next().unwrap().get_color_opt(), Some(&0xFF8080.into()));
+    /// ```
+    pub fn rainbow_hsv<S>(msg: S, saturation: f32, value: f32) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let (saturation, value) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let colors = gradient::rainbow_hsv(msg.chars().count(), saturation, value);
+        Self::colored_chars(&msg, colors)
+    }
+
+    /// Same as [`Self::gradient()`], but colors each whitespace-delimited word of the message
+    /// with a single color, so that the component only has a child for each word.
+    /// The whitespace following a word is a part of its child.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component};
+    ///
+    /// let words = Component::gradient_words("Hello there, World", &[0xFF0000, 0x0000FF]);
+    /// assert_eq!(
+    ///     words,
+    ///     Component::default()
+    ///         .append(Component::text("Hello ").color(0xFF0000))
+    ///         .append(Component::text("there, ").color(0x800080))
+    ///         .append(Component::text("World").color(0x0000FF))
+    /// );
+    /// ```
+    pub fn gradient_words<S>(msg: S, stops: &[u32]) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let words = gradient::words(&msg);
+        let colors = gradient::colors(stops, words.len(), ColorSpace::default());
+        Self::colored_parts(words, colors)
+    }
+
+    /// Same as [`Self::rainbow()`], but colors each whitespace-delimited word of the message
+    /// with a single color, same as [`Self::gradient_words()`].
+    pub fn rainbow_words<S>(msg: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let msg = msg.into();
+        let words = gradient::words(&msg);
+        let colors = gradient::rainbow(words.len());
+        Self::colored_parts(words, colors)
+    }
+
+    /// Constructs a component with a child for each part of the message,
+    /// colored with the colors from the iterator
+    fn colored_parts(parts: Vec<&str>, colors: impl Iterator<Item = u32>) -> Self {
+        let children = parts
+            .into_iter()
+            .zip(colors)
+            .map(|(part, color)| Component::text(part).color(color))
+            .collect();
+        Self::default().with_extra(children)
+    }
+
+    /// Constructs a component with a child for each character of the message,
+    /// colored with the colors from the iterator
+    fn colored_chars(msg: &str, colors: impl Iterator<Item = u32>) -> Self {
+        let mut df = Self::default();
+        if msg.is_empty() {
+            return df;
+        }
+        df.extra = Some(
+            msg.chars()
+                .zip(colors)
+                .map(|(char, color)| Component::text(char).color(color))
+                .collect(),
+        );
+        df
+    }
+
     /// Constructs a new translatable component.
     pub fn translate<S, C>(msg: S, placeholders: Option<Vec<C>>) -> Self
     where
@@ -272,10 +787,39 @@ impl Component {
                     .map(|e| e.as_component())
                     .collect::<Vec<Component>>()
             }),
+            fallback: None,
         });
         df
     }
 
+    /// Constructs a new translatable component, validating that the translation key
+    /// only contains the characters allowed in a [ResourceLocation].
+    ///
+    /// See [`Self::translate()`] for the unvalidated variant.
+    pub fn try_translate<S, C>(
+        msg: S,
+        placeholders: Option<Vec<C>>,
+    ) -> Result<Self, InvalidIdentifier>
+    where
+        S: Into<String>,
+        C: AsComponent,
+    {
+        let key = msg.into();
+        ResourceLocation::from_str(&key)?;
+        Ok(Self::translate(key, placeholders))
+    }
+
+    /// Sets the text displayed by a translatable component when the client
+    /// lacks its translation key. Only supported by Minecraft 1.19.4 and newer.
+    ///
+    /// Does nothing if this component is not a translatable component.
+    pub fn fallback<S: Into<String>>(&mut self, fallback: S) -> Self {
+        if let MessageContents::Translate(translated) = &mut self.contents {
+            translated.fallback = Some(fallback.into());
+        }
+        self.clone()
+    }
+
     /// Constructs a new scoreboard component.
     pub fn score<S>(name: S, objective: S, placeholder: Option<S>) -> Self
     where
@@ -306,7 +850,9 @@ impl Component {
         df
     }
 
-    /// Constructs a new keybind component
+    /// Constructs a new keybind component.
+    ///
+    /// See [`Self::try_keybind()`] for the variant validating the key.
     pub fn keybind<S: Into<String>>(key: S) -> Self {
         let mut df = Self::default();
         df.contents = MessageContents::Keybind(KeyMessage {
@@ -338,6 +884,25 @@ impl Component {
         df
     }
 
+    /// Constructs a new block nbt data based component, where the block
+    /// is specified by its coordinates, e.g. `0 64 0` or `~ ~-1 ~`
+    pub fn block_nbt<S, C>(path: S, block: S, interpret: Option<bool>, separator: Option<C>) -> Self
+    where
+        S: Into<String>,
+        C: AsComponent,
+    {
+        let mut df = Self::default();
+        df.contents = MessageContents::Nbt(Box::from(NbtMessage {
+            nbt: path.into(),
+            interpret,
+            separator: separator.map(|it| it.as_component()),
+            block: Some(block.into()),
+            entity: None,
+            storage: None,
+        }));
+        df
+    }
+
     /// Constructs a new storage nbt data based component
     pub fn storage_nbt<S, C>(
         path: S,
@@ -361,6 +926,36 @@ impl Component {
         df
     }
 
+    /// Sets the separator of an entity or nbt component to a `", "` text component,
+    /// which is what the vanilla client uses when none is set. The separator is
+    /// displayed between the values when the selector or path matches more than once.
+    ///
+    /// Does nothing if this component is neither an entity nor an nbt component.
+    pub fn with_default_separator(&mut self) -> Self {
+        let separator = Some(Component::text(", "));
+        match &mut self.contents {
+            MessageContents::Entity(entity) => entity.separator = separator,
+            MessageContents::Nbt(nbt) => nbt.separator = separator,
+            _ => {}
+        }
+        self.clone()
+    }
+
+    /// Parses the string as a color in the `#rrggbb` or `#rgb` format, or as a named color,
+    /// and sets it as the color of this component
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Component, TextColor};
+    ///
+    /// let comp = Component::text("Hello").try_color("#ff00aa").unwrap();
+    /// assert_eq!(comp.get_color_opt(), Some(&TextColor::Hex("#ff00aa".into())));
+    /// assert!(Component::text("Hello").try_color("reddish").is_err());
+    /// ```
+    pub fn try_color(&mut self, color: &str) -> Result<Self, ColorParseError> {
+        self.color = Some(TextColor::from_str(color)?);
+        Ok(self.clone())
+    }
+
     /// Adds text that is inserted each time you click this component.
     /// Not connected to [ClickEvent]
     pub fn insert_text<S: Into<String>>(&mut self, text: S) -> Self {
@@ -368,6 +963,40 @@ impl Component {
         self.clone()
     }
 
+    /// Sets the font this component is rendered with, as a resource location
+    /// of a font in the resource pack, e.g. `minecraft:uniform`
+    pub fn font<S: Into<String>>(&mut self, font: S) -> Self {
+        self.font = Some(font.into());
+        self.clone()
+    }
+
+    /// Sets the color of the text shadow of this component, in the `0xAARRGGBB` format
+    pub fn shadow_color(&mut self, argb: u32) -> Self {
+        self.shadow_color = Some(argb);
+        self.clone()
+    }
+
+    /// Sets the shadow color of this component to the one the vanilla client uses by default,
+    /// which is the color of the component with its RGB channels scaled by `0.25`, and full alpha.
+    /// Components without a valid color are treated as white.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let gold = Component::text("Gold").color(NamedColor::Gold).apply_default_shadow();
+    /// assert_eq!(gold.get_shadow_color(), Some(0xFF3F2A00));
+    /// ```
+    pub fn apply_default_shadow(&mut self) -> Self {
+        let rgb = self
+            .color
+            .as_ref()
+            .and_then(TextColor::to_rgb)
+            .unwrap_or(0xFFFFFF);
+        // scaling by 0.25 and rounding down, same as the vanilla client
+        let channel = |shift: u32| (((rgb >> shift) & 0xFF) / 4) << shift;
+        self.shadow_color(0xFF000000 | channel(16) | channel(8) | channel(0))
+    }
+
     /// Adds a click event handler to this component
     pub fn click_event(&mut self, e: ClickEvent) -> Self {
         self.click_event = Some(e);
@@ -385,15 +1014,110 @@ impl Component {
     where
         C: Into<Component>,
     {
-        if let Some(vec) = &mut self.extra {
-            vec.push(comp.into());
-            self.extra = Some(vec.to_owned())
-        } else {
-            self.extra = Some(vec![comp.into()])
+        self.push_extra(comp.into());
+        self.clone()
+    }
+
+    /// Appends a plain text child to this one.
+    pub fn append_str(&mut self, text: &str) -> Self {
+        self.append(Component::text(text))
+    }
+
+    /// Appends the formatted value as a plain text child to this one,
+    /// without having to call [`ToString::to_string()`] on it first.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let mut comp = Component::text("Kills: ").append_display(12);
+    /// assert_eq!(comp.flatten(), "Kills: 12");
+    /// ```
+    pub fn append_display<D: std::fmt::Display>(&mut self, value: D) -> Self {
+        self.append(Component::text(value.to_string()))
+    }
+
+    /// Prepends another component to this one, making it the first child.
+    pub fn prepend<C>(&mut self, comp: C) -> Self
+    where
+        C: Into<Component>,
+    {
+        self.insert_child(0, comp.into());
+        self.clone()
+    }
+
+    /// Appends all the provided components to this one, keeping their order.
+    /// Nothing is changed if there are no components, as empty `extra` lists are invalid.
+    pub fn extend<I>(&mut self, comps: I) -> Self
+    where
+        I: IntoIterator<Item = Component>,
+    {
+        let mut comps = comps.into_iter().peekable();
+        if comps.peek().is_some() {
+            self.extra.get_or_insert_with(Vec::new).extend(comps);
         }
         self.clone()
     }
 
+    /// Sets the children of this component, replacing any existing ones,
+    /// without cloning anything. An empty vector removes all the children.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let line = Component::text("Hello").with_extra(vec![", ".into(), "World!".into()]);
+    /// assert_eq!(line, Component::text("Hello").append(", ").append("World!"));
+    /// ```
+    pub fn with_extra(mut self, children: Vec<Component>) -> Self {
+        self.extra = Some(children).filter(|it| !it.is_empty());
+        self
+    }
+
+    /// Same as [`Self::with_extra()`], but clones the children from a slice.
+    pub fn with_extra_ref(self, children: &[Component]) -> Self {
+        self.with_extra(children.to_vec())
+    }
+
+    /// Makes this component the first child of the provided parent, returning the parent.
+    /// Useful for applying the style of the parent to this component and its new siblings.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let wrapped = Component::text("Hello").wrap_in(Component::text("").color(NamedColor::Red));
+    /// assert_eq!(wrapped, Component::text("").color(NamedColor::Red).append("Hello"));
+    /// ```
+    pub fn wrap_in(self, mut parent: Component) -> Component {
+        parent.insert_child(0, self);
+        parent
+    }
+
+    /// Constructs an empty parent component with `n` clones of this component as its children,
+    /// e.g. for progress bars. Zero repetitions result in an empty component.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let mut bar = Component::colored("|", NamedColor::Green).repeat(3);
+    /// assert_eq!(bar.flatten(), "|||");
+    /// assert!(Component::text("|").repeat(0).is_empty());
+    /// ```
+    pub fn repeat(&self, n: usize) -> Component {
+        Component::default().with_extra(vec![self.clone(); n])
+    }
+
+    /// Pushes a child component without cloning this component afterwards.
+    pub(crate) fn push_extra(&mut self, comp: Component) {
+        self.extra.get_or_insert_with(Vec::new).push(comp)
+    }
+
+    /// Sets the colors of the direct children of this component, taking them from the iterator
+    #[cfg(feature = "minimessage")]
+    pub(crate) fn recolor_children(&mut self, colors: &mut impl Iterator<Item = u32>) {
+        for (child, color) in self.extra.iter_mut().flatten().zip(colors) {
+            child.color = Some(color.into());
+        }
+    }
+
     /// Appends another component to the last child component.
     ///
     /// Imagine this structure:
@@ -416,35 +1140,184 @@ impl Component {
     ///     --- component baz:
     ///         --- component quz
     /// ```
+    /// The descent stops at the first component without children, which receives
+    /// the appended component as its first child. Components that are not literal
+    /// text (e.g. translatable or keybind components) are never descended into,
+    /// the appended component becomes their sibling instead.
     pub fn append_to_last_child(&mut self, comp: Component) -> Self {
-        if let Some(vec) = &mut self.extra {
-            let mut last = vec.pop().unwrap();
-            last = last.append_to_last_child(comp);
-            vec.push(last);
-            self.clone()
-        } else {
-            self.extra = Some(vec![comp]);
-            self.clone()
+        self.push_to_last_child(comp);
+        self.clone()
+    }
+
+    fn push_to_last_child(&mut self, comp: Component) {
+        match self.extra.as_mut().and_then(|extra| extra.last_mut()) {
+            Some(last) if matches!(last.contents, MessageContents::Plain { .. }) => {
+                last.push_to_last_child(comp)
+            }
+            _ => self.push_extra(comp),
         }
     }
 
-    /// Gets the current color of this component, or white if it is not assigned.
-    pub fn get_color(&mut self) -> TextColor {
+    /// Inserts a child component at the provided index, shifting all children after it.
+    /// Indices past the end of the children are clamped, appending the child instead.
+    pub fn insert_child(&mut self, index: usize, child: Component) {
+        let extra = self.extra.get_or_insert_with(Vec::new);
+        extra.insert(index.min(extra.len()), child);
+    }
+
+    /// Inserts a child component right after the first direct child matching the predicate,
+    /// returning whether any child matched. Nothing is inserted if no child matches.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let mut comp = Component::text("").append("a").append("c");
+    /// assert!(comp.insert_after_match(|it| it.get_text_content().as_deref() == Some("a"), "b".into()));
+    /// assert_eq!(comp.flatten(), "abc");
+    /// ```
+    pub fn insert_after_match<F>(&mut self, predicate: F, child: Component) -> bool
+    where
+        F: Fn(&Component) -> bool,
+    {
+        match self.children().position(predicate) {
+            Some(index) => {
+                self.insert_child(index + 1, child);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Same as [`Self::insert_after_match()`], but inserts the child right before the match
+    pub fn insert_before_match<F>(&mut self, predicate: F, child: Component) -> bool
+    where
+        F: Fn(&Component) -> bool,
+    {
+        match self.children().position(predicate) {
+            Some(index) => {
+                self.insert_child(index, child);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the child component at the provided index, shifting all children after it.
+    /// Returns [None] if there is no child at this index.
+    pub fn remove_child(&mut self, index: usize) -> Option<Component> {
+        let extra = self.extra.as_mut()?;
+        if index >= extra.len() {
+            return None;
+        }
+        let removed = extra.remove(index);
+        if extra.is_empty() {
+            self.extra = None;
+        }
+        Some(removed)
+    }
+
+    /// Removes all the child components of this component.
+    pub fn clear_children(&mut self) {
+        self.extra = None;
+    }
+
+    /// Unsets the color of this component, so that it is inherited from the parent again
+    pub fn clear_color(&mut self) {
+        self.color = None;
+    }
+
+    /// Gets the current color of this component, or white if it is not assigned.
+    ///
+    /// Note that this does not take the parent components into account, see
+    /// [`Self::get_color_inherited()`] to get the color this component is actually rendered with.
+    pub fn get_color(&mut self) -> TextColor {
         match &self.color {
             None => TextColor::Named(NamedColor::White),
             Some(color) => color.to_owned(),
         }
     }
 
+    /// Gets the effective color of this component inside a component tree.
+    /// Falls back to the color inherited from the parent component if this component
+    /// has no color assigned, and to white if neither of them is assigned.
+    pub fn get_color_inherited(&self, parent_color: Option<&TextColor>) -> TextColor {
+        match self.color.as_ref().or(parent_color) {
+            None => TextColor::Named(NamedColor::White),
+            Some(color) => color.to_owned(),
+        }
+    }
+
+    /// Gets the color assigned to this component, or [None] if it is not assigned.
+    ///
+    /// Unlike [`Self::get_color()`], this does not fall back to white.
+    pub fn get_color_opt(&self) -> Option<&TextColor> {
+        self.color.as_ref()
+    }
+
+    /// Gets the font of this component, if it sets one
+    pub fn get_font(&self) -> Option<&str> {
+        self.font.as_deref()
+    }
+
+    /// Gets the shadow color of this component in the `0xAARRGGBB` format, if it sets one
+    pub fn get_shadow_color(&self) -> Option<u32> {
+        self.shadow_color
+    }
+
+    /// Gets the click event handler of this component
+    pub fn get_click_event(&self) -> Option<&ClickEvent> {
+        self.click_event.as_ref()
+    }
+
+    /// Gets the hover event handler of this component
+    pub fn get_hover_event(&self) -> Option<&HoverEvent> {
+        self.hover_event.as_ref()
+    }
+
+    /// Gets the text that is inserted each time you click this component
+    pub fn get_insertion(&self) -> Option<&str> {
+        self.insertion.as_deref()
+    }
+
     /// Attempts to get text contents of this component.
     /// Returns [None] if this component is not a Literal Text Component
-    pub fn get_text_content(&mut self) -> Option<String> {
+    pub fn get_text_content(&self) -> Option<String> {
         match &self.contents {
             MessageContents::Plain { text } => Some(text.clone()),
             _ => None,
         }
     }
 
+    /// Gets the kind of the contents of this component
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Component, ComponentKind};
+    ///
+    /// assert_eq!(Component::text("Hello").kind(), ComponentKind::Text);
+    /// assert_eq!(Component::keybind("key.jump").kind(), ComponentKind::Keybind);
+    /// ```
+    pub fn kind(&self) -> ComponentKind {
+        match &self.contents {
+            MessageContents::Plain { .. } => ComponentKind::Text,
+            MessageContents::Translate(_) => ComponentKind::Translate,
+            MessageContents::Score { .. } => ComponentKind::Score,
+            MessageContents::Entity(_) => ComponentKind::Entity,
+            MessageContents::Keybind(_) => ComponentKind::Keybind,
+            MessageContents::Nbt(_) => ComponentKind::Nbt,
+        }
+    }
+
+    /// Checks whether this component is a literal text component, regardless of its style
+    /// and children. See [`Self::as_plain_if_unstyled()`] to also check that it is unstyled.
+    pub fn is_plain(&self) -> bool {
+        self.kind() == ComponentKind::Text
+    }
+
+    /// Checks whether this component is a translatable component
+    pub fn is_translatable(&self) -> bool {
+        self.kind() == ComponentKind::Translate
+    }
+
     _fmt_impl! {
         bold(get_bold), italic(get_italic), obfuscated(get_obfuscated), strikethrough(get_strikethrough), underlined(get_underlined), reset(get_reset),
     }
@@ -463,6 +1336,35 @@ impl Component {
         }
     }
 
+    /// Unsets the formatting of this component, so that it is inherited from the parent again
+    /// instead of being explicitly enabled or disabled
+    pub fn clear_formatting(&mut self, format: Formatting) {
+        *self.formatting_mut(format) = None;
+    }
+
+    /// Gets the field of this component storing the formatting
+    fn formatting_mut(&mut self, format: Formatting) -> &mut Option<bool> {
+        match format {
+            Formatting::Obfuscated => &mut self.obfuscated,
+            Formatting::Bold => &mut self.bold,
+            Formatting::Strikethrough => &mut self.strikethrough,
+            Formatting::Underline => &mut self.underlined,
+            Formatting::Italic => &mut self.italic,
+            Formatting::Reset => &mut self.reset,
+        }
+    }
+
+    /// Unsets all the formatting of this component, see [`Self::clear_formatting()`].
+    /// Unlike [`Self::strip_formatting()`], the color, events and children are kept.
+    pub fn clear_all_formatting(&mut self) {
+        self.obfuscated = None;
+        self.bold = None;
+        self.strikethrough = None;
+        self.underlined = None;
+        self.italic = None;
+        self.reset = None;
+    }
+
     /// Gets whether the specific formatting is enabled in this component
     pub fn get_formatting(&self, format: Formatting) -> bool {
         match format {
@@ -475,6 +1377,550 @@ impl Component {
         }
     }
 
+    /// Sets the color of this component and all of its children recursively,
+    /// overriding any colors they had. Unlike [`Colored::color()`], which only
+    /// colors this component, children can not keep their own color.
+    ///
+    /// The contents of hover events are not recolored, see [`Self::paint_with_hover()`].
+    pub fn paint<C: Into<TextColor>>(&mut self, color: C) -> Self {
+        self.paint_in_place(&color.into(), false);
+        self.clone()
+    }
+
+    /// Same as [`Self::paint()`], but also recolors the contents of `show_text` hover events
+    pub fn paint_with_hover<C: Into<TextColor>>(&mut self, color: C) -> Self {
+        self.paint_in_place(&color.into(), true);
+        self.clone()
+    }
+
+    fn paint_in_place(&mut self, color: &TextColor, hover: bool) {
+        self.color = Some(color.clone());
+        if hover {
+            if let Some(HoverEvent::ShowText { contents }) = &mut self.hover_event {
+                contents.paint_in_place(color, hover);
+            }
+        }
+        for child in self.extra.iter_mut().flatten() {
+            child.paint_in_place(color, hover);
+        }
+    }
+
+    /// Enables or disables the formatting in this component and all of its children
+    /// recursively, overriding the values the children set themselves.
+    ///
+    /// The contents of hover events are left as they are, same as in [`Self::paint()`].
+    pub fn apply_formatting_recursive(&mut self, format: Formatting, enable: bool) {
+        *self.formatting_mut(format) = Some(enable);
+        for child in self.extra.iter_mut().flatten() {
+            child.apply_formatting_recursive(format, enable);
+        }
+    }
+
+    /// Merges the style into this component and all of its children recursively.
+    /// Every field set in the style overrides the values the components set themselves,
+    /// while the fields that are not set are left untouched.
+    ///
+    /// The contents of hover events are left as they are, same as in [`Self::paint()`].
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Component, NamedColor, Style, TextColor};
+    ///
+    /// let mut comp = Component::text("Hello").italic(true).append("World");
+    /// comp.apply_style_recursive(&Style {
+    ///     color: Some(NamedColor::Red.into()),
+    ///     ..Default::default()
+    /// });
+    /// assert!(comp.descendants().all(|it| it.get_color_opt() == Some(&TextColor::Named(NamedColor::Red))));
+    /// assert!(comp.get_italic());
+    /// ```
+    pub fn apply_style_recursive(&mut self, style: &Style) {
+        style.merge_into(self);
+        for child in self.extra.iter_mut().flatten() {
+            child.apply_style_recursive(style);
+        }
+    }
+
+    /// Moves the styles shared by all the children of a component to the component itself,
+    /// recursively, so that the children inherit them instead of setting them each, which
+    /// makes the serialized component smaller. Styles that are set to the same value as
+    /// in the parent are removed from the children as well.
+    ///
+    /// A style is only moved to a component without any contents of its own, such as an
+    /// empty text component, so the component is always displayed the same way as before.
+    /// Only the color, formatting, font and shadow color are moved, but not the events.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let comp = Component::text("")
+    ///     .append(Component::colored("a", NamedColor::Red))
+    ///     .append(Component::colored("b", NamedColor::Red));
+    /// let hoisted = comp.hoist_common_styles();
+    /// assert_eq!(hoisted, Component::colored("", NamedColor::Red).append("a").append("b"));
+    /// ```
+    pub fn hoist_common_styles(&self) -> Component {
+        let mut comp = self.clone();
+        comp.hoist_styles_in_place();
+        comp
+    }
+
+    fn hoist_styles_in_place(&mut self) {
+        let Some(children) = &mut self.extra else {
+            return;
+        };
+        for child in children.iter_mut() {
+            child.hoist_styles_in_place();
+        }
+        let empty = matches!(&self.contents, MessageContents::Plain { text } if text.is_empty());
+        self.hoist_style(empty, |comp| &mut comp.color);
+        self.hoist_style(empty, |comp| &mut comp.bold);
+        self.hoist_style(empty, |comp| &mut comp.italic);
+        self.hoist_style(empty, |comp| &mut comp.obfuscated);
+        self.hoist_style(empty, |comp| &mut comp.strikethrough);
+        self.hoist_style(empty, |comp| &mut comp.underlined);
+        self.hoist_style(empty, |comp| &mut comp.font);
+        self.hoist_style(empty, |comp| &mut comp.shadow_color);
+    }
+
+    /// Moves a single style field shared by all the children to this component,
+    /// where `empty` is whether this component has no contents of its own
+    fn hoist_style<T, F>(&mut self, empty: bool, field: F)
+    where
+        T: Clone + PartialEq,
+        F: Fn(&mut Component) -> &mut Option<T>,
+    {
+        let common = match field(self).clone() {
+            Some(value) => value,
+            None if empty => {
+                let mut values = self
+                    .extra
+                    .iter_mut()
+                    .flatten()
+                    .map(|child| field(child).clone());
+                let Some(Some(first)) = values.next() else {
+                    return;
+                };
+                if !values.all(|value| value.as_ref() == Some(&first)) {
+                    return;
+                }
+                first
+            }
+            None => return,
+        };
+        for child in self.extra.iter_mut().flatten() {
+            let value = field(child);
+            if value.as_ref() == Some(&common) {
+                *value = None;
+            }
+        }
+        *field(self) = Some(common);
+    }
+
+    /// Finds the first component in this tree matching the predicate.
+    ///
+    /// The tree is traversed depth-first in pre-order, meaning that a component is checked
+    /// before its children, and the children are checked in order. This component itself
+    /// is checked first, while the contents of hover events are not checked at all.
+    pub fn find_first<F>(&self, pred: F) -> Option<&Component>
+    where
+        F: Fn(&Component) -> bool,
+    {
+        self.find_first_by(&pred)
+    }
+
+    fn find_first_by<F>(&self, pred: &F) -> Option<&Component>
+    where
+        F: Fn(&Component) -> bool,
+    {
+        if pred(self) {
+            return Some(self);
+        }
+        self.extra
+            .iter()
+            .flatten()
+            .find_map(|child| child.find_first_by(pred))
+    }
+
+    /// Finds all the components in this tree matching the predicate, in the
+    /// same order as they are traversed by [`Self::find_first()`]
+    pub fn find_all<F>(&self, pred: F) -> Vec<&Component>
+    where
+        F: Fn(&Component) -> bool,
+    {
+        let mut found = Vec::new();
+        self.find_all_by(&pred, &mut found);
+        found
+    }
+
+    fn find_all_by<'a, F>(&'a self, pred: &F, found: &mut Vec<&'a Component>)
+    where
+        F: Fn(&Component) -> bool,
+    {
+        if pred(self) {
+            found.push(self);
+        }
+        for child in self.extra.iter().flatten() {
+            child.find_all_by(pred, found);
+        }
+    }
+
+    /// Collects all the colors set on the components of this tree, as they are written.
+    /// The contents of hover events are not checked, same as in [`Self::find_all()`].
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor, TextColor};
+    ///
+    /// let comp = Component::text("a")
+    ///     .color(NamedColor::Red)
+    ///     .append(Component::text("b").color(0x00FF00));
+    /// let colors = comp.colors_used();
+    /// assert_eq!(colors.len(), 2);
+    /// assert!(colors.contains(&TextColor::Named(NamedColor::Red)));
+    /// ```
+    pub fn colors_used(&self) -> HashSet<TextColor> {
+        self.find_all(|comp| comp.color.is_some())
+            .into_iter()
+            .filter_map(|comp| comp.color.clone())
+            .collect()
+    }
+
+    /// Collects the colors the text of this tree is actually displayed with,
+    /// resolving the colors inherited from the parents like [`Self::runs()`] does.
+    /// Colors of components without any text, or overridden by all of their children, are
+    /// not included, while text that is not colored at all is not represented either.
+    pub fn colors_used_inherited(&self) -> HashSet<TextColor> {
+        self.runs().filter_map(|(_, style)| style.color).collect()
+    }
+
+    /// Checks whether the color is set on any component of this tree.
+    ///
+    /// With `by_rgb`, colors are compared by their RGB value instead of how they are written,
+    /// so that e.g. [`NamedColor::Red`] and `#ff5555` are the same color.
+    /// Invalid hex colors are always compared as they are written.
+    pub fn uses_color(&self, color: &TextColor, by_rgb: bool) -> bool {
+        let rgb = color.to_rgb().filter(|_| by_rgb);
+        self.find_first(|comp| match &comp.color {
+            Some(own) if rgb.is_some() => own.to_rgb() == rgb,
+            Some(own) => own == color,
+            None => false,
+        })
+        .is_some()
+    }
+
+    /// Creates a copy of this component with all colors, formatting and events
+    /// removed from every node of the tree, including the arguments of translatable
+    /// contents and the separators of entity and NBT contents. Unlike [`Self::flatten()`],
+    /// the text structure of the component is kept.
+    pub fn strip_formatting(&self) -> Component {
+        let mut contents = self.contents.clone();
+        for nested in contents.components_mut() {
+            *nested = nested.strip_formatting();
+        }
+        Component {
+            extra: self
+                .extra
+                .as_ref()
+                .map(|extra| extra.iter().map(Component::strip_formatting).collect()),
+            contents,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a copy of this component with the colors removed from every node of the tree,
+    /// keeping the formatting, events and everything else, e.g. for a monochrome mode.
+    ///
+    /// The contents of hover events keep their colors, see [`Self::strip_color_with_hover()`].
+    pub fn strip_color(&self) -> Component {
+        let mut stripped = self.clone();
+        stripped.strip_color_in_place(false);
+        stripped
+    }
+
+    /// Same as [`Self::strip_color()`], but also removes the colors from the contents
+    /// of `show_text` hover events
+    pub fn strip_color_with_hover(&self) -> Component {
+        let mut stripped = self.clone();
+        stripped.strip_color_in_place(true);
+        stripped
+    }
+
+    fn strip_color_in_place(&mut self, hover: bool) {
+        self.color = None;
+        if hover {
+            if let Some(HoverEvent::ShowText { contents }) = &mut self.hover_event {
+                contents.strip_color_in_place(hover);
+            }
+        }
+        for child in self.extra.iter_mut().flatten() {
+            child.strip_color_in_place(hover);
+        }
+    }
+
+    /// Copies this component, removing the click and hover events of it and all
+    /// of its children, while keeping their text, colors and formatting.
+    ///
+    /// Useful for relaying components written by untrusted players.
+    /// See [`Self::strip_click_events_where()`] to only remove some click events.
+    pub fn strip_events(&self) -> Component {
+        let mut stripped = self.clone();
+        stripped.strip_events_in_place(&|_| true, true);
+        stripped
+    }
+
+    /// Copies this component, removing the click events matching the predicate from it and all
+    /// of its children, including the text of `show_text` hover events. Other events are kept.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{ClickEvent, Component};
+    ///
+    /// let comp = Component::text("Click")
+    ///     .click_event(ClickEvent::run_command("/op Notch"))
+    ///     .append(Component::text("Link").click_event(ClickEvent::open_url("https://example.com")));
+    /// let safe = comp.strip_click_events_where(|e| matches!(e, ClickEvent::RunCommand(_)));
+    /// assert_eq!(safe.get_click_event(), None);
+    /// assert!(safe.find_first(|it| it.get_click_event().is_some()).is_some());
+    /// ```
+    pub fn strip_click_events_where<F>(&self, predicate: F) -> Component
+    where
+        F: Fn(&ClickEvent) -> bool,
+    {
+        let mut stripped = self.clone();
+        stripped.strip_events_in_place(&predicate, false);
+        stripped
+    }
+
+    fn strip_events_in_place(&mut self, predicate: &dyn Fn(&ClickEvent) -> bool, hover: bool) {
+        if self.click_event.as_ref().is_some_and(predicate) {
+            self.click_event = None;
+        }
+        if hover {
+            self.hover_event = None;
+        } else if let Some(HoverEvent::ShowText { contents }) = &mut self.hover_event {
+            contents.strip_events_in_place(predicate, hover);
+        }
+        for child in self.extra.iter_mut().flatten() {
+            child.strip_events_in_place(predicate, hover);
+        }
+    }
+
+    /// Checks whether this component has no visible contents, meaning that
+    /// both it and all of its children are literal text components with empty text
+    pub fn is_empty(&self) -> bool {
+        matches!(&self.contents, MessageContents::Plain { text } if text.is_empty())
+            && self.extra.iter().flatten().all(Component::is_empty)
+    }
+
+    /// Counts the leaves of this component tree, which are the components without any children.
+    /// The contents of hover events are not counted.
+    pub fn count_leaves(&self) -> usize {
+        match &self.extra {
+            Some(extra) if !extra.is_empty() => extra.iter().map(Component::count_leaves).sum(),
+            _ => 1,
+        }
+    }
+
+    /// Gets the maximum nesting depth of this component tree, where a component
+    /// without children has a depth of 1. The text of `show_text` hover events
+    /// counts as nested in the component it is shown for.
+    pub fn depth(&self) -> usize {
+        let children = self.extra.iter().flatten().map(Component::depth).max();
+        let hover = match &self.hover_event {
+            Some(HoverEvent::ShowText { contents }) => Some(contents.depth()),
+            _ => None,
+        };
+        1 + children.max(hover).unwrap_or(0)
+    }
+
+    /// Shortens this component so that its text is at most `max_chars` characters long,
+    /// including the ellipsis, which is appended only if anything was cut off.
+    ///
+    /// The text is kept in display order, and the kept parts retain their styling.
+    /// The ellipsis is styled the same way as the last kept text. Non-text contents
+    /// are measured by their approximation in [`Self::flatten()`], and are either kept
+    /// whole or dropped. Lengths are counted in characters, so no character is ever split.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let short = Component::text("Hello, ").append("World!").truncate(10, "...");
+    /// assert_eq!(short, Component::text("Hello, ..."));
+    /// ```
+    pub fn truncate(&self, max_chars: usize, ellipsis: &str) -> Component {
+        let len = self
+            .runs()
+            .map(|(text, _)| text.chars().count())
+            .sum::<usize>();
+        if len <= max_chars {
+            return self.clone();
+        }
+        // the ellipsis itself is cut off if it does not fit
+        let ellipsis = ellipsis.chars().take(max_chars).collect::<String>();
+        let mut budget = max_chars - ellipsis.chars().count();
+        let mut truncated = self.truncated(&mut budget);
+        // the last node in display order is the one the ellipsis follows
+        let mut last = &mut truncated;
+        while last.extra.as_ref().is_some_and(|extra| !extra.is_empty()) {
+            last = last.extra.as_mut().unwrap().last_mut().unwrap();
+        }
+        match &mut last.contents {
+            MessageContents::Plain { text } => text.push_str(&ellipsis),
+            _ => last.push_extra(Component::text(ellipsis)),
+        }
+        truncated
+    }
+
+    /// Copies this component, keeping only as much text as the budget allows
+    fn truncated(&self, budget: &mut usize) -> Component {
+        let mut copy = Component {
+            extra: None,
+            bold: self.bold,
+            italic: self.italic,
+            obfuscated: self.obfuscated,
+            strikethrough: self.strikethrough,
+            underlined: self.underlined,
+            reset: self.reset,
+            color: self.color.clone(),
+            font: self.font.clone(),
+            shadow_color: self.shadow_color,
+            contents: self.contents.clone(),
+            insertion: self.insertion.clone(),
+            click_event: self.click_event.clone(),
+            hover_event: self.hover_event.clone(),
+        };
+        match &self.contents {
+            MessageContents::Plain { text } => {
+                let kept = text.chars().take(*budget).collect::<String>();
+                *budget -= kept.chars().count();
+                copy.contents = MessageContents::Plain { text: kept };
+            }
+            other => {
+                let len = other.flatten().chars().count();
+                match len <= *budget {
+                    true => *budget -= len,
+                    false => {
+                        *budget = 0;
+                        copy.contents = MessageContents::default();
+                    }
+                }
+            }
+        }
+        for child in self.extra.iter().flatten() {
+            if *budget == 0 {
+                break;
+            }
+            copy.push_extra(child.truncated(budget));
+        }
+        copy
+    }
+
+    /// Gets the text of this component if it is a literal text component without any style,
+    /// events or children, which can be sent as a bare JSON string instead of an object
+    pub fn as_plain_if_unstyled(&self) -> Option<&str> {
+        // destructured, so that any style added later has to be checked here as well
+        let Component {
+            extra,
+            bold,
+            italic,
+            obfuscated,
+            strikethrough,
+            underlined,
+            reset,
+            color,
+            font,
+            shadow_color,
+            contents,
+            insertion,
+            click_event,
+            hover_event,
+        } = self;
+        let formatted = [bold, italic, obfuscated, strikethrough, underlined, reset]
+            .iter()
+            .any(|it| it.is_some());
+        let styled = formatted
+            || color.is_some()
+            || font.is_some()
+            || shadow_color.is_some()
+            || insertion.is_some()
+            || click_event.is_some()
+            || hover_event.is_some()
+            || extra.as_ref().is_some_and(|extra| !extra.is_empty());
+        match contents {
+            MessageContents::Plain { text } if !styled => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Serializes this component as JSON in the smallest form the client accepts.
+    ///
+    /// Unlike [`ToString::to_string()`], unstyled literal text components are written as bare
+    /// JSON strings, see [`Self::as_plain_if_unstyled()`]. The same goes for the children,
+    /// the arguments of translatable components and the text of `show_text` hover events.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let comp = Component::text("Hello, ").bold(true).append("World!");
+    /// assert_eq!(comp.to_json_minimal(), r#"{"bold":true,"extra":["World!"],"text":"Hello, "}"#);
+    /// assert_eq!(Component::text("Hi").to_json_minimal(), r#""Hi""#);
+    /// ```
+    pub fn to_json_minimal(&self) -> String {
+        self.to_minimal_value().to_string()
+    }
+
+    fn to_minimal_value(&self) -> Value {
+        if let Some(text) = self.as_plain_if_unstyled() {
+            return Value::String(text.to_string());
+        }
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        let Some(fields) = value.as_object_mut() else {
+            return value;
+        };
+        if let Some(extra) = &self.extra {
+            let extra = extra.iter().map(Component::to_minimal_value).collect();
+            fields.insert("extra".into(), Value::Array(extra));
+        }
+        if let MessageContents::Translate(TranslatedMessage {
+            with: Some(with), ..
+        }) = &self.contents
+        {
+            let with = with.iter().map(Component::to_minimal_value).collect();
+            fields.insert("with".into(), Value::Array(with));
+        }
+        if let Some(HoverEvent::ShowText { contents }) = &self.hover_event {
+            if let Some(hover) = fields.get_mut("hoverEvent").and_then(Value::as_object_mut) {
+                hover.insert("contents".into(), contents.to_minimal_value());
+            }
+        }
+        value
+    }
+
+    /// Serializes this component as JSON directly into the writer, without
+    /// allocating an intermediate string like [`ToString::to_string()`] does
+    pub fn to_writer<W: std::io::Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Deserializes a component from JSON read from the reader
+    pub fn from_reader<R: std::io::Read>(reader: R) -> serde_json::Result<Component> {
+        serde_json::from_reader(reader)
+    }
+
+    /// Parses a minimessage string read from the reader, failing on invalid tags like
+    /// [`FromStr`] does. See [`crate::message::MiniMessageParser::parse_reader()`] for more info.
+    #[cfg(feature = "minimessage")]
+    pub fn parse_minimessage_reader<R: std::io::BufRead>(
+        reader: R,
+    ) -> Result<Component, crate::message::ParseError> {
+        crate::message::MiniMessageParser::new()
+            .strict(true)
+            .parse_reader(reader)
+    }
+
+    /// Deserializes a component from a JSON string
+    pub fn from_json(json: &str) -> serde_json::Result<Component> {
+        serde_json::from_str(json)
+    }
+
     /// Flattens this component, getting the *approximate* contents of it
     pub fn flatten(&mut self) -> String {
         let mut buf = self.contents.flatten();
@@ -487,10 +1933,45 @@ impl Component {
 
         buf
     }
+
+    /// Flattens this component like [`Self::flatten()`], but omits the text rendered
+    /// with any of the [`DEFAULT_HIDDEN_FONTS`], which would only show up as
+    /// unreadable characters in plain text, e.g. in server logs.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let comp = Component::text("Secret: ").append(Component::text("hidden").font("alt"));
+    /// assert_eq!(comp.flatten_visible(), "Secret: ");
+    /// ```
+    pub fn flatten_visible(&self) -> String {
+        self.flatten_visible_with(DEFAULT_HIDDEN_FONTS)
+    }
+
+    /// Same as [`Self::flatten_visible()`], but omits the text rendered with any of the
+    /// provided fonts instead, e.g. the icon fonts of a custom resource pack.
+    /// Fonts without a namespace are in the `minecraft` namespace, as usual.
+    pub fn flatten_visible_with(&self, hidden_fonts: &[&str]) -> String {
+        let hidden = |font: &str| {
+            let font = ResourceLocation::from_str(font);
+            hidden_fonts
+                .iter()
+                .any(|hidden| ResourceLocation::from_str(hidden) == font)
+        };
+        self.runs()
+            .filter(|(_, style)| !style.font.as_deref().is_some_and(hidden))
+            .map(|(text, _)| text)
+            .collect()
+    }
 }
 
+/// Fonts omitted by [`Component::flatten_visible()`]. These are the vanilla fonts that
+/// replace the letters with glyphs of made-up alphabets: `minecraft:alt`, used by the
+/// enchanting table, and `minecraft:illageralt`, used by illager banners.
+pub const DEFAULT_HIDDEN_FONTS: &[&str] = &["minecraft:alt", "minecraft:illageralt"];
+
 /// Type of formatting for component
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub enum Formatting {
     /// Renders component as obfuscated
     Obfuscated,
@@ -513,19 +1994,67 @@ impl FromStr for Formatting {
         use Formatting::*;
 
         Ok(match s {
-            "obfuscated" => Obfuscated,
-            "bold" => Bold,
-            "strikethrough" => Strikethrough,
-            "underline" => Underline,
-            "italic" => Italic,
+            // `magic` is used by Bedrock and some plugins
+            "obfuscated" | "obf" | "magic" => Obfuscated,
+            "bold" | "b" => Bold,
+            "strikethrough" | "st" => Strikethrough,
+            "underline" | "u" => Underline,
+            "italic" | "i" => Italic,
             "reset" => Reset,
             _ => return Err(()),
         })
     }
 }
 
+impl Formatting {
+    /// Gets the formatting for a legacy formatting code (`k`, `l`, `m`, `n`, `o`, `r`).
+    /// Uppercase codes are accepted as well.
+    pub fn from_legacy_code(code: char) -> Option<Formatting> {
+        use Formatting::*;
+        Some(match code.to_ascii_lowercase() {
+            'k' => Obfuscated,
+            'l' => Bold,
+            'm' => Strikethrough,
+            'n' => Underline,
+            'o' => Italic,
+            'r' => Reset,
+            _ => return None,
+        })
+    }
+
+    /// Gets the legacy formatting code of this formatting
+    pub fn to_legacy_code(&self) -> char {
+        use Formatting::*;
+        match self {
+            Obfuscated => 'k',
+            Bold => 'l',
+            Strikethrough => 'm',
+            Underline => 'n',
+            Italic => 'o',
+            Reset => 'r',
+        }
+    }
+}
+
+/// Kind of the contents of a component, see [`Component::kind()`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    /// Literal text
+    Text,
+    /// Translatable text
+    Translate,
+    /// Score of a scoreboard objective
+    Score,
+    /// Names of the entities matching a selector
+    Entity,
+    /// Key bound to a keybind
+    Keybind,
+    /// NBT data of a block, an entity or a storage
+    Nbt,
+}
+
 /// Container for inner contents of a component
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 #[serde(untagged)]
 pub enum MessageContents {
     /// Literal text
@@ -559,20 +2088,37 @@ impl Default for MessageContents {
 impl MessageContents {
     /// Flattens this component, acquiring it's inner data
     pub fn flatten(&self) -> String {
+        self.flatten_str().to_string()
+    }
+
+    /// Same as [`Self::flatten()`], but borrows the data instead of copying it
+    pub(crate) fn flatten_str(&self) -> &str {
         match self {
-            MessageContents::Plain { text } => text.clone(),
-            MessageContents::Translate(translated) => translated.translate.clone(),
-            MessageContents::Score { .. } => "<scoreboard message>".into(),
-            MessageContents::Entity(_) => "<entity message>".into(),
-            MessageContents::Keybind(key) => key.keybind.clone(),
-            MessageContents::Nbt(_) => "<nbt message>".into(),
+            MessageContents::Plain { text } => text,
+            MessageContents::Translate(translated) => &translated.translate,
+            MessageContents::Score { .. } => "<scoreboard message>",
+            MessageContents::Entity(_) => "<entity message>",
+            MessageContents::Keybind(key) => &key.keybind,
+            MessageContents::Nbt(_) => "<nbt message>",
         }
     }
+
+    /// Gets the components nested in these contents, which are the arguments
+    /// of translatable contents and the separators of entity and NBT contents
+    pub(crate) fn components_mut(&mut self) -> impl Iterator<Item = &mut Component> {
+        let (args, separator) = match self {
+            MessageContents::Translate(translated) => (translated.with.as_deref_mut(), None),
+            MessageContents::Entity(entity) => (None, entity.separator.as_mut()),
+            MessageContents::Nbt(nbt) => (None, nbt.separator.as_mut()),
+            _ => (None, None),
+        };
+        args.into_iter().flatten().chain(separator)
+    }
 }
 
 /// NBT based message
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 pub struct NbtMessage {
     nbt: String,
     interpret: Option<bool>,
@@ -583,14 +2129,14 @@ pub struct NbtMessage {
 }
 
 /// Keybind based message
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 pub struct KeyMessage {
     keybind: String,
 }
 
 /// Entity based message
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 pub struct EntityMessage {
     selector: String,
     separator: Option<Component>,
@@ -598,15 +2144,16 @@ pub struct EntityMessage {
 
 /// Translatable message
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 pub struct TranslatedMessage {
     translate: String,
     with: Option<Vec<Component>>,
+    fallback: Option<String>,
 }
 
 /// Scoreboard message
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash)]
 pub struct ScoreboardMessage {
     name: String,
     objective: String,
@@ -614,7 +2161,11 @@ pub struct ScoreboardMessage {
 }
 
 /// A text color formatting
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+///
+/// Colors are deserialized from either a snake_case named color, a `#rrggbb` or `#rgb` hex color,
+/// a single legacy color code like `"c"` or an RGB integer, as written by some older tools.
+/// Anything else fails the deserialization.
+#[derive(Debug, Clone, Serialize, PartialEq, PartialOrd, Eq, Hash)]
 #[serde(untagged)]
 pub enum TextColor {
     /// A named color
@@ -623,6 +2174,198 @@ pub enum TextColor {
     Hex(String),
 }
 
+impl From<NamedColor> for TextColor {
+    fn from(color: NamedColor) -> Self {
+        TextColor::Named(color)
+    }
+}
+
+/// Converts an `0xRRGGBB` value into a `#RRGGBB` hex color.
+/// Bits above the lower 24 are ignored, e.g. the alpha of an `0xAARRGGBB` value.
+impl From<u32> for TextColor {
+    fn from(color: u32) -> Self {
+        TextColor::Hex(format!("#{:06X}", color & 0xFFFFFF))
+    }
+}
+
+/// Converts a `#rrggbb` string or a snake_case named color into a [TextColor].
+/// Any string that is not a named color is kept as a hex color, use [`str::parse`]
+/// to validate it instead.
+impl From<&str> for TextColor {
+    fn from(color: &str) -> Self {
+        match NamedColor::from_str(color) {
+            Ok(named) => TextColor::Named(named),
+            Err(_) => TextColor::Hex(color.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TextColor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawColor {
+            Text(String),
+            Rgb(u32),
+        }
+
+        match RawColor::deserialize(deserializer)? {
+            RawColor::Rgb(rgb) if rgb <= 0xFFFFFF => Ok(TextColor::from(rgb)),
+            RawColor::Rgb(rgb) => Err(D::Error::custom(format!(
+                "Invalid color {}, expected an RGB value up to 0xFFFFFF",
+                rgb
+            ))),
+            RawColor::Text(text) => {
+                let mut chars = text.chars();
+                if let (Some(code), None) = (chars.next(), chars.next()) {
+                    if let Some(named) = NamedColor::from_legacy_code(code) {
+                        return Ok(TextColor::Named(named));
+                    }
+                }
+                TextColor::from_str(&text).map_err(D::Error::custom)
+            }
+        }
+    }
+}
+
+impl TextColor {
+    /// Gets the RGB value of this color, using the vanilla palette for named colors.
+    /// Returns [None] if this is not a valid `#rrggbb` hex color.
+    pub fn to_rgb(&self) -> Option<u32> {
+        match self {
+            TextColor::Named(named) => Some(named.to_rgb()),
+            TextColor::Hex(hex) => hex
+                .strip_prefix('#')
+                .filter(|digits| digits.len() == 6)
+                .and_then(|digits| u32::from_str_radix(digits, 16).ok()),
+        }
+    }
+
+    /// Mixes this color with the other one in RGB, `t` being the fraction of the other color
+    /// in range of `0.0..=1.0`. Values outside of the range are clamped.
+    ///
+    /// Invalid hex colors are treated as white, the default text color.
+    /// The result is always a hex color.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{NamedColor, TextColor};
+    ///
+    /// let black = TextColor::Named(NamedColor::Black);
+    /// let white = TextColor::Named(NamedColor::White);
+    /// assert_eq!(black.blend(&white, 0.5), TextColor::from(0x808080));
+    /// ```
+    pub fn blend(&self, other: &TextColor, t: f32) -> TextColor {
+        let rgb = |color: &TextColor| color.to_rgb().unwrap_or(0xFFFFFF);
+        gradient::lerp_rgb(rgb(self), rgb(other), t.clamp(0.0, 1.0)).into()
+    }
+
+    /// Moves this color towards white by the `amount` in range of `0.0..=1.0`,
+    /// see [`Self::blend()`]
+    pub fn lighten(&self, amount: f32) -> TextColor {
+        self.blend(&TextColor::from(0xFFFFFF), amount)
+    }
+
+    /// Moves this color towards black by the `amount` in range of `0.0..=1.0`,
+    /// see [`Self::blend()`]
+    pub fn darken(&self, amount: f32) -> TextColor {
+        self.blend(&TextColor::from(0x000000), amount)
+    }
+
+    /// Gets the [relative luminance](https://www.w3.org/TR/WCAG21/#dfn-relative-luminance)
+    /// of this color as defined by WCAG, from `0.0` for black to `1.0` for white.
+    /// Invalid hex colors are treated as white.
+    pub fn luminance(&self) -> f64 {
+        let rgb = self.to_rgb().unwrap_or(0xFFFFFF);
+        let linear = |shift: u32| {
+            let c = ((rgb >> shift) & 0xFF) as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(16) + 0.7152 * linear(8) + 0.0722 * linear(0)
+    }
+
+    /// Gets either black or white, whichever has the higher WCAG contrast ratio
+    /// with the background color, so that text in that color is readable on it.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{NamedColor, TextColor};
+    ///
+    /// let navy = TextColor::from(0x000080);
+    /// assert_eq!(TextColor::contrast_on(&navy), TextColor::Named(NamedColor::White));
+    /// ```
+    pub fn contrast_on(bg: &TextColor) -> TextColor {
+        let luminance = bg.luminance();
+        // contrast ratios are (lighter + 0.05) / (darker + 0.05), with white being 1.0
+        if (luminance + 0.05) / 0.05 > 1.05 / (luminance + 0.05) {
+            TextColor::Named(NamedColor::Black)
+        } else {
+            TextColor::Named(NamedColor::White)
+        }
+    }
+}
+
+/// Parses a `#rrggbb` or `#rgb` hex color, or a snake_case named color.
+///
+/// Hex colors keep the case they were written in, and the short `#rgb`
+/// form is expanded to `#rrggbb`.
+impl FromStr for TextColor {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(hex) = s.strip_prefix('#') else {
+            return NamedColor::from_str(s)
+                .map(TextColor::Named)
+                .map_err(|_| ColorParseError(s.to_string()));
+        };
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ColorParseError(s.to_string()));
+        }
+        match hex.len() {
+            6 => Ok(TextColor::Hex(s.to_string())),
+            3 => Ok(TextColor::Hex(hex.chars().fold(
+                String::from("#"),
+                |mut buf, c| {
+                    buf.push(c);
+                    buf.push(c);
+                    buf
+                },
+            ))),
+            _ => Err(ColorParseError(s.to_string())),
+        }
+    }
+}
+
+impl Display for TextColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextColor::Named(named) => named.fmt(f),
+            TextColor::Hex(hex) => f.write_str(hex),
+        }
+    }
+}
+
+/// An error returned when parsing an invalid [TextColor]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorParseError(String);
+
+impl Display for ColorParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid color '{}', expected `#rrggbb`, `#rgb` or a named color",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
 /// A named color
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -646,6 +2389,138 @@ pub enum NamedColor {
     Black,
 }
 
+impl NamedColor {
+    /// Gets all the named colors, ordered the same way as their legacy codes,
+    /// from [`NamedColor::Black`] (`0`) to [`NamedColor::White`] (`f`)
+    pub fn all() -> [NamedColor; 16] {
+        use NamedColor::*;
+        [
+            Black,
+            DarkBlue,
+            DarkGreen,
+            DarkAqua,
+            DarkRed,
+            DarkPurple,
+            Gold,
+            Gray,
+            DarkGray,
+            Blue,
+            Green,
+            Aqua,
+            Red,
+            LightPurple,
+            Yellow,
+            White,
+        ]
+    }
+
+    /// Gets the index of this color in [`NamedColor::all()`],
+    /// which is the value of its legacy code as a hex digit
+    pub fn ordinal(&self) -> u8 {
+        self.to_legacy_code().to_digit(16).unwrap_or_default() as u8
+    }
+
+    /// Gets the color at the provided index in [`NamedColor::all()`]
+    pub fn from_ordinal(ordinal: u8) -> Option<NamedColor> {
+        Self::all().get(ordinal as usize).copied()
+    }
+
+    /// Gets the named color for a legacy color code (`0`-`9`, `a`-`f`).
+    /// Uppercase codes are accepted as well.
+    pub fn from_legacy_code(code: char) -> Option<NamedColor> {
+        use NamedColor::*;
+        Some(match code.to_ascii_lowercase() {
+            '0' => Black,
+            '1' => DarkBlue,
+            '2' => DarkGreen,
+            '3' => DarkAqua,
+            '4' => DarkRed,
+            '5' => DarkPurple,
+            '6' => Gold,
+            '7' => Gray,
+            '8' => DarkGray,
+            '9' => Blue,
+            'a' => Green,
+            'b' => Aqua,
+            'c' => Red,
+            'd' => LightPurple,
+            'e' => Yellow,
+            'f' => White,
+            _ => return None,
+        })
+    }
+
+    /// Gets the RGB value the vanilla client renders this color with
+    pub fn to_rgb(&self) -> u32 {
+        use NamedColor::*;
+        match self {
+            Black => 0x000000,
+            DarkBlue => 0x0000AA,
+            DarkGreen => 0x00AA00,
+            DarkAqua => 0x00AAAA,
+            DarkRed => 0xAA0000,
+            DarkPurple => 0xAA00AA,
+            Gold => 0xFFAA00,
+            Gray => 0xAAAAAA,
+            DarkGray => 0x555555,
+            Blue => 0x5555FF,
+            Green => 0x55FF55,
+            Aqua => 0x55FFFF,
+            Red => 0xFF5555,
+            LightPurple => 0xFF55FF,
+            Yellow => 0xFFFF55,
+            White => 0xFFFFFF,
+        }
+    }
+
+    /// Gets the legacy color code of this color
+    pub fn to_legacy_code(&self) -> char {
+        use NamedColor::*;
+        match self {
+            Black => '0',
+            DarkBlue => '1',
+            DarkGreen => '2',
+            DarkAqua => '3',
+            DarkRed => '4',
+            DarkPurple => '5',
+            Gold => '6',
+            Gray => '7',
+            DarkGray => '8',
+            Blue => '9',
+            Green => 'a',
+            Aqua => 'b',
+            Red => 'c',
+            LightPurple => 'd',
+            Yellow => 'e',
+            White => 'f',
+        }
+    }
+}
+
+impl Display for NamedColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use NamedColor::*;
+        f.write_str(match self {
+            DarkRed => "dark_red",
+            Red => "red",
+            Gold => "gold",
+            Yellow => "yellow",
+            Green => "green",
+            DarkGreen => "dark_green",
+            Aqua => "aqua",
+            DarkAqua => "dark_aqua",
+            DarkBlue => "dark_blue",
+            Blue => "blue",
+            LightPurple => "light_purple",
+            DarkPurple => "dark_purple",
+            White => "white",
+            Gray => "gray",
+            DarkGray => "dark_gray",
+            Black => "black",
+        })
+    }
+}
+
 impl FromStr for NamedColor {
     type Err = ();
 
@@ -0,0 +1,180 @@
+//! Builder for written books, splitting their pages to fit the limits of the game
+
+use crate::component::item::quote_snbt;
+use crate::component::{Component, DisplayItemData, MessageContents, ResolvedStyle};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Maximum amount of pages in a written book
+pub const MAX_PAGES: usize = 100;
+
+/// Maximum amount of characters on a page of a book, which is the most the book editor allows
+pub const MAX_PAGE_CHARS: usize = 1024;
+
+/// An error returned when a book has more than [`MAX_PAGES`] pages after splitting them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooManyPages(pub(crate) usize);
+
+impl Display for TooManyPages {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Book has {} pages, but at most {} are allowed",
+            self.0, MAX_PAGES
+        )
+    }
+}
+
+impl Error for TooManyPages {}
+
+/// A builder for a written book, assembling the SNBT tag of the `minecraft:written_book` item
+///
+/// ```rust
+/// use lobsterchat::component::{Book, Component};
+///
+/// let book = Book::new("Guide", "Server")
+///     .page(Component::text("Welcome!"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(book.id, "minecraft:written_book");
+/// assert_eq!(
+///     book.tag.unwrap(),
+///     r#"{title:'Guide',author:'Server',pages:['{"text":"Welcome!"}']}"#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Book {
+    title: String,
+    author: String,
+    pages: Vec<Component>,
+    max_page_chars: usize,
+}
+
+impl Book {
+    /// Creates a builder for a book without any pages, with the provided title and author
+    pub fn new<T: Into<String>, A: Into<String>>(title: T, author: A) -> Self {
+        Self {
+            title: title.into(),
+            author: author.into(),
+            pages: Vec::new(),
+            max_page_chars: MAX_PAGE_CHARS,
+        }
+    }
+
+    /// Adds a page to the book, which is split into several pages if it is too long
+    pub fn page<C: Into<Component>>(mut self, page: C) -> Self {
+        self.pages.push(page.into());
+        self
+    }
+
+    /// Adds several pages to the book, see [`Self::page()`]
+    pub fn pages<I: IntoIterator<Item = Component>>(mut self, pages: I) -> Self {
+        self.pages.extend(pages);
+        self
+    }
+
+    /// Sets the maximum amount of characters on a page, [`MAX_PAGE_CHARS`] by default.
+    /// Pages are always allowed at least one character.
+    pub fn max_page_chars(mut self, max_chars: usize) -> Self {
+        self.max_page_chars = max_chars.max(1);
+        self
+    }
+
+    /// Gets the pages of the book, with the pages longer than the maximum amount of characters
+    /// split into several ones. Pages short enough are kept as they are.
+    ///
+    /// The text of a split page is broken up into empty text components with a child for every
+    /// part of the text, styled the same way as it was displayed in the original page.
+    /// Non-text contents are measured by their approximation in [`Component::flatten()`],
+    /// and are moved to the next page whole if they do not fit.
+    pub fn split_pages(&self) -> Vec<Component> {
+        self.pages
+            .iter()
+            .flat_map(|page| split_page(page, self.max_page_chars))
+            .collect()
+    }
+
+    /// Builds the book item, failing if the book has more than [`MAX_PAGES`] pages
+    /// after splitting them. Every page is written as JSON in the `pages` of the tag.
+    pub fn build(self) -> Result<DisplayItemData, TooManyPages> {
+        let pages = self.split_pages();
+        if pages.len() > MAX_PAGES {
+            return Err(TooManyPages(pages.len()));
+        }
+        let pages = pages
+            .iter()
+            .map(|page| quote_snbt(&page.to_string()))
+            .collect::<Vec<_>>();
+        let tag = format!(
+            "{{title:{},author:{},pages:[{}]}}",
+            quote_snbt(&self.title),
+            quote_snbt(&self.author),
+            pages.join(",")
+        );
+        Ok(DisplayItemData {
+            id: "minecraft:written_book".into(),
+            count: None,
+            tag: Some(tag),
+        })
+    }
+}
+
+/// Splits the page into pages with at most `max_chars` characters
+fn split_page(page: &Component, max_chars: usize) -> Vec<Component> {
+    let len = page
+        .runs()
+        .map(|(text, _)| text.chars().count())
+        .sum::<usize>();
+    if len <= max_chars {
+        return vec![page.clone()];
+    }
+    let mut pages = vec![Component::default()];
+    let mut len = 0;
+    let mut pending = vec![(page, ResolvedStyle::default())];
+    while let Some((comp, parent)) = pending.pop() {
+        let style = parent.inherit(comp);
+        pending.extend(
+            comp.extra
+                .iter()
+                .flatten()
+                .rev()
+                .map(|child| (child, style.clone())),
+        );
+        match &comp.contents {
+            MessageContents::Plain { text } => {
+                let mut rest = text.as_str();
+                while !rest.is_empty() {
+                    // a non-text part may have already filled the page past the maximum
+                    if len >= max_chars {
+                        pages.push(Component::default());
+                        len = 0;
+                    }
+                    let end = rest
+                        .char_indices()
+                        .nth(max_chars - len)
+                        .map_or(rest.len(), |(idx, _)| idx);
+                    let (part, tail) = rest.split_at(end);
+                    let page = pages.last_mut().expect("there is always a page");
+                    page.push_extra(style.apply(Component::text(part)));
+                    len += part.chars().count();
+                    rest = tail;
+                }
+            }
+            other => {
+                let part_len = other.flatten_str().chars().count();
+                if len > 0 && len + part_len > max_chars {
+                    pages.push(Component::default());
+                    len = 0;
+                }
+                let part = Component {
+                    contents: other.clone(),
+                    ..Default::default()
+                };
+                let page = pages.last_mut().expect("there is always a page");
+                page.push_extra(style.apply(part));
+                len += part_len;
+            }
+        }
+    }
+    pages
+}
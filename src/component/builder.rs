@@ -0,0 +1,158 @@
+//! Builder for assembling nested components without cloning them at every step
+
+use crate::component::{ClickEvent, Component, Formatting, HoverEvent, MessageContents, TextColor};
+
+/// A builder for [Component], see [`Component::builder()`].
+///
+/// Unlike the setters on [Component], the methods of this builder
+/// modify it in place and never clone the component being built.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentBuilder {
+    component: Component,
+}
+
+impl Component {
+    /// Creates a builder for an empty literal text component.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let built = Component::builder()
+    ///     .text("Hello, ")
+    ///     .color(NamedColor::Gold)
+    ///     .child(|b| b.text("World").bold(true))
+    ///     .child(|b| b.text("!"))
+    ///     .build();
+    /// let chained = Component::text("Hello, ")
+    ///     .color(NamedColor::Gold)
+    ///     .append(Component::text("World").bold(true))
+    ///     .append(Component::text("!"));
+    /// assert_eq!(built, chained);
+    /// ```
+    pub fn builder() -> ComponentBuilder {
+        ComponentBuilder::default()
+    }
+}
+
+impl From<Component> for ComponentBuilder {
+    fn from(component: Component) -> Self {
+        Self { component }
+    }
+}
+
+impl ComponentBuilder {
+    /// Sets the literal text of the component
+    pub fn text<S: Into<String>>(&mut self, text: S) -> &mut Self {
+        self.component.contents = MessageContents::Plain { text: text.into() };
+        self
+    }
+
+    /// Sets the color of the component
+    pub fn color<C: Into<TextColor>>(&mut self, color: C) -> &mut Self {
+        self.component.color = Some(color.into());
+        self
+    }
+
+    /// Gives or removes the provided formatting from the component
+    pub fn formatted(&mut self, fmt: Formatting, enabled: bool) -> &mut Self {
+        let field = match fmt {
+            Formatting::Obfuscated => &mut self.component.obfuscated,
+            Formatting::Bold => &mut self.component.bold,
+            Formatting::Strikethrough => &mut self.component.strikethrough,
+            Formatting::Underline => &mut self.component.underlined,
+            Formatting::Italic => &mut self.component.italic,
+            Formatting::Reset => &mut self.component.reset,
+        };
+        *field = Some(enabled);
+        self
+    }
+
+    /// Gives or removes the `bold` effect from the component
+    pub fn bold(&mut self, bold: bool) -> &mut Self {
+        self.component.bold = Some(bold);
+        self
+    }
+
+    /// Gives or removes the `italic` effect from the component
+    pub fn italic(&mut self, italic: bool) -> &mut Self {
+        self.component.italic = Some(italic);
+        self
+    }
+
+    /// Gives or removes the `obfuscated` effect from the component
+    pub fn obfuscated(&mut self, obfuscated: bool) -> &mut Self {
+        self.component.obfuscated = Some(obfuscated);
+        self
+    }
+
+    /// Gives or removes the `strikethrough` effect from the component
+    pub fn strikethrough(&mut self, strikethrough: bool) -> &mut Self {
+        self.component.strikethrough = Some(strikethrough);
+        self
+    }
+
+    /// Gives or removes the `underlined` effect from the component
+    pub fn underlined(&mut self, underlined: bool) -> &mut Self {
+        self.component.underlined = Some(underlined);
+        self
+    }
+
+    /// Sets the font the component is rendered with
+    pub fn font<S: Into<String>>(&mut self, font: S) -> &mut Self {
+        self.component.font = Some(font.into());
+        self
+    }
+
+    /// Sets the color of the text shadow of the component, in the `0xAARRGGBB` format
+    pub fn shadow_color(&mut self, argb: u32) -> &mut Self {
+        self.component.shadow_color = Some(argb);
+        self
+    }
+
+    /// Sets the text that is inserted each time the component is shift-clicked
+    pub fn insertion<S: Into<String>>(&mut self, insertion: S) -> &mut Self {
+        self.component.insertion = Some(insertion.into());
+        self
+    }
+
+    /// Sets the click event handler of the component
+    pub fn click_event(&mut self, e: ClickEvent) -> &mut Self {
+        self.component.click_event = Some(e);
+        self
+    }
+
+    /// Sets the hover event handler of the component
+    pub fn hover_event(&mut self, e: HoverEvent) -> &mut Self {
+        self.component.hover_event = Some(e);
+        self
+    }
+
+    /// Appends an already built component as a child
+    pub fn append<C: Into<Component>>(&mut self, comp: C) -> &mut Self {
+        self.component.push_extra(comp.into());
+        self
+    }
+
+    /// Appends the formatted value as a plain text child
+    pub fn append_display<D: std::fmt::Display>(&mut self, value: D) -> &mut Self {
+        self.component
+            .push_extra(Component::text(value.to_string()));
+        self
+    }
+
+    /// Builds a child component with a new builder and appends it
+    pub fn child<F>(&mut self, build: F) -> &mut Self
+    where
+        F: FnOnce(&mut ComponentBuilder) -> &mut ComponentBuilder,
+    {
+        let mut child = ComponentBuilder::default();
+        build(&mut child);
+        self.component.push_extra(child.component);
+        self
+    }
+
+    /// Takes the built component out of this builder, leaving an empty one in its place
+    pub fn build(&mut self) -> Component {
+        std::mem::take(&mut self.component)
+    }
+}
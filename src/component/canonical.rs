@@ -0,0 +1,109 @@
+//! Serialization of components into JSON with a fixed order of the fields
+
+use crate::component::Component;
+use serde_json::Value;
+
+/// Order of the fields in [`Component::to_json_canonical()`], in every object of the component.
+///
+/// The contents come first, followed by the style in the same order as in the vanilla
+/// `Style` codec, and the children come last. Event fields start with their action.
+const FIELD_ORDER: &[&str] = &[
+    "action",
+    // contents
+    "text",
+    "translate",
+    "fallback",
+    "with",
+    "score",
+    "name",
+    "objective",
+    "value",
+    "selector",
+    "separator",
+    "keybind",
+    "nbt",
+    "interpret",
+    "block",
+    "entity",
+    "storage",
+    // hover event contents
+    "type",
+    "id",
+    "count",
+    "tag",
+    "contents",
+    // style
+    "color",
+    "shadow_color",
+    "bold",
+    "italic",
+    "underlined",
+    "strikethrough",
+    "obfuscated",
+    "reset",
+    "clickEvent",
+    "hoverEvent",
+    "insertion",
+    "font",
+    // children
+    "extra",
+];
+
+impl Component {
+    /// Serializes this component as JSON with the fields of every object in a fixed order,
+    /// so that equal components are always serialized into the same bytes, e.g. to use
+    /// the JSON as a cache key. Fields unknown to this crate are written last, sorted by name.
+    ///
+    /// The contents are written first, followed by the style, in the order of `color`,
+    /// `shadow_color`, `bold`, `italic`, `underlined`, `strikethrough`, `obfuscated`, `reset`,
+    /// `clickEvent`, `hoverEvent`, `insertion` and `font`, and the `extra` children come last.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let comp = Component::text("Hi").append("!").italic(true).color(NamedColor::Red);
+    /// assert_eq!(
+    ///     comp.to_json_canonical(),
+    ///     r#"{"text":"Hi","color":"red","italic":true,"extra":[{"text":"!"}]}"#
+    /// );
+    /// ```
+    pub fn to_json_canonical(&self) -> String {
+        let mut buf = String::new();
+        write_value(&serde_json::to_value(self).unwrap_or_default(), &mut buf);
+        buf
+    }
+}
+
+/// Writes the JSON value, with the fields of objects in the [`FIELD_ORDER`]
+fn write_value(value: &Value, buf: &mut String) {
+    match value {
+        Value::Array(values) => {
+            buf.push('[');
+            for (idx, value) in values.iter().enumerate() {
+                if idx > 0 {
+                    buf.push(',');
+                }
+                write_value(value, buf);
+            }
+            buf.push(']');
+        }
+        Value::Object(fields) => {
+            let mut fields = fields.iter().collect::<Vec<_>>();
+            fields.sort_by_key(|(name, _)| {
+                let rank = FIELD_ORDER.iter().position(|known| known == name);
+                (rank.unwrap_or(FIELD_ORDER.len()), *name)
+            });
+            buf.push('{');
+            for (idx, (name, value)) in fields.into_iter().enumerate() {
+                if idx > 0 {
+                    buf.push(',');
+                }
+                buf.push_str(&Value::String(name.clone()).to_string());
+                buf.push(':');
+                write_value(value, buf);
+            }
+            buf.push('}');
+        }
+        scalar => buf.push_str(&scalar.to_string()),
+    }
+}
@@ -0,0 +1,202 @@
+//! Semantic comparison of components, ignoring differences in how they are structured
+
+use crate::component::TranslatedMessage;
+use crate::component::{Component, HoverEvent, MessageContents, ResolvedStyle, TextColor};
+
+impl ResolvedStyle {
+    /// Checks whether the text is displayed the same way with both styles,
+    /// regardless of how their colors and hover text are written
+    fn semantically_eq(&self, other: &ResolvedStyle) -> bool {
+        let hover_eq = match (&self.hover_event, &other.hover_event) {
+            (
+                Some(HoverEvent::ShowText { contents: this }),
+                Some(HoverEvent::ShowText { contents: that }),
+            ) => this.semantically_eq(that),
+            (this, that) => this == that,
+        };
+        normalize_color(&self.color) == normalize_color(&other.color)
+            && self.bold == other.bold
+            && self.italic == other.italic
+            && self.obfuscated == other.obfuscated
+            && self.strikethrough == other.strikethrough
+            && self.underlined == other.underlined
+            && self.font == other.font
+            && self.shadow_color == other.shadow_color
+            && self.insertion == other.insertion
+            && self.click_event == other.click_event
+            && hover_eq
+    }
+}
+
+/// A run of the component tree, with its style resolved
+#[derive(Debug)]
+enum Run<'a> {
+    Text(ResolvedStyle, String),
+    Other(ResolvedStyle, &'a MessageContents),
+}
+
+impl Run<'_> {
+    fn semantically_eq(&self, other: &Run) -> bool {
+        match (self, other) {
+            (Run::Text(this_style, this), Run::Text(that_style, that)) => {
+                this == that && this_style.semantically_eq(that_style)
+            }
+            (Run::Other(this_style, this), Run::Other(that_style, that)) => {
+                this == that && this_style.semantically_eq(that_style)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Gets the RGB value of the color, or the raw string if it is not a valid hex color
+fn normalize_color(color: &Option<TextColor>) -> Option<Result<u32, &str>> {
+    color.as_ref().map(|color| match color {
+        TextColor::Hex(hex) => color.to_rgb().ok_or(hex.as_str()),
+        TextColor::Named(named) => Ok(named.to_rgb()),
+    })
+}
+
+/// Collects the runs of the component, merging adjacent text with the same style
+fn collect_runs(comp: &Component) -> Vec<Run<'_>> {
+    let mut runs = Vec::new();
+    for (contents, style) in comp.styled_contents() {
+        match contents {
+            MessageContents::Plain { text } if text.is_empty() => {}
+            MessageContents::Plain { text } => match runs.last_mut() {
+                Some(Run::Text(last, buf)) if last.semantically_eq(&style) => buf.push_str(text),
+                _ => runs.push(Run::Text(style, text.clone())),
+            },
+            other => runs.push(Run::Other(style, other)),
+        }
+    }
+    runs
+}
+
+impl Component {
+    /// Checks whether this component is displayed the same way as the other one,
+    /// even if the two are structured or serialized differently.
+    ///
+    /// Both components are resolved into runs of contents, with the style of every run
+    /// inherited from its parents. The following differences are considered irrelevant:
+    /// * How the tree is nested, e.g. a flat text component and a parent with a single child
+    /// * How text is split between components, as long as the adjacent parts share their style
+    /// * Empty literal text components
+    /// * Explicitly disabled formatting and formatting that was never set
+    /// * Styles set on a component that were already inherited from its parent
+    /// * How colors are written, e.g. `red` and `#FF5555` are equal, as are `#ff5555` and `#FF5555`
+    /// * How hover text is structured, which is compared semantically as well
+    /// * The `reset` field, which is not a part of the vanilla format
+    ///
+    /// Everything else, like the arguments of translatable components
+    /// or the contents of item and entity hover events, is compared as is.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let flat = Component::text("Hello, World!").color(NamedColor::Red);
+    /// let nested = Component::text("Hello, ")
+    ///     .color(0xFF5555)
+    ///     .append(Component::text("World!"));
+    /// assert_ne!(flat, nested);
+    /// assert!(flat.semantically_eq(&nested));
+    /// ```
+    pub fn semantically_eq(&self, other: &Component) -> bool {
+        let this = collect_runs(self);
+        let that = collect_runs(other);
+        this.len() == that.len()
+            && this
+                .iter()
+                .zip(&that)
+                .all(|(this, that)| this.semantically_eq(that))
+    }
+
+    /// Checks whether this component has the same structure and style as the template,
+    /// treating any two literal text components as equal regardless of their text,
+    /// e.g. to recognize which template a received message was built from.
+    ///
+    /// Unlike [`Self::semantically_eq()`], the trees have to be nested exactly the same way,
+    /// and every style is compared as it is set on each component. The arguments of translatable
+    /// components and the text of `show_text` hover events are matched as templates as well,
+    /// while any other contents and events are compared as is.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let joined = |name: &str| {
+    ///     Component::text(name)
+    ///         .color(NamedColor::Yellow)
+    ///         .append(Component::text(" joined the game").color(NamedColor::Gray))
+    /// };
+    /// let template = joined("<player>");
+    /// assert!(joined("Steve").matches_template(&template));
+    /// assert!(!Component::text("Steve joined the game").matches_template(&template));
+    /// ```
+    pub fn matches_template(&self, template: &Component) -> bool {
+        // destructured, so that any style added later has to be compared here as well
+        let Component {
+            extra,
+            bold,
+            italic,
+            obfuscated,
+            strikethrough,
+            underlined,
+            reset,
+            color,
+            font,
+            shadow_color,
+            contents,
+            insertion,
+            click_event,
+            hover_event,
+        } = self;
+        let children = extra.as_deref().unwrap_or_default();
+        let template_children = template.extra.as_deref().unwrap_or_default();
+        let hover_matches = match (hover_event, &template.hover_event) {
+            (
+                Some(HoverEvent::ShowText { contents: this }),
+                Some(HoverEvent::ShowText { contents: that }),
+            ) => this.matches_template(that),
+            (this, that) => this == that,
+        };
+        let contents_match = match (contents, &template.contents) {
+            (MessageContents::Plain { .. }, MessageContents::Plain { .. }) => true,
+            (MessageContents::Translate(this), MessageContents::Translate(that)) => {
+                translation_matches(this, that)
+            }
+            (this, that) => this == that,
+        };
+        *bold == template.bold
+            && *italic == template.italic
+            && *obfuscated == template.obfuscated
+            && *strikethrough == template.strikethrough
+            && *underlined == template.underlined
+            && *reset == template.reset
+            && *color == template.color
+            && *font == template.font
+            && *shadow_color == template.shadow_color
+            && *insertion == template.insertion
+            && *click_event == template.click_event
+            && hover_matches
+            && contents_match
+            && children.len() == template_children.len()
+            && children
+                .iter()
+                .zip(template_children)
+                .all(|(child, template)| child.matches_template(template))
+    }
+}
+
+/// Checks whether the translatable contents match the template, with their arguments
+/// matched as templates, see [`Component::matches_template()`]
+fn translation_matches(this: &TranslatedMessage, template: &TranslatedMessage) -> bool {
+    let args = this.with.as_deref().unwrap_or_default();
+    let template_args = template.with.as_deref().unwrap_or_default();
+    this.translate == template.translate
+        && this.fallback == template.fallback
+        && args.len() == template_args.len()
+        && args
+            .iter()
+            .zip(template_args)
+            .all(|(arg, template)| arg.matches_template(template))
+}
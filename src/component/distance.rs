@@ -0,0 +1,128 @@
+//! Color differences used for downsampling RGB colors to named colors
+
+use crate::component::NamedColor;
+
+impl NamedColor {
+    /// Gets the named color closest to the provided RGB color, by the euclidean
+    /// distance of their RGB channels. Cheap, but misranks some colors perceptually,
+    /// see [`NamedColor::nearest_perceptual()`] for a more accurate version.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::NamedColor;
+    ///
+    /// assert_eq!(NamedColor::nearest(0xFFA500), NamedColor::Gold);
+    /// ```
+    pub fn nearest(rgb: u32) -> NamedColor {
+        let channels =
+            |rgb: u32| [(rgb >> 16) & 0xFF, (rgb >> 8) & 0xFF, rgb & 0xFF].map(|c| c as i32);
+        let target = channels(rgb);
+        NamedColor::all()
+            .into_iter()
+            .min_by_key(|named| {
+                channels(named.to_rgb())
+                    .iter()
+                    .zip(target)
+                    .map(|(a, b)| (a - b).pow(2))
+                    .sum::<i32>()
+            })
+            .unwrap_or(NamedColor::White)
+    }
+
+    /// Gets the named color closest to the provided RGB color, by the
+    /// [CIEDE2000](https://en.wikipedia.org/wiki/Color_difference#CIEDE2000) difference
+    /// of their CIELAB coordinates, which matches the human perception of colors closely.
+    ///
+    /// Noticeably slower than [`NamedColor::nearest()`], so prefer that one in hot paths.
+    pub fn nearest_perceptual(rgb: u32) -> NamedColor {
+        let target = srgb_to_lab(rgb);
+        NamedColor::all()
+            .into_iter()
+            .map(|named| (named, ciede2000(srgb_to_lab(named.to_rgb()), target)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(NamedColor::White, |(named, _)| named)
+    }
+}
+
+/// Converts an sRGB color to its `[L, a, b]` CIELAB coordinates, under the D65 illuminant
+pub(crate) fn srgb_to_lab(rgb: u32) -> [f64; 3] {
+    let linear = |shift: u32| {
+        let c = ((rgb >> shift) & 0xFF) as f64 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(16), linear(8), linear(0));
+
+    // relative to the D65 white point
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+
+    let f = |t: f64| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Computes the CIEDE2000 color difference between two CIELAB colors
+pub(crate) fn ciede2000([l1, a1, b1]: [f64; 3], [l2, a2, b2]: [f64; 3]) -> f64 {
+    let pow7 = |v: f64| v.powi(7);
+    let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f64, b: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1 - 360.0
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+    let (l, c, h) = (dl / s_l, dc / s_c, dh / s_h);
+    (l * l + c * c + h * h + r_t * c * h).sqrt()
+}
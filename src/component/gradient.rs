@@ -0,0 +1,193 @@
+//! Color interpolation used by gradient components
+
+/// The color space in which gradient colors are interpolated
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// Interpolates the RGB channels directly. Cheap, but produces muddy mid-tones
+    /// between colors that are far apart.
+    #[default]
+    LinearRgb,
+    /// Interpolates in the perceptually uniform [OKLab](https://bottosson.github.io/posts/oklab/)
+    /// color space, producing evenly spaced and vivid mid-tones.
+    Oklab,
+}
+
+/// Linearly interpolates between two RGB colors, `t` being in range of `0.0..=1.0`
+pub(crate) fn lerp_rgb(from: u32, to: u32, t: f32) -> u32 {
+    let channel = |shift: u32| {
+        let a = ((from >> shift) & 0xFF) as f32;
+        let b = ((to >> shift) & 0xFF) as f32;
+        ((a + (b - a) * t).round() as u32).min(0xFF) << shift
+    };
+    channel(16) | channel(8) | channel(0)
+}
+
+/// Interpolates between two RGB colors in the OKLab color space, `t` being in range of `0.0..=1.0`
+pub(crate) fn lerp_oklab(from: u32, to: u32, t: f32) -> u32 {
+    let [l1, a1, b1] = srgb_to_oklab(from);
+    let [l2, a2, b2] = srgb_to_oklab(to);
+    oklab_to_srgb([l1 + (l2 - l1) * t, a1 + (a2 - a1) * t, b1 + (b2 - b1) * t])
+}
+
+/// Converts an sRGB color to its `[L, a, b]` OKLab coordinates
+pub(crate) fn srgb_to_oklab(rgb: u32) -> [f32; 3] {
+    let linear = |shift: u32| {
+        let c = ((rgb >> shift) & 0xFF) as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(16), linear(8), linear(0));
+
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+/// Converts `[L, a, b]` OKLab coordinates to an sRGB color,
+/// clamping colors outside of the sRGB gamut
+pub(crate) fn oklab_to_srgb([l, a, b]: [f32; 3]) -> u32 {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+
+    let channel = |linear: f32, shift: u32| {
+        let c = linear.clamp(0.0, 1.0);
+        let c = if c <= 0.003_130_8 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        ((c * 255.0).round() as u32).min(0xFF) << shift
+    };
+    channel(4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_, 16)
+        | channel(-1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_, 8)
+        | channel(
+            -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+            0,
+        )
+}
+
+/// Samples the color at the position `t` (in range of `0.0..=1.0`)
+/// of a gradient going through all the provided stops
+pub(crate) fn sample(stops: &[u32], t: f32, space: ColorSpace) -> u32 {
+    match stops {
+        [] => 0xFFFFFF,
+        [single] => *single,
+        _ => {
+            let scaled = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+            let idx = (scaled as usize).min(stops.len() - 2);
+            let lerp = match space {
+                ColorSpace::LinearRgb => lerp_rgb,
+                ColorSpace::Oklab => lerp_oklab,
+            };
+            lerp(stops[idx], stops[idx + 1], scaled - idx as f32)
+        }
+    }
+}
+
+/// Gets the colors of a gradient spread over `len` characters
+pub(crate) fn colors(
+    stops: &[u32],
+    len: usize,
+    space: ColorSpace,
+) -> impl Iterator<Item = u32> + '_ {
+    colors_phased(stops, len, space, 0.0)
+}
+
+/// Gets the colors of a gradient spread over `len` characters, starting at the `phase`
+/// of the gradient, which is a fraction of its length wrapped into range of `0.0..1.0`.
+/// The colors past the end of the gradient wrap around to its start.
+pub(crate) fn colors_phased(
+    stops: &[u32],
+    len: usize,
+    space: ColorSpace,
+    phase: f32,
+) -> impl Iterator<Item = u32> + '_ {
+    let steps = len.saturating_sub(1).max(1) as f32;
+    let phase = phase.rem_euclid(1.0);
+    (0..len).map(move |i| {
+        let t = i as f32 / steps + phase;
+        // the end of the gradient is only wrapped once it is passed,
+        // so that without a phase the last character has the last stop
+        let t = if t > 1.0 { t - 1.0 } else { t };
+        sample(stops, t, space)
+    })
+}
+
+/// Gets the colors of a rainbow spread over `len` characters, going once around the hue circle
+pub(crate) fn rainbow(len: usize) -> impl DoubleEndedIterator<Item = u32> {
+    rainbow_hsv(len, 1.0, 1.0)
+}
+
+/// Same as [`rainbow`], but with the provided saturation and value of all the colors,
+/// both in range of `0.0..=1.0`, e.g. lower saturation for pastel colors
+pub(crate) fn rainbow_hsv(
+    len: usize,
+    saturation: f32,
+    value: f32,
+) -> impl DoubleEndedIterator<Item = u32> {
+    let steps = len.max(1) as f32;
+    (0..len).map(move |i| hsv(i as f32 / steps, saturation, value))
+}
+
+/// Splits the text into words, each of them along with the whitespace following it.
+/// Whitespace at the start of the text is kept with the first word.
+pub(crate) fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut prev_whitespace = false;
+    for (idx, c) in text.char_indices() {
+        if prev_whitespace && !c.is_whitespace() && !text[start..idx].trim_start().is_empty() {
+            words.push(&text[start..idx]);
+            start = idx;
+        }
+        prev_whitespace = c.is_whitespace();
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+/// Checks whether a new word starts in the text, given whether the text before it
+/// ended with whitespace. Text after which nothing was written counts as whitespace.
+#[cfg(feature = "minimessage")]
+pub(crate) fn starts_word(text: &str, after_whitespace: bool) -> bool {
+    let mut prev_whitespace = after_whitespace;
+    for c in text.chars() {
+        if prev_whitespace && !c.is_whitespace() {
+            return true;
+        }
+        prev_whitespace = c.is_whitespace();
+    }
+    false
+}
+
+/// Converts a hue in range of `0.0..1.0`, along with a saturation and value
+/// in range of `0.0..=1.0`, to an RGB color
+fn hsv(hue: f32, saturation: f32, value: f32) -> u32 {
+    let scaled = hue.rem_euclid(1.0) * 6.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (scaled % 2.0 - 1.0).abs());
+    let (r, g, b) = match scaled as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    // lifting all the channels equally, so that the brightest one reaches the value
+    let min = value - chroma;
+    let channel = |c: f32| ((c + min) * 255.0).round() as u32;
+    channel(r) << 16 | channel(g) << 8 | channel(b)
+}
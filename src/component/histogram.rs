@@ -0,0 +1,66 @@
+//! Statistics of the styles used in the text of components
+
+use crate::component::{Component, Formatting, TextColor};
+use std::collections::HashMap;
+
+/// Character counts of a component by their resolved style, see [`Component::style_histogram()`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyleHistogram {
+    /// Total count of characters
+    pub total: usize,
+    /// Count of characters of every color, keyed by the color as it is written,
+    /// so `red` and `#FF5555` are counted separately
+    pub colors: HashMap<TextColor, usize>,
+    /// Count of characters without any color, which are displayed in the default color
+    pub uncolored: usize,
+    /// Count of characters with every enabled formatting. Characters with several
+    /// formattings are counted for each of them, and [`Formatting::Reset`] is never counted.
+    pub formatting: HashMap<Formatting, usize>,
+}
+
+impl StyleHistogram {
+    /// Gets the count of characters with the formatting enabled
+    pub fn count_formatted(&self, format: Formatting) -> usize {
+        self.formatting.get(&format).copied().unwrap_or_default()
+    }
+}
+
+impl Component {
+    /// Counts the characters of the text of this component by their color and formatting,
+    /// inherited from their parents the same way as in [`Self::runs()`].
+    /// Non-text contents are counted by their approximation in [`Self::flatten()`].
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, Formatting, NamedColor};
+    ///
+    /// let comp = Component::text("Hello, ")
+    ///     .color(NamedColor::Red)
+    ///     .append(Component::text("World!").obfuscated(true));
+    /// let histogram = comp.style_histogram();
+    /// assert_eq!(histogram.total, 13);
+    /// assert_eq!(histogram.colors[&NamedColor::Red.into()], 13);
+    /// assert_eq!(histogram.count_formatted(Formatting::Obfuscated), 6);
+    /// ```
+    pub fn style_histogram(&self) -> StyleHistogram {
+        let mut histogram = StyleHistogram::default();
+        for (text, style) in self.runs() {
+            let len = text.chars().count();
+            histogram.total += len;
+            match style.color {
+                Some(color) => *histogram.colors.entry(color).or_default() += len,
+                None => histogram.uncolored += len,
+            }
+            let formatting = [
+                (style.bold, Formatting::Bold),
+                (style.italic, Formatting::Italic),
+                (style.obfuscated, Formatting::Obfuscated),
+                (style.strikethrough, Formatting::Strikethrough),
+                (style.underlined, Formatting::Underline),
+            ];
+            for (_, format) in formatting.iter().filter(|(enabled, _)| *enabled) {
+                *histogram.formatting.entry(*format).or_default() += len;
+            }
+        }
+        histogram
+    }
+}
@@ -0,0 +1,134 @@
+//! HTML rendering of components, e.g. for web chat viewers
+
+use crate::component::{ClickEvent, Component, HoverEvent, TextColor};
+
+impl Component {
+    /// Renders this component as HTML.
+    ///
+    /// Every styled component is wrapped in a `<span>` with an inline `style`
+    /// attribute, nested the same way as the component tree. Components opening
+    /// `http(s)` URLs on click become `<a>` anchors, and hover text becomes a `title` attribute.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let html = Component::text("<3").color(NamedColor::Red).bold(true).to_html();
+    /// assert_eq!(html, r#"<span style="color:#FF5555;font-weight:bold">&lt;3</span>"#);
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut buf = String::new();
+        render(self, &mut buf);
+        buf
+    }
+}
+
+fn render(comp: &Component, buf: &mut String) {
+    let style = style(comp);
+    let url = match comp.get_click_event() {
+        // other schemes, e.g. `javascript:`, are not safe to link to
+        Some(ClickEvent::OpenUrl(url))
+            if url.starts_with("https://") || url.starts_with("http://") =>
+        {
+            Some(url)
+        }
+        _ => None,
+    };
+    let title = match comp.get_hover_event() {
+        Some(HoverEvent::ShowText { contents }) => Some(contents.clone().flatten()),
+        _ => None,
+    };
+
+    let tag = match url {
+        Some(_) => "a",
+        None if style.is_empty() && title.is_none() => "",
+        None => "span",
+    };
+    if !tag.is_empty() {
+        buf.push('<');
+        buf.push_str(tag);
+        if let Some(url) = url {
+            buf.push_str(" href=\"");
+            escape(url, buf);
+            buf.push('"');
+        }
+        if !style.is_empty() {
+            buf.push_str(" style=\"");
+            escape(&style.join(";"), buf);
+            buf.push('"');
+        }
+        if let Some(title) = title {
+            buf.push_str(" title=\"");
+            escape(&title, buf);
+            buf.push('"');
+        }
+        buf.push('>');
+    }
+
+    for (idx, line) in comp.contents.flatten().split('\n').enumerate() {
+        if idx > 0 {
+            buf.push_str("<br>");
+        }
+        escape(line, buf);
+    }
+    for child in comp.extra.iter().flatten() {
+        render(child, buf);
+    }
+
+    if !tag.is_empty() {
+        buf.push_str("</");
+        buf.push_str(tag);
+        buf.push('>');
+    }
+}
+
+/// Gets the CSS declarations for the styles set on this component itself,
+/// the inherited ones are applied by the parent elements
+fn style(comp: &Component) -> Vec<String> {
+    let mut style = Vec::new();
+    // the color is always written from its RGB value, as the raw hex string may contain
+    // anything and would end up in the CSS as is, so malformed colors are dropped
+    if let Some(rgb) = comp.get_color_opt().and_then(TextColor::to_rgb) {
+        style.push(format!("color:#{:06X}", rgb));
+    }
+    match comp.bold {
+        Some(true) => style.push("font-weight:bold".into()),
+        Some(false) => style.push("font-weight:normal".into()),
+        None => {}
+    }
+    match comp.italic {
+        Some(true) => style.push("font-style:italic".into()),
+        Some(false) => style.push("font-style:normal".into()),
+        None => {}
+    }
+    let decorations = [
+        (comp.underlined, "underline"),
+        (comp.strikethrough, "line-through"),
+    ];
+    let enabled = decorations
+        .iter()
+        .filter(|(enabled, _)| *enabled == Some(true))
+        .map(|(_, decoration)| *decoration)
+        .collect::<Vec<_>>();
+    if !enabled.is_empty() {
+        style.push(format!("text-decoration:{}", enabled.join(" ")));
+    } else if decorations
+        .iter()
+        .any(|(enabled, _)| *enabled == Some(false))
+    {
+        style.push("text-decoration:none".into());
+    }
+    style
+}
+
+fn escape(text: &str, buf: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\'' => buf.push_str("&#39;"),
+            c => buf.push(c),
+        }
+    }
+}
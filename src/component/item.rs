@@ -0,0 +1,129 @@
+//! Builder for displayed item data, assembling its SNBT tag
+
+use crate::component::{Component, DisplayItemData, InvalidIdentifier, ResourceLocation};
+use std::str::FromStr;
+
+/// Checks whether the identifier is a valid resource location with an explicit namespace
+fn validate_namespaced(id: &str) -> Result<(), InvalidIdentifier> {
+    match id.contains(':') {
+        true => ResourceLocation::from_str(id).map(|_| ()),
+        false => Err(InvalidIdentifier(id.to_string())),
+    }
+}
+
+/// Quotes a string for SNBT, using single quotes as the JSON of components contains double quotes
+pub(crate) fn quote_snbt(value: &str) -> String {
+    let mut buf = String::with_capacity(value.len() + 2);
+    buf.push('\'');
+    for c in value.chars() {
+        if matches!(c, '\\' | '\'') {
+            buf.push('\\');
+        }
+        buf.push(c);
+    }
+    buf.push('\'');
+    buf
+}
+
+/// A builder for [DisplayItemData], see [`DisplayItemData::builder()`]
+#[derive(Debug, Clone)]
+pub struct DisplayItemDataBuilder {
+    id: String,
+    count: Option<i32>,
+    name: Option<Component>,
+    lore: Vec<Component>,
+    enchantments: Vec<(String, i16)>,
+}
+
+impl DisplayItemData {
+    /// Creates a builder for the item with the provided namespaced ID,
+    /// which assembles the SNBT `tag` of the item.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Component, DisplayItemData};
+    ///
+    /// let sword = DisplayItemData::builder("minecraft:diamond_sword")
+    ///     .name(Component::text("Sword"))
+    ///     .enchantment("minecraft:sharpness", 5)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     sword.tag.unwrap(),
+    ///     r#"{display:{Name:'{"text":"Sword"}'},Enchantments:[{id:"minecraft:sharpness",lvl:5s}]}"#
+    /// );
+    /// ```
+    pub fn builder<S: Into<String>>(id: S) -> DisplayItemDataBuilder {
+        DisplayItemDataBuilder {
+            id: id.into(),
+            count: None,
+            name: None,
+            lore: Vec::new(),
+            enchantments: Vec::new(),
+        }
+    }
+}
+
+impl DisplayItemDataBuilder {
+    /// Sets the amount of items displayed
+    pub fn count(mut self, count: i32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Sets the custom name of the item
+    pub fn name<C: Into<Component>>(mut self, name: C) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Adds a line of lore to the item
+    pub fn lore<C: Into<Component>>(mut self, line: C) -> Self {
+        self.lore.push(line.into());
+        self
+    }
+
+    /// Adds an enchantment with the provided namespaced ID and level to the item
+    pub fn enchantment<S: Into<String>>(mut self, id: S, level: i16) -> Self {
+        self.enchantments.push((id.into(), level));
+        self
+    }
+
+    /// Builds the item data, failing if the item ID or any of the
+    /// enchantment IDs are not in the `namespace:path` format
+    pub fn build(self) -> Result<DisplayItemData, InvalidIdentifier> {
+        validate_namespaced(&self.id)?;
+
+        let mut tag = Vec::new();
+        let mut display = Vec::new();
+        if let Some(name) = &self.name {
+            display.push(format!("Name:{}", quote_snbt(&name.to_string())));
+        }
+        if !self.lore.is_empty() {
+            let lines = self
+                .lore
+                .iter()
+                .map(|line| quote_snbt(&line.to_string()))
+                .collect::<Vec<_>>();
+            display.push(format!("Lore:[{}]", lines.join(",")));
+        }
+        if !display.is_empty() {
+            tag.push(format!("display:{{{}}}", display.join(",")));
+        }
+        if !self.enchantments.is_empty() {
+            let mut enchantments = Vec::with_capacity(self.enchantments.len());
+            for (id, level) in &self.enchantments {
+                validate_namespaced(id)?;
+                enchantments.push(format!("{{id:\"{}\",lvl:{}s}}", id, level));
+            }
+            tag.push(format!("Enchantments:[{}]", enchantments.join(",")));
+        }
+
+        Ok(DisplayItemData {
+            id: self.id,
+            count: self.count,
+            tag: Some(tag)
+                .filter(|it| !it.is_empty())
+                .map(|it| format!("{{{}}}", it.join(","))),
+        })
+    }
+}
@@ -0,0 +1,98 @@
+//! Iteration over the children and descendants of a component
+
+use crate::component::Component;
+
+/// Iterator over all the descendants of a component, see [`Component::descendants()`]
+#[derive(Debug, Clone)]
+pub struct Descendants<'a> {
+    /// Components that are yet to be visited, in reverse order
+    pending: Vec<&'a Component>,
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a Component;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let comp = self.pending.pop()?;
+        self.pending.extend(comp.extra.iter().flatten().rev());
+        Some(comp)
+    }
+}
+
+impl Component {
+    /// Iterates over the direct children of this component, in order.
+    ///
+    /// Iterating over a component or a reference to it with a `for` loop does the same.
+    /// Use [`Self::descendants()`] to also visit the children of the children.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let comp = Component::text("Hello").append(", ").append("World!");
+    /// let mut texts = Vec::new();
+    /// for child in &comp {
+    ///     texts.push(child.get_text_content().unwrap());
+    /// }
+    /// assert_eq!(texts, [", ", "World!"]);
+    /// ```
+    pub fn children(&self) -> std::slice::Iter<'_, Component> {
+        self.extra.as_deref().unwrap_or_default().iter()
+    }
+
+    /// Iterates over all the descendants of this component, not including the component itself.
+    ///
+    /// The tree is traversed in the same order as in [`Self::find_first()`],
+    /// and the contents of hover events are not visited either.
+    pub fn descendants(&self) -> Descendants<'_> {
+        Descendants {
+            pending: self.children().rev().collect(),
+        }
+    }
+
+    /// Iterates over the flattened contents of this component and all of its descendants,
+    /// in the same order as [`Self::flatten()`], but without joining them into a single string.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    /// use std::fmt::Write;
+    ///
+    /// let comp = Component::text("Hello").append(", ").append("World!");
+    /// let mut sink = String::new();
+    /// for text in comp.flatten_iter() {
+    ///     sink.write_str(text).unwrap();
+    /// }
+    /// assert_eq!(sink, "Hello, World!");
+    /// ```
+    pub fn flatten_iter(&self) -> impl Iterator<Item = &str> + '_ {
+        std::iter::once(self)
+            .chain(self.descendants())
+            .map(|comp| comp.contents.flatten_str())
+    }
+}
+
+impl IntoIterator for Component {
+    type Item = Component;
+    type IntoIter = std::vec::IntoIter<Component>;
+
+    /// Consumes this component, iterating over its direct children
+    fn into_iter(self) -> Self::IntoIter {
+        self.extra.unwrap_or_default().into_iter()
+    }
+}
+
+/// Collects the components as the children of an empty text component without any style,
+/// same as converting a vector of the components with [`From`]
+impl FromIterator<Component> for Component {
+    fn from_iter<I: IntoIterator<Item = Component>>(iter: I) -> Self {
+        Component::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a> IntoIterator for &'a Component {
+    type Item = &'a Component;
+    type IntoIter = std::slice::Iter<'a, Component>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.children()
+    }
+}
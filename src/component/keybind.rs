@@ -0,0 +1,80 @@
+//! Validation of keybind identifiers against the vanilla keybinds
+
+use crate::component::Component;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Identifiers of all the keybinds of vanilla Minecraft, see [`Component::try_keybind()`]
+pub const VANILLA_KEYBINDS: &[&str] = &[
+    "key.advancements",
+    "key.attack",
+    "key.back",
+    "key.chat",
+    "key.command",
+    "key.drop",
+    "key.forward",
+    "key.fullscreen",
+    "key.hotbar.1",
+    "key.hotbar.2",
+    "key.hotbar.3",
+    "key.hotbar.4",
+    "key.hotbar.5",
+    "key.hotbar.6",
+    "key.hotbar.7",
+    "key.hotbar.8",
+    "key.hotbar.9",
+    "key.inventory",
+    "key.jump",
+    "key.left",
+    "key.loadToolbarActivator",
+    "key.pickItem",
+    "key.playerlist",
+    "key.right",
+    "key.saveToolbarActivator",
+    "key.screenshot",
+    "key.smoothCamera",
+    "key.sneak",
+    "key.socialInteractions",
+    "key.spectatorOutlines",
+    "key.sprint",
+    "key.swapOffhand",
+    "key.togglePerspective",
+    "key.use",
+];
+
+/// An error returned when a keybind identifier is not one of the [`VANILLA_KEYBINDS`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKeybind(pub(crate) String);
+
+impl Display for UnknownKeybind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unknown keybind '{}', expected a vanilla keybind like `key.jump`",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownKeybind {}
+
+impl Component {
+    /// Constructs a new keybind component, validating that the key is one of the
+    /// [`VANILLA_KEYBINDS`], as the client displays unknown keys as they are.
+    ///
+    /// See [`Self::keybind()`] for the unvalidated variant, e.g. for keybinds added by mods.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// assert!(Component::try_keybind("key.jump").is_ok());
+    /// assert!(Component::try_keybind("key.jmup").is_err());
+    /// ```
+    pub fn try_keybind<S: Into<String>>(key: S) -> Result<Self, UnknownKeybind> {
+        let key = key.into();
+        match VANILLA_KEYBINDS.contains(&key.as_str()) {
+            true => Ok(Self::keybind(key)),
+            false => Err(UnknownKeybind(key)),
+        }
+    }
+}
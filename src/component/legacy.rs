@@ -0,0 +1,240 @@
+//! Conversion between components and legacy strings formatted with `§` codes
+
+use crate::component::{Component, Formatting, NamedColor, ResolvedStyle, TextColor};
+
+/// The character prefixing legacy codes in vanilla Minecraft
+pub const SECTION_SIGN: char = '§';
+
+/// Formattings that can be toggled with legacy codes, in the order they are written
+const FORMATS: [Formatting; 5] = [
+    Formatting::Obfuscated,
+    Formatting::Bold,
+    Formatting::Strikethrough,
+    Formatting::Underline,
+    Formatting::Italic,
+];
+
+/// Style of a legacy text run
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LegacyStyle {
+    color: Option<TextColor>,
+    formats: [bool; 5],
+}
+
+impl LegacyStyle {
+    fn resolved(style: ResolvedStyle) -> LegacyStyle {
+        LegacyStyle {
+            color: style.color,
+            formats: [
+                style.obfuscated,
+                style.bold,
+                style.strikethrough,
+                style.underlined,
+                style.italic,
+            ],
+        }
+    }
+
+    fn apply(&self, text: String) -> Component {
+        let mut comp = Component::text(text);
+        comp.color = self.color.clone();
+        for (fmt, _) in FORMATS.iter().zip(self.formats).filter(|(_, on)| *on) {
+            comp.formatted(*fmt, Some(true));
+        }
+        comp
+    }
+}
+
+impl Component {
+    /// Parses a legacy string, where colors and formatting are prefixed with the `code` character,
+    /// which is usually either [SECTION_SIGN] or `&`.
+    ///
+    /// Same as in vanilla, color codes reset the formatting, and `r` resets both.
+    /// BungeeCord hex colors in the `§x§r§r§g§g§b§b` format are supported as well.
+    /// The code character is kept as literal text along with the character after it
+    /// if they do not form a known code, e.g. in `Tom & Jerry`.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let mut legacy = Component::from_legacy("&cHello, &lWorld!", '&');
+    /// assert_eq!(legacy.flatten(), "Hello, World!");
+    /// ```
+    pub fn from_legacy(text: &str, code: char) -> Component {
+        let mut root = Component::default();
+        let mut style = LegacyStyle::default();
+        let mut buf = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c != code {
+                buf.push(c);
+                continue;
+            }
+            let Some(next) = chars.clone().next() else {
+                buf.push(c);
+                continue;
+            };
+            let mut next_style = style.clone();
+            if next.eq_ignore_ascii_case(&'x') {
+                let mut ahead = chars.clone();
+                ahead.next();
+                match read_bungee_hex(&mut ahead, code) {
+                    Some(hex) => {
+                        chars = ahead;
+                        next_style = LegacyStyle {
+                            color: Some(hex.into()),
+                            ..Default::default()
+                        };
+                    }
+                    None => {
+                        buf.push(c);
+                        continue;
+                    }
+                }
+            } else if let Some(color) = NamedColor::from_legacy_code(next) {
+                chars.next();
+                next_style = LegacyStyle {
+                    color: Some(color.into()),
+                    ..Default::default()
+                };
+            } else if let Some(fmt) = Formatting::from_legacy_code(next) {
+                chars.next();
+                match FORMATS.iter().position(|it| *it == fmt) {
+                    Some(idx) => next_style.formats[idx] = true,
+                    // reset
+                    None => next_style = LegacyStyle::default(),
+                }
+            } else {
+                buf.push(c);
+                continue;
+            }
+            if next_style != style && !buf.is_empty() {
+                root.push_extra(style.apply(std::mem::take(&mut buf)));
+            }
+            style = next_style;
+        }
+        if !buf.is_empty() {
+            root.push_extra(style.apply(buf));
+        }
+        root
+    }
+
+    /// Converts this component into a legacy string, where colors and formatting are
+    /// prefixed with the `code` character, which is usually either [SECTION_SIGN] or `&`.
+    ///
+    /// Legacy strings only support named colors, so hex colors are replaced with the
+    /// closest named color. See [`Self::to_legacy_bungee_hex()`] to keep them instead.
+    /// Events and non-text contents are lost in the conversion.
+    pub fn to_legacy(&self, code: char) -> String {
+        write_legacy(self, code, false)
+    }
+
+    /// Same as [`Self::to_legacy()`], but writes hex colors in the BungeeCord
+    /// `§x§r§r§g§g§b§b` format, which is supported by BungeeCord and Spigot servers.
+    pub fn to_legacy_bungee_hex(&self, code: char) -> String {
+        write_legacy(self, code, true)
+    }
+
+    /// Converts this component into a legacy string for the `description` of a server list
+    /// status response, also known as the MOTD.
+    ///
+    /// Only the first two lines are kept, as the server list does not display any more,
+    /// and they are separated by a single `\n`. Same as in [`Self::to_legacy()`], hex colors
+    /// are replaced with the closest named color, so that older clients display them,
+    /// and events are dropped, as the server list does not support them.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let motd = Component::colored("Lobster\n", NamedColor::Gold).append("Welcome!\nIgnored");
+    /// assert_eq!(motd.to_motd(), "§6Lobster\n§6Welcome!");
+    /// ```
+    pub fn to_motd(&self) -> String {
+        let mut lines = self.lines().into_iter().take(2);
+        let first = lines.next().unwrap_or_default();
+        let mut motd = first.to_legacy(SECTION_SIGN);
+        if let Some(second) = lines.next() {
+            motd.push('\n');
+            let styled = |style: Option<ResolvedStyle>| {
+                style.is_some_and(|style| LegacyStyle::resolved(style) != LegacyStyle::default())
+            };
+            // the style of the first line carries over to the second one in legacy strings
+            if styled(first.runs().last().map(|(_, style)| style))
+                && !styled(second.runs().next().map(|(_, style)| style))
+            {
+                motd.push(SECTION_SIGN);
+                motd.push(Formatting::Reset.to_legacy_code());
+            }
+            motd.push_str(&second.to_legacy(SECTION_SIGN));
+        }
+        motd
+    }
+}
+
+/// Reads the `§r§r§g§g§b§b` part of a BungeeCord hex color
+pub(crate) fn read_bungee_hex(chars: &mut std::str::Chars, code: char) -> Option<u32> {
+    let mut hex = 0;
+    for _ in 0..6 {
+        if chars.next()? != code {
+            return None;
+        }
+        hex = hex << 4 | chars.next()?.to_digit(16)?;
+    }
+    Some(hex)
+}
+
+fn write_legacy(comp: &Component, code: char, bungee_hex: bool) -> String {
+    let mut buf = String::new();
+    let mut current = LegacyStyle::default();
+    for (text, style) in comp.runs() {
+        let style = LegacyStyle::resolved(style);
+        if style != current {
+            write_style(&style, &current, code, bungee_hex, &mut buf);
+            current = style;
+        }
+        buf.push_str(&text);
+    }
+    buf
+}
+
+/// Writes the codes needed to change from the current style to the provided one
+fn write_style(
+    style: &LegacyStyle,
+    current: &LegacyStyle,
+    code: char,
+    bungee_hex: bool,
+    buf: &mut String,
+) {
+    let additive = style.color == current.color
+        && current
+            .formats
+            .iter()
+            .zip(style.formats)
+            .all(|(was, is)| !was || is);
+    if !additive {
+        // both color codes and resets clear the formatting
+        buf.push(code);
+        match &style.color {
+            None => buf.push(Formatting::Reset.to_legacy_code()),
+            Some(TextColor::Named(named)) => buf.push(named.to_legacy_code()),
+            Some(TextColor::Hex(hex)) => {
+                let rgb = u32::from_str_radix(hex.trim_start_matches('#'), 16).unwrap_or(0xFFFFFF);
+                if bungee_hex {
+                    buf.push('x');
+                    for digit in format!("{:06x}", rgb).chars() {
+                        buf.push(code);
+                        buf.push(digit);
+                    }
+                } else {
+                    buf.push(NamedColor::nearest(rgb).to_legacy_code());
+                }
+            }
+        }
+    }
+    for (idx, fmt) in FORMATS.iter().enumerate() {
+        if style.formats[idx] && (!additive || !current.formats[idx]) {
+            buf.push(code);
+            buf.push(fmt.to_legacy_code());
+        }
+    }
+}
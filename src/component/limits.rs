@@ -0,0 +1,135 @@
+//! Restructuring of component trees to fit into the nesting limits of the client
+
+use crate::component::{Component, HoverEvent, MessageContents, ResolvedStyle};
+
+impl Component {
+    /// Restructures this component so that no component in the tree has more than
+    /// `max_children` children, and the tree is at most `max_depth` deep, as the client
+    /// disconnects when receiving components nested too deeply. The depth is measured the
+    /// same way as in [`Self::depth()`], and the text of `show_text` hover events is
+    /// restructured as well. A component already within the limits is returned as it is.
+    ///
+    /// Otherwise, the tree is flattened into its contents in display order, each with the
+    /// style it was displayed with, and adjacent text with the same style is merged. The
+    /// contents are then grouped under empty text components, as few levels deep as possible,
+    /// so that the result is displayed the same way, see [`Self::semantically_eq()`].
+    ///
+    /// If there are too many differently styled contents to fit into the limits, adjacent
+    /// contents are merged into plain text with the style of the first one of them, keeping all
+    /// the text but losing some of the styling, and hover events that can not fit are dropped.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor};
+    ///
+    /// let mut nested = Component::text("!");
+    /// for color in [NamedColor::Red, NamedColor::Gold, NamedColor::Yellow] {
+    ///     nested = Component::colored("a", color).append(nested);
+    /// }
+    /// assert_eq!(nested.depth(), 4);
+    /// let flat = nested.enforce_limits(4, 2);
+    /// assert_eq!(flat.depth(), 2);
+    /// assert!(flat.semantically_eq(&nested));
+    /// ```
+    pub fn enforce_limits(&self, max_children: usize, max_depth: usize) -> Component {
+        let max_depth = max_depth.max(1);
+        if self.depth() <= max_depth && self.max_children() <= max_children {
+            return self.clone();
+        }
+        let mut parts: Vec<(ResolvedStyle, MessageContents)> = Vec::new();
+        let mut pending = vec![(self, ResolvedStyle::default())];
+        while let Some((comp, parent)) = pending.pop() {
+            let style = parent.inherit(comp);
+            pending.extend(
+                comp.extra
+                    .iter()
+                    .flatten()
+                    .rev()
+                    .map(|child| (child, style.clone())),
+            );
+            match (&comp.contents, parts.last_mut()) {
+                (MessageContents::Plain { text }, _) if text.is_empty() => {}
+                (
+                    MessageContents::Plain { text },
+                    Some((last_style, MessageContents::Plain { text: last })),
+                ) if *last_style == style => last.push_str(text),
+                (contents, _) => parts.push((style, contents.clone())),
+            }
+        }
+
+        // the fewest levels of empty components the contents have to be grouped under
+        let capacity = |levels: usize| {
+            (0..levels).fold(1usize, |capacity, _| capacity.saturating_mul(max_children))
+        };
+        // more levels only add room for more contents with several children per component
+        let levels = (0..max_depth)
+            .find(|levels| capacity(*levels) >= parts.len())
+            .unwrap_or(if max_children > 1 { max_depth - 1 } else { 0 });
+        let parts = merge_parts(parts, capacity(levels));
+
+        let mut nodes = parts
+            .into_iter()
+            .map(|(style, contents)| {
+                let mut part = style.apply(Component {
+                    contents,
+                    ..Default::default()
+                });
+                // the hover text is nested in the part, below all the levels above it
+                let hover_depth = max_depth - levels - 1;
+                part.hover_event = match part.hover_event.take() {
+                    Some(HoverEvent::ShowText { .. }) if hover_depth == 0 => None,
+                    Some(HoverEvent::ShowText { contents }) => Some(HoverEvent::ShowText {
+                        contents: Box::new(contents.enforce_limits(max_children, hover_depth)),
+                    }),
+                    other => other,
+                };
+                part
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..levels {
+            nodes = nodes
+                .chunks(max_children)
+                .map(|chunk| Component::default().with_extra_ref(chunk))
+                .collect();
+        }
+        nodes.pop().unwrap_or_default()
+    }
+
+    /// Gets the maximum amount of children of any component in this tree,
+    /// including the text of `show_text` hover events
+    fn max_children(&self) -> usize {
+        let children = self.extra.iter().flatten();
+        let hover = match &self.hover_event {
+            Some(HoverEvent::ShowText { contents }) => contents.max_children(),
+            _ => 0,
+        };
+        children
+            .clone()
+            .map(Component::max_children)
+            .fold(children.count().max(hover), usize::max)
+    }
+}
+
+/// Merges adjacent parts into plain text, so that there are at most `max_parts` of them.
+/// Every merged part keeps the style of the first part merged into it.
+fn merge_parts(
+    parts: Vec<(ResolvedStyle, MessageContents)>,
+    max_parts: usize,
+) -> Vec<(ResolvedStyle, MessageContents)> {
+    if parts.len() <= max_parts {
+        return parts;
+    }
+    let per_part = parts.len().div_ceil(max_parts.max(1));
+    parts
+        .chunks(per_part)
+        .map(|chunk| {
+            if let [part] = chunk {
+                return part.clone();
+            }
+            let text = chunk
+                .iter()
+                .map(|(_, contents)| contents.flatten_str())
+                .collect::<String>();
+            (chunk[0].0.clone(), MessageContents::Plain { text })
+        })
+        .collect()
+}
@@ -0,0 +1,126 @@
+//! Estimation of the display width of components, e.g. for aligning text in GUIs
+
+use crate::component::Component;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// Widths of the glyphs of the vanilla default font, in pixels, without the 1 pixel spacing
+/// after every glyph. Characters not listed here are 5 pixels wide.
+const VANILLA_WIDTHS: &[(&str, u32)] = &[
+    ("!',.:;i|", 1),
+    ("`l", 2),
+    ("\"I[] ", 3),
+    ("()*<>fkt{}", 4),
+    ("@~", 6),
+];
+
+/// Widths of the characters of a font, used by [`Component::display_width()`].
+///
+/// Widths are in arbitrary units, e.g. pixels for the vanilla font, or cells for a monospace
+/// font, where [`FontMetrics::new(1)`](FontMetrics::new) counts every character as one cell.
+/// Widths of single characters take precedence over the widths of ranges, and later ranges
+/// take precedence over earlier ones.
+///
+/// ```rust
+/// use lobsterchat::component::FontMetrics;
+///
+/// let metrics = FontMetrics::new(2).range('\u{4E00}'..='\u{9FFF}', 4).width('.', 1);
+/// assert_eq!(metrics.char_width('a'), 2);
+/// assert_eq!(metrics.char_width('中'), 4);
+/// assert_eq!(metrics.char_width('.'), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontMetrics {
+    default_width: u32,
+    widths: HashMap<char, u32>,
+    ranges: Vec<(RangeInclusive<char>, u32)>,
+    bold_extra: u32,
+}
+
+impl FontMetrics {
+    /// Constructs new metrics where every character has the provided width
+    pub fn new(default_width: u32) -> Self {
+        Self {
+            default_width,
+            widths: HashMap::new(),
+            ranges: Vec::new(),
+            bold_extra: 0,
+        }
+    }
+
+    /// Metrics of the vanilla default font in pixels, including the 1 pixel spacing after
+    /// every glyph. Bold characters are 1 pixel wider, and obfuscated characters keep their
+    /// width, as vanilla only replaces them with random characters of the same width.
+    ///
+    /// Characters outside of ASCII are assumed to be as wide as most letters.
+    pub fn vanilla() -> Self {
+        let mut metrics = Self::new(6).bold_extra(1);
+        for (chars, width) in VANILLA_WIDTHS {
+            for c in chars.chars() {
+                metrics = metrics.width(c, width + 1);
+            }
+        }
+        metrics
+    }
+
+    /// Sets the width of a single character
+    pub fn width(mut self, c: char, width: u32) -> Self {
+        self.widths.insert(c, width);
+        self
+    }
+
+    /// Sets the width of all the characters in the range
+    pub fn range(mut self, range: RangeInclusive<char>, width: u32) -> Self {
+        self.ranges.push((range, width));
+        self
+    }
+
+    /// Sets the width added to every bold character
+    pub fn bold_extra(mut self, bold_extra: u32) -> Self {
+        self.bold_extra = bold_extra;
+        self
+    }
+
+    /// Gets the width of the character when it is not bold
+    pub fn char_width(&self, c: char) -> u32 {
+        if let Some(width) = self.widths.get(&c) {
+            return *width;
+        }
+        self.ranges
+            .iter()
+            .rev()
+            .find(|(range, _)| range.contains(&c))
+            .map_or(self.default_width, |(_, width)| *width)
+    }
+
+    /// Gets the width of the text, with every character made wider if it is bold
+    pub fn text_width(&self, text: &str, bold: bool) -> u32 {
+        let extra = if bold { self.bold_extra } else { 0 };
+        text.chars().map(|c| self.char_width(c) + extra).sum()
+    }
+}
+
+impl Default for FontMetrics {
+    /// Same as [`FontMetrics::vanilla()`]
+    fn default() -> Self {
+        Self::vanilla()
+    }
+}
+
+impl Component {
+    /// Estimates the width of the text of this component when displayed on a single line,
+    /// taking bold text into account. Obfuscated text has the same width as the original text.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Component, FontMetrics};
+    ///
+    /// let metrics = FontMetrics::vanilla();
+    /// assert_eq!(Component::text("Hi!").display_width(&metrics), 6 + 2 + 2);
+    /// assert_eq!(Component::text("Hi!").bold(true).display_width(&metrics), 13);
+    /// ```
+    pub fn display_width(&self, metrics: &FontMetrics) -> u32 {
+        self.runs()
+            .map(|(text, style)| metrics.text_width(&text, style.bold))
+            .sum()
+    }
+}
@@ -0,0 +1,209 @@
+//! Serialization of components into minimessage strings
+
+use crate::component::{ClickEvent, NbtMessage};
+use crate::component::{Component, HoverEvent, MessageContents, ResolvedStyle, TextColor};
+
+impl Component {
+    /// Serializes this component into a minimessage string, which is parsed back into a
+    /// component displayed the same way, see [`Component::semantically_eq()`], as long as
+    /// its text does not contain anything that looks like a tag, see below.
+    ///
+    /// Colors, formatting, click and hover events and insertions become tags, with their
+    /// arguments quoted and escaped, so that they may contain any characters. Several of them
+    /// set on the same component become nested tags. Translated, score, selector and NBT
+    /// contents become their tags as well, while keybinds are written as their key, as
+    /// minimessage has no tag for them. Fonts, shadow colors, the NBT data of hover items and
+    /// legacy achievement hover events can not be written in minimessage at all, so they are left out.
+    ///
+    /// Tags are never closed explicitly, instead a `<reset>` tag is written whenever a style
+    /// ends, and the styles that are still in effect are opened again after it.
+    /// Angle brackets in the text are written as is, as minimessage can not escape them, so
+    /// text such as `use <red> tag` is parsed back as a tag and is not displayed the same way.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{ClickEvent, Colored, Component, NamedColor};
+    ///
+    /// let comp = Component::text("Hello, ")
+    ///     .color(NamedColor::Gold)
+    ///     .append(Component::text("World").click_event(ClickEvent::run_command("/say hi")))
+    ///     .append(Component::text("!").color(NamedColor::Red));
+    /// assert_eq!(
+    ///     comp.to_minimessage(),
+    ///     "<gold>Hello, <click:run_command:'/say hi'>World<reset><red>!"
+    /// );
+    /// ```
+    pub fn to_minimessage(&self) -> String {
+        let mut buf = String::new();
+        write(self, &ResolvedStyle::default(), &mut Vec::new(), &mut buf);
+        buf
+    }
+}
+
+/// Writes the component and its children, where `open` are the tags currently in effect
+fn write(comp: &Component, parent: &ResolvedStyle, open: &mut Vec<String>, buf: &mut String) {
+    let style = parent.inherit(comp);
+    let contents = contents(&comp.contents);
+    if !contents.is_empty() {
+        let tags = tags(&style);
+        if !tags.starts_with(open) {
+            buf.push_str("<reset>");
+            open.clear();
+        }
+        for tag in &tags[open.len()..] {
+            buf.push_str(tag);
+        }
+        *open = tags;
+        buf.push_str(&contents);
+    }
+    for child in comp.extra.iter().flatten() {
+        write(child, &style, open, buf);
+    }
+}
+
+/// Gets the tags opening the style, from the outermost to the innermost one
+fn tags(style: &ResolvedStyle) -> Vec<String> {
+    let mut tags = Vec::new();
+    match &style.color {
+        Some(TextColor::Named(named)) => tags.push(format!("<{}>", named)),
+        Some(hex) => {
+            // malformed hex colors can not be written as a tag
+            if let Some(rgb) = hex.to_rgb() {
+                tags.push(format!("<#{:06X}>", rgb));
+            }
+        }
+        None => {}
+    }
+    let formatting = [
+        (style.bold, "<bold>"),
+        (style.italic, "<italic>"),
+        (style.underlined, "<underline>"),
+        (style.strikethrough, "<strikethrough>"),
+        (style.obfuscated, "<obfuscated>"),
+    ];
+    for (_, tag) in formatting.iter().filter(|(enabled, _)| *enabled) {
+        tags.push(tag.to_string());
+    }
+    if let Some(insertion) = &style.insertion {
+        tags.push(format!("<insert:{}>", quote(insertion)));
+    }
+    if let Some(click) = &style.click_event {
+        let (action, value) = match click {
+            ClickEvent::OpenUrl(url) => ("open_url", url),
+            ClickEvent::RunCommand(command) => ("run_command", command),
+            ClickEvent::SuggestCommand(command) => ("suggest_command", command),
+            ClickEvent::ChangePage(page) => ("change_page", page),
+            ClickEvent::CopyToClipboard(text) => ("copy_to_clipboard", text),
+        };
+        tags.push(format!("<click:{}:{}>", action, quote(value)));
+    }
+    if let Some(hover) = style.hover_event.as_ref().and_then(hover_tag) {
+        tags.push(hover);
+    }
+    tags
+}
+
+/// Gets the `<hover>` tag of the hover event, or [None] if it can not be written as a tag
+fn hover_tag(hover: &HoverEvent) -> Option<String> {
+    let tag = match hover {
+        HoverEvent::ShowText { contents } => {
+            format!("<hover:show_text:{}>", quote(&contents.to_minimessage()))
+        }
+        HoverEvent::ShowItem { contents: item } => match item.count {
+            Some(count) => format!("<hover:show_item:{}:{}>", quote(&item.id), count),
+            None => format!("<hover:show_item:{}>", quote(&item.id)),
+        },
+        HoverEvent::ShowEntity { contents: entity } => {
+            let mut tag = format!(
+                "<hover:show_entity:{}:{}",
+                quote(&entity.entity_type),
+                entity.id
+            );
+            if let Some(name) = &entity.name {
+                tag.push(':');
+                tag.push_str(&quote(&name.to_minimessage()));
+            }
+            tag.push('>');
+            tag
+        }
+        HoverEvent::ShowAchievement { .. } => return None,
+    };
+    Some(tag)
+}
+
+/// Gets the text or the tag of the contents of a component
+fn contents(contents: &MessageContents) -> String {
+    match contents {
+        MessageContents::Plain { text } => text.clone(),
+        MessageContents::Translate(translated) => {
+            let mut tag = match &translated.fallback {
+                Some(fallback) => format!(
+                    "<lang_or:{}:{}",
+                    quote(&translated.translate),
+                    quote(fallback)
+                ),
+                None => format!("<lang:{}", quote(&translated.translate)),
+            };
+            for arg in translated.with.iter().flatten() {
+                tag.push(':');
+                tag.push_str(&quote(&arg.to_minimessage()));
+            }
+            tag.push('>');
+            tag
+        }
+        MessageContents::Score { score } => {
+            let mut tag = format!("<score:{}:{}", quote(&score.name), quote(&score.objective));
+            if let Some(value) = &score.value {
+                tag.push(':');
+                tag.push_str(&quote(value));
+            }
+            tag.push('>');
+            tag
+        }
+        MessageContents::Entity(entity) => match &entity.separator {
+            Some(separator) => format!(
+                "<selector:{}:{}>",
+                quote(&entity.selector),
+                quote(&separator.clone().flatten())
+            ),
+            None => format!("<selector:{}>", quote(&entity.selector)),
+        },
+        MessageContents::Nbt(nbt) => nbt_tag(nbt),
+        MessageContents::Keybind(key) => key.keybind.clone(),
+    }
+}
+
+/// Gets the `<nbt>` tag of the NBT contents
+fn nbt_tag(nbt: &NbtMessage) -> String {
+    let (kind, source) = match (&nbt.block, &nbt.entity, &nbt.storage) {
+        (Some(block), _, _) => ("block", block),
+        (_, Some(entity), _) => ("entity", entity),
+        (_, _, Some(storage)) => ("storage", storage),
+        // without a source, the contents are not displayed at all
+        _ => return String::new(),
+    };
+    let interpret = match nbt.interpret {
+        Some(true) => ":interpret",
+        _ => "",
+    };
+    format!(
+        "<nbt:{}:{}:{}{}>",
+        kind,
+        quote(source),
+        quote(&nbt.nbt),
+        interpret
+    )
+}
+
+/// Quotes a tag argument in single quotes, escaping the quotes and backslashes inside of it
+fn quote(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('\'');
+    for c in arg.chars() {
+        if c == '\'' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
@@ -0,0 +1,91 @@
+//! Namespaced identifiers, also known as resource locations
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The namespace used when an identifier does not specify one
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+/// An error returned when an identifier is not a valid resource location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidIdentifier(pub(crate) String);
+
+impl Display for InvalidIdentifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid identifier '{}', expected `namespace:path`",
+            self.0
+        )
+    }
+}
+
+impl Error for InvalidIdentifier {}
+
+/// A namespaced identifier in the `namespace:path` format, e.g. `minecraft:stone`.
+///
+/// The namespace may only contain `[a-z0-9_.-]` characters, and the path
+/// may additionally contain `/`. The namespace defaults to `minecraft` when omitted.
+///
+/// ```rust
+/// use lobsterchat::component::ResourceLocation;
+///
+/// let stone: ResourceLocation = "stone".parse().unwrap();
+/// assert_eq!(stone.to_string(), "minecraft:stone");
+/// assert!("Dirt Block".parse::<ResourceLocation>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceLocation {
+    namespace: String,
+    path: String,
+}
+
+impl ResourceLocation {
+    /// Constructs a new resource location, validating both of its parts
+    pub fn new<N, P>(namespace: N, path: P) -> Result<Self, InvalidIdentifier>
+    where
+        N: Into<String>,
+        P: Into<String>,
+    {
+        let namespace = namespace.into();
+        let path = path.into();
+        let valid = !namespace.is_empty()
+            && !path.is_empty()
+            && namespace.chars().all(is_namespace_char)
+            && path.chars().all(|c| is_namespace_char(c) || c == '/');
+        match valid {
+            true => Ok(Self { namespace, path }),
+            false => Err(InvalidIdentifier(format!("{}:{}", namespace, path))),
+        }
+    }
+
+    /// Gets the namespace of this resource location
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Gets the path of this resource location
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+fn is_namespace_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '_' | '.' | '-')
+}
+
+impl FromStr for ResourceLocation {
+    type Err = InvalidIdentifier;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, path) = s.split_once(':').unwrap_or((DEFAULT_NAMESPACE, s));
+        Self::new(namespace, path).map_err(|_| InvalidIdentifier(s.to_string()))
+    }
+}
+
+impl Display for ResourceLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
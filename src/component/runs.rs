@@ -0,0 +1,185 @@
+//! Iteration over the styled text runs of a component tree
+
+use crate::component::{ClickEvent, Component, HoverEvent, MessageContents, TextColor};
+
+/// The effective style of a text run, with everything inherited from its ancestors applied
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ResolvedStyle {
+    /// Color of the text, or `None` if the default color is used
+    pub color: Option<TextColor>,
+    /// Whether the text is bold
+    pub bold: bool,
+    /// Whether the text is italic
+    pub italic: bool,
+    /// Whether the text is obfuscated
+    pub obfuscated: bool,
+    /// Whether the text is struck through
+    pub strikethrough: bool,
+    /// Whether the text is underlined
+    pub underlined: bool,
+    /// Resource location of the font of the text, or `None` if the default font is used
+    pub font: Option<String>,
+    /// Color of the text shadow in the `0xAARRGGBB` format, or `None` if the default shadow is used
+    pub shadow_color: Option<u32>,
+    /// Text inserted into the chat input when the text is shift-clicked
+    pub insertion: Option<String>,
+    /// Event triggered when the text is clicked
+    pub click_event: Option<ClickEvent>,
+    /// Event triggered when the text is hovered over
+    pub hover_event: Option<HoverEvent>,
+}
+
+impl ResolvedStyle {
+    /// Resolves the style of the component, inheriting anything it does not set from this style
+    pub(crate) fn inherit(&self, comp: &Component) -> ResolvedStyle {
+        ResolvedStyle {
+            color: comp.color.clone().or_else(|| self.color.clone()),
+            bold: comp.bold.unwrap_or(self.bold),
+            italic: comp.italic.unwrap_or(self.italic),
+            obfuscated: comp.obfuscated.unwrap_or(self.obfuscated),
+            strikethrough: comp.strikethrough.unwrap_or(self.strikethrough),
+            underlined: comp.underlined.unwrap_or(self.underlined),
+            font: comp.font.clone().or_else(|| self.font.clone()),
+            shadow_color: comp.shadow_color.or(self.shadow_color),
+            insertion: comp.insertion.clone().or_else(|| self.insertion.clone()),
+            click_event: comp
+                .click_event
+                .clone()
+                .or_else(|| self.click_event.clone()),
+            hover_event: comp
+                .hover_event
+                .clone()
+                .or_else(|| self.hover_event.clone()),
+        }
+    }
+
+    /// Applies this style to a component without any style of its own,
+    /// leaving everything that is not set by this style unset in the component as well
+    pub(crate) fn apply(&self, mut comp: Component) -> Component {
+        let flag = |enabled: bool| Some(true).filter(|_| enabled);
+        comp.color = self.color.clone();
+        comp.bold = flag(self.bold);
+        comp.italic = flag(self.italic);
+        comp.obfuscated = flag(self.obfuscated);
+        comp.strikethrough = flag(self.strikethrough);
+        comp.underlined = flag(self.underlined);
+        comp.font = self.font.clone();
+        comp.shadow_color = self.shadow_color;
+        comp.insertion = self.insertion.clone();
+        comp.click_event = self.click_event.clone();
+        comp.hover_event = self.hover_event.clone();
+        comp
+    }
+}
+
+/// Iterator over the contents of every component in a tree in display order,
+/// along with their effective style, see [`Component::styled_contents()`]
+#[derive(Debug, Clone)]
+struct StyledContents<'a> {
+    /// Components that are yet to be visited, along with the style of their parent
+    pending: Vec<(&'a Component, ResolvedStyle)>,
+}
+
+impl<'a> Iterator for StyledContents<'a> {
+    type Item = (&'a MessageContents, ResolvedStyle);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (comp, parent) = self.pending.pop()?;
+        let style = parent.inherit(comp);
+        self.pending.extend(
+            comp.extra
+                .iter()
+                .flatten()
+                .rev()
+                .map(|child| (child, style.clone())),
+        );
+        Some((&comp.contents, style))
+    }
+}
+
+impl Component {
+    /// Iterates over the text of this component and all of its children in display order,
+    /// along with the effective style of each text run.
+    ///
+    /// Components without any text are skipped, and non-text contents
+    /// are approximated the same way as in [`Self::flatten()`].
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, NamedColor, TextColor};
+    ///
+    /// let comp = Component::text("Hello, ")
+    ///     .color(NamedColor::Red)
+    ///     .append(Component::text("World!").bold(true));
+    /// let runs = comp.runs().collect::<Vec<_>>();
+    /// assert_eq!(runs[1].0, "World!");
+    /// assert_eq!(runs[1].1.color, Some(TextColor::Named(NamedColor::Red)));
+    /// assert!(runs[1].1.bold);
+    /// ```
+    pub fn runs(&self) -> impl Iterator<Item = (String, ResolvedStyle)> + '_ {
+        self.styled_contents()
+            .map(|(contents, style)| (contents.flatten(), style))
+            .filter(|(text, _)| !text.is_empty())
+    }
+
+    /// Iterates over the contents of this component and all of its children in display order,
+    /// along with their effective style, including the components without any text
+    pub(crate) fn styled_contents(
+        &self,
+    ) -> impl Iterator<Item = (&MessageContents, ResolvedStyle)> + '_ {
+        StyledContents {
+            pending: vec![(self, ResolvedStyle::default())],
+        }
+    }
+
+    /// Splits this component into lines at the `\n` characters in its text, e.g. for
+    /// the rows of a sign. The newline characters themselves are removed.
+    ///
+    /// Every line is an empty text component, with a child for each part of the line,
+    /// styled the same way as it was displayed in this component. Same as [`str::split`],
+    /// a trailing newline results in a trailing empty line, and a component without
+    /// any newlines results in a single line. Non-text contents are never split.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    ///
+    /// let lines = Component::text("Hello\n").append("World").lines();
+    /// assert_eq!(lines.len(), 2);
+    /// assert_eq!(lines[1], Component::default().append("World"));
+    /// ```
+    pub fn lines(&self) -> Vec<Component> {
+        let mut lines = vec![Component::default()];
+        let mut pending = vec![(self, ResolvedStyle::default())];
+        while let Some((comp, parent)) = pending.pop() {
+            let style = parent.inherit(comp);
+            pending.extend(
+                comp.extra
+                    .iter()
+                    .flatten()
+                    .rev()
+                    .map(|child| (child, style.clone())),
+            );
+            match &comp.contents {
+                MessageContents::Plain { text } => {
+                    for (idx, part) in text.split('\n').enumerate() {
+                        if idx > 0 {
+                            lines.push(Component::default());
+                        }
+                        if !part.is_empty() {
+                            let line = lines.last_mut().expect("there is always a line");
+                            line.push_extra(style.apply(Component::text(part)));
+                        }
+                    }
+                }
+                other => {
+                    let part = Component {
+                        contents: other.clone(),
+                        ..Default::default()
+                    };
+                    let line = lines.last_mut().expect("there is always a line");
+                    line.push_extra(style.apply(part));
+                }
+            }
+        }
+        lines
+    }
+}
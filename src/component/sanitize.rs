@@ -0,0 +1,148 @@
+//! Neutralization of untrusted components, e.g. when relaying chat from other platforms
+
+use crate::component::{Component, HoverEvent, MessageContents};
+
+/// Features of a component that survive [`Component::sanitize()`].
+/// Every feature whose flag is `false` is removed from the whole tree.
+///
+/// ```rust
+/// use lobsterchat::component::SanitizePolicy;
+///
+/// let policy = SanitizePolicy {
+///     keep_colors: true,
+///     max_length: Some(100),
+///     ..SanitizePolicy::strict()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SanitizePolicy {
+    /// Whether the colors and shadow colors are kept
+    pub keep_colors: bool,
+    /// Whether bold, italic, strikethrough, underlined and reset are kept
+    pub keep_formatting: bool,
+    /// Whether obfuscated text is kept, which may hide the text from moderation
+    pub keep_obfuscated: bool,
+    /// Whether custom fonts are kept, which may render the text as arbitrary glyphs
+    pub keep_fonts: bool,
+    /// Whether the texts inserted into the chat input on shift-click are kept
+    pub keep_insertions: bool,
+    /// Whether click events are kept
+    pub keep_click_events: bool,
+    /// Whether `show_text` hover events are kept, with their text sanitized by the same
+    /// policy. Other hover events, which show items and entities, are always removed.
+    pub keep_hover_text: bool,
+    /// Whether translated, score, selector, keybind and NBT contents are kept.
+    /// Otherwise they are replaced with their approximation in [`MessageContents::flatten()`],
+    /// so that they are not resolved by the server or the client.
+    pub keep_non_text: bool,
+    /// Maximum amount of characters of text kept, see [`Component::truncate()`]
+    pub max_length: Option<usize>,
+}
+
+impl SanitizePolicy {
+    /// Keeps only the plain text, clamped to the 256 characters of a vanilla chat message
+    pub fn strict() -> Self {
+        Self {
+            keep_colors: false,
+            keep_formatting: false,
+            keep_obfuscated: false,
+            keep_fonts: false,
+            keep_insertions: false,
+            keep_click_events: false,
+            keep_hover_text: false,
+            keep_non_text: false,
+            max_length: Some(256),
+        }
+    }
+
+    /// Keeps the colors, formatting and hover text, but removes everything that can hide
+    /// the text or act on behalf of the reader, without clamping the length
+    pub fn relaxed() -> Self {
+        Self {
+            keep_colors: true,
+            keep_formatting: true,
+            keep_hover_text: true,
+            max_length: None,
+            ..Self::strict()
+        }
+    }
+}
+
+impl Default for SanitizePolicy {
+    /// Same as [`SanitizePolicy::strict()`]
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+impl Component {
+    /// Creates a copy of this component with everything the policy does not allow removed
+    /// from every node of the tree, e.g. to relay messages written by untrusted players.
+    /// The arguments of translatable contents and the separators of entity and NBT contents
+    /// are sanitized by the same policy when the contents are kept.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{ClickEvent, Colored, Component, NamedColor, SanitizePolicy};
+    ///
+    /// let hostile = Component::text("Free op!")
+    ///     .color(NamedColor::Gold)
+    ///     .obfuscated(true)
+    ///     .click_event(ClickEvent::run_command("/op @a"));
+    /// let safe = hostile.sanitize(SanitizePolicy::relaxed());
+    /// assert_eq!(safe, Component::text("Free op!").color(NamedColor::Gold));
+    /// ```
+    pub fn sanitize(&self, policy: SanitizePolicy) -> Component {
+        let mut sanitized = self.clone();
+        sanitized.sanitize_in_place(&policy);
+        match policy.max_length {
+            Some(max) => sanitized.truncate(max, ""),
+            None => sanitized,
+        }
+    }
+
+    fn sanitize_in_place(&mut self, policy: &SanitizePolicy) {
+        if !policy.keep_colors {
+            self.color = None;
+            self.shadow_color = None;
+        }
+        if !policy.keep_formatting {
+            self.bold = None;
+            self.italic = None;
+            self.strikethrough = None;
+            self.underlined = None;
+            self.reset = None;
+        }
+        if !policy.keep_obfuscated {
+            self.obfuscated = None;
+        }
+        if !policy.keep_fonts {
+            self.font = None;
+        }
+        if !policy.keep_insertions {
+            self.insertion = None;
+        }
+        if !policy.keep_click_events {
+            self.click_event = None;
+        }
+        self.hover_event = match self.hover_event.take() {
+            Some(HoverEvent::ShowText { contents }) if policy.keep_hover_text => {
+                Some(HoverEvent::ShowText {
+                    contents: Box::new(contents.sanitize(*policy)),
+                })
+            }
+            _ => None,
+        };
+        if !policy.keep_non_text && !matches!(self.contents, MessageContents::Plain { .. }) {
+            self.contents = MessageContents::Plain {
+                text: self.contents.flatten(),
+            };
+        }
+        // the arguments and separators are displayed as a part of the contents
+        for nested in self.contents.components_mut() {
+            nested.sanitize_in_place(policy);
+        }
+        for child in self.extra.iter_mut().flatten() {
+            child.sanitize_in_place(policy);
+        }
+    }
+}
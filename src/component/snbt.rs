@@ -0,0 +1,93 @@
+//! A minimal reader for [SNBT](https://minecraft.fandom.com/wiki/NBT_format#SNBT_format) compounds,
+//! as used by the legacy hover event format
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Reads the top level entries of an SNBT compound.
+///
+/// Quoted values are unescaped, while any other values, including
+/// nested compounds and lists, are kept as written in the source.
+pub(crate) fn read_compound(snbt: &str) -> Option<Vec<(String, String)>> {
+    let mut chars = snbt.char_indices().peekable();
+    skip_whitespace(&mut chars);
+    if chars.next()?.1 != '{' {
+        return None;
+    }
+    let mut entries = Vec::new();
+    loop {
+        skip_whitespace(&mut chars);
+        match chars.peek()?.1 {
+            '}' => break,
+            ',' => {
+                chars.next();
+                continue;
+            }
+            _ => {}
+        }
+        let key = read_value(snbt, &mut chars)?;
+        skip_whitespace(&mut chars);
+        if chars.next()?.1 != ':' {
+            return None;
+        }
+        skip_whitespace(&mut chars);
+        let value = read_value(snbt, &mut chars)?;
+        entries.push((key, value));
+    }
+    Some(entries)
+}
+
+fn skip_whitespace(chars: &mut Peekable<CharIndices>) {
+    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+}
+
+/// Reads a single quoted string, nested compound or list, or a bare value
+fn read_value(snbt: &str, chars: &mut Peekable<CharIndices>) -> Option<String> {
+    let (start, first) = *chars.peek()?;
+    match first {
+        '"' | '\'' => read_quoted(chars),
+        '{' | '[' => {
+            let mut depth = 0usize;
+            while let Some(&(idx, c)) = chars.peek() {
+                match c {
+                    '"' | '\'' => {
+                        read_quoted(chars)?;
+                        continue;
+                    }
+                    '{' | '[' => depth += 1,
+                    '}' | ']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            chars.next();
+                            return Some(snbt[start..=idx].to_string());
+                        }
+                    }
+                    _ => {}
+                }
+                chars.next();
+            }
+            None
+        }
+        _ => {
+            let mut end = start;
+            while let Some((idx, c)) =
+                chars.next_if(|(_, c)| !matches!(c, ',' | ':' | '}' | ']') && !c.is_whitespace())
+            {
+                end = idx + c.len_utf8();
+            }
+            Some(snbt[start..end].to_string()).filter(|it| !it.is_empty())
+        }
+    }
+}
+
+fn read_quoted(chars: &mut Peekable<CharIndices>) -> Option<String> {
+    let (_, quote) = chars.next()?;
+    let mut buf = String::new();
+    loop {
+        match chars.next()?.1 {
+            '\\' => buf.push(chars.next()?.1),
+            c if c == quote => return Some(buf),
+            c => buf.push(c),
+        }
+    }
+}
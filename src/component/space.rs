@@ -0,0 +1,74 @@
+//! Spacing of components by a number of pixels, for the alignment of chat based GUIs
+
+use crate::component::Component;
+
+/// The font with the space characters, as defined by the widely used
+/// negative space font resource pack, see [`Component::pixel_space()`]
+pub const SPACE_FONT: &str = "space:default";
+
+/// Characters of the [`SPACE_FONT`] by the pixels they advance the cursor by,
+/// from the widest to the narrowest in both directions.
+/// Negative widths move the cursor to the left.
+pub const SPACE_CHARACTERS: &[(i32, char)] = &[
+    (1024, '\u{F82F}'),
+    (512, '\u{F82E}'),
+    (256, '\u{F82D}'),
+    (128, '\u{F82C}'),
+    (64, '\u{F82B}'),
+    (32, '\u{F82A}'),
+    (16, '\u{F829}'),
+    (8, '\u{F828}'),
+    (7, '\u{F827}'),
+    (6, '\u{F826}'),
+    (5, '\u{F825}'),
+    (4, '\u{F824}'),
+    (3, '\u{F823}'),
+    (2, '\u{F822}'),
+    (1, '\u{F821}'),
+    (-1024, '\u{F80F}'),
+    (-512, '\u{F80E}'),
+    (-256, '\u{F80D}'),
+    (-128, '\u{F80C}'),
+    (-64, '\u{F80B}'),
+    (-32, '\u{F80A}'),
+    (-16, '\u{F809}'),
+    (-8, '\u{F808}'),
+    (-7, '\u{F807}'),
+    (-6, '\u{F806}'),
+    (-5, '\u{F805}'),
+    (-4, '\u{F804}'),
+    (-3, '\u{F803}'),
+    (-2, '\u{F802}'),
+    (-1, '\u{F801}'),
+];
+
+impl Component {
+    /// Constructs a text component in the [`SPACE_FONT`], which advances the cursor by the
+    /// provided amount of pixels, or moves it back for negative amounts. The space is made
+    /// of the fewest [`SPACE_CHARACTERS`] adding up to the amount, and is empty for zero.
+    ///
+    /// The space font has to be installed with a resource pack to be displayed.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Component, SPACE_FONT};
+    ///
+    /// let space = Component::pixel_space(-20);
+    /// assert_eq!(space.get_text_content().as_deref(), Some("\u{F809}\u{F804}"));
+    /// assert_eq!(space.get_font(), Some(SPACE_FONT));
+    /// ```
+    pub fn pixel_space(pixels: i32) -> Self {
+        let mut text = String::new();
+        let mut rest = pixels;
+        for (width, char) in SPACE_CHARACTERS {
+            // only using the characters moving the cursor in the same direction
+            if width.signum() != rest.signum() {
+                continue;
+            }
+            while rest / width >= 1 {
+                text.push(*char);
+                rest -= width;
+            }
+        }
+        Component::text(text).font(SPACE_FONT)
+    }
+}
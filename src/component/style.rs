@@ -0,0 +1,58 @@
+//! Partial styles that can be merged into components
+
+use crate::component::{Component, TextColor};
+
+/// A partial style of a component, where every field that is [None] is left untouched
+/// when the style is merged into a component, see [`Component::apply_style_recursive()`].
+///
+/// ```rust
+/// use lobsterchat::component::{NamedColor, Style};
+///
+/// let warning = Style {
+///     color: Some(NamedColor::Gold.into()),
+///     bold: Some(true),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// Color of the text
+    pub color: Option<TextColor>,
+    /// Whether the text is bold
+    pub bold: Option<bool>,
+    /// Whether the text is italic
+    pub italic: Option<bool>,
+    /// Whether the text is obfuscated
+    pub obfuscated: Option<bool>,
+    /// Whether the text is struck through
+    pub strikethrough: Option<bool>,
+    /// Whether the text is underlined
+    pub underlined: Option<bool>,
+    /// Resource location of the font of the text
+    pub font: Option<String>,
+    /// Color of the text shadow in the `0xAARRGGBB` format
+    pub shadow_color: Option<u32>,
+    /// Text inserted into the chat input when the text is shift-clicked
+    pub insertion: Option<String>,
+}
+
+impl Style {
+    /// Sets every field of the component that is set in this style
+    pub(crate) fn merge_into(&self, comp: &mut Component) {
+        if let Some(color) = &self.color {
+            comp.color = Some(color.clone());
+        }
+        comp.bold = self.bold.or(comp.bold);
+        comp.italic = self.italic.or(comp.italic);
+        comp.obfuscated = self.obfuscated.or(comp.obfuscated);
+        comp.strikethrough = self.strikethrough.or(comp.strikethrough);
+        comp.underlined = self.underlined.or(comp.underlined);
+        if let Some(font) = &self.font {
+            comp.font = Some(font.clone());
+        }
+        comp.shadow_color = self.shadow_color.or(comp.shadow_color);
+        if let Some(insertion) = &self.insertion {
+            comp.insertion = Some(insertion.clone());
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Conversions between components and the text of `valence_protocol`, with the `valence` feature
+
+use crate::component::{
+    ClickEvent, Component, DisplayEntityData, DisplayItemData, HoverEvent, MessageContents,
+    NamedColor, TextColor,
+};
+use std::borrow::Cow;
+use std::str::FromStr;
+use valence_protocol::text::color::{NamedColor as ValenceNamedColor, RgbColor};
+use valence_protocol::text::{
+    ClickEvent as ValenceClickEvent, Color, Font, HoverEvent as ValenceHoverEvent,
+    ScoreboardValueContent, TextContent,
+};
+use valence_protocol::{Ident, Text};
+
+/// Converts the component into valence text, dropping anything valence can not represent:
+/// * Malformed hex colors
+/// * Fonts other than `minecraft:default`, `minecraft:uniform` and `minecraft:alt`
+/// * The fallback of translatable components, the shadow color and the `reset` formatting
+/// * Page changes on click that are not numbers
+/// * Item and entity hover events with IDs that are not valid resource locations,
+///   and legacy achievement hover events
+/// * NBT contents without a source, or with a storage that is not a valid resource location,
+///   which become empty text
+///
+/// ```rust
+/// use lobsterchat::component::{Colored, Component};
+/// use valence_protocol::text::Color;
+/// use valence_protocol::Text;
+///
+/// let text = Text::from(Component::text("Hi").color(0xFFAA11).bold(true));
+/// assert_eq!(text.color, Some(Color::rgb(0xFF, 0xAA, 0x11)));
+/// assert_eq!(text.bold, Some(true));
+/// ```
+impl From<Component> for Text {
+    fn from(comp: Component) -> Self {
+        let mut text = Text::default();
+        text.content = content(comp.contents);
+        text.color = comp.color.and_then(|color| match color {
+            TextColor::Named(named) => ValenceNamedColor::try_from(named.to_string().as_str())
+                .ok()
+                .map(Color::Named),
+            hex => hex.to_rgb().map(|rgb| {
+                let [_, r, g, b] = rgb.to_be_bytes();
+                Color::rgb(r, g, b)
+            }),
+        });
+        text.font = comp.font.and_then(|font| match font.as_str() {
+            "minecraft:default" | "default" => Some(Font::Default),
+            "minecraft:uniform" | "uniform" => Some(Font::Uniform),
+            "minecraft:alt" | "alt" => Some(Font::Alt),
+            _ => None,
+        });
+        text.bold = comp.bold;
+        text.italic = comp.italic;
+        text.underlined = comp.underlined;
+        text.strikethrough = comp.strikethrough;
+        text.obfuscated = comp.obfuscated;
+        text.insertion = comp.insertion.map(Cow::Owned);
+        text.click_event = comp.click_event.and_then(|click| match click {
+            ClickEvent::OpenUrl(url) => Some(ValenceClickEvent::OpenUrl(url.into())),
+            ClickEvent::RunCommand(command) => Some(ValenceClickEvent::RunCommand(command.into())),
+            ClickEvent::SuggestCommand(command) => {
+                Some(ValenceClickEvent::SuggestCommand(command.into()))
+            }
+            ClickEvent::ChangePage(page) => page.parse().ok().map(ValenceClickEvent::ChangePage),
+            ClickEvent::CopyToClipboard(text) => {
+                Some(ValenceClickEvent::CopyToClipboard(text.into()))
+            }
+        });
+        text.hover_event = comp.hover_event.and_then(|hover| match hover {
+            HoverEvent::ShowText { contents } => {
+                Some(ValenceHoverEvent::ShowText((*contents).into()))
+            }
+            HoverEvent::ShowItem { contents: item } => Some(ValenceHoverEvent::ShowItem {
+                id: Ident::new(item.id).ok()?,
+                count: item.count,
+                tag: item.tag.unwrap_or_default().into(),
+            }),
+            HoverEvent::ShowEntity { contents: entity } => Some(ValenceHoverEvent::ShowEntity {
+                id: entity.id,
+                kind: Some(Ident::new(entity.entity_type).ok()?),
+                name: entity.name.map(Text::from),
+            }),
+            HoverEvent::ShowAchievement { .. } => None,
+        });
+        text.extra = comp.extra.into_iter().flatten().map(Text::from).collect();
+        text
+    }
+}
+
+/// Converts the contents of a component into the contents of valence text
+fn content(contents: MessageContents) -> TextContent {
+    match contents {
+        MessageContents::Plain { text } => TextContent::Text { text: text.into() },
+        MessageContents::Translate(translated) => TextContent::Translate {
+            translate: translated.translate.into(),
+            with: translated
+                .with
+                .into_iter()
+                .flatten()
+                .map(Text::from)
+                .collect(),
+        },
+        MessageContents::Score { score } => TextContent::ScoreboardValue {
+            score: ScoreboardValueContent {
+                name: score.name.into(),
+                objective: score.objective.into(),
+                value: score.value.map(Cow::Owned),
+            },
+        },
+        MessageContents::Entity(entity) => TextContent::EntityNames {
+            selector: entity.selector.into(),
+            separator: entity.separator.map(Text::from),
+        },
+        MessageContents::Keybind(key) => TextContent::Keybind {
+            keybind: key.keybind.into(),
+        },
+        MessageContents::Nbt(nbt) => {
+            let separator = nbt.separator.map(Text::from);
+            let (nbt_path, interpret) = (Cow::Owned(nbt.nbt), nbt.interpret);
+            if let Some(block) = nbt.block {
+                TextContent::BlockNbt {
+                    block: block.into(),
+                    nbt: nbt_path,
+                    interpret,
+                    separator,
+                }
+            } else if let Some(entity) = nbt.entity {
+                TextContent::EntityNbt {
+                    entity: entity.into(),
+                    nbt: nbt_path,
+                    interpret,
+                    separator,
+                }
+            } else if let Some(storage) = nbt.storage.and_then(|it| Ident::new(it).ok()) {
+                TextContent::StorageNbt {
+                    storage,
+                    nbt: nbt_path,
+                    interpret,
+                    separator,
+                }
+            } else {
+                // without a valid source, the contents are not displayed at all
+                TextContent::Text { text: "".into() }
+            }
+        }
+    }
+}
+
+/// Converts the valence text into a component, dropping anything lobster can not represent:
+/// * The `reset` color, which leaves the component uncolored
+/// * The `open_file` click event
+/// * Entity hover events without an entity type
+///
+/// RGB colors always become hex colors, even if they match a named color.
+impl From<Text> for Component {
+    fn from(mut text: Text) -> Self {
+        let text = std::mem::take(&mut *text);
+        let mut comp = match text.content {
+            TextContent::Text { text } => Component::text(text),
+            TextContent::Translate { translate, with } => {
+                let with = with.into_iter().map(Component::from).collect::<Vec<_>>();
+                Component::translate(translate, Some(with).filter(|it| !it.is_empty()))
+            }
+            TextContent::ScoreboardValue { score } => {
+                Component::score(score.name, score.objective, score.value)
+            }
+            TextContent::EntityNames {
+                selector,
+                separator,
+            } => Component::entity(selector, separator.map(Component::from)),
+            TextContent::Keybind { keybind } => Component::keybind(keybind),
+            TextContent::BlockNbt {
+                block,
+                nbt,
+                interpret,
+                separator,
+            } => Component::block_nbt(nbt, block, interpret, separator.map(Component::from)),
+            TextContent::EntityNbt {
+                entity,
+                nbt,
+                interpret,
+                separator,
+            } => Component::entity_nbt(nbt, entity, interpret, separator.map(Component::from)),
+            TextContent::StorageNbt {
+                storage,
+                nbt,
+                interpret,
+                separator,
+            } => Component::storage_nbt(
+                nbt.into_owned(),
+                storage.to_string(),
+                interpret,
+                separator.map(Component::from),
+            ),
+        };
+        comp.color = text.color.and_then(|color| match color {
+            Color::Reset => None,
+            Color::Named(named) => NamedColor::from_str(named.name())
+                .ok()
+                .map(TextColor::Named),
+            Color::Rgb(RgbColor { r, g, b }) => Some(u32::from_be_bytes([0, r, g, b]).into()),
+        });
+        comp.font = text.font.map(|font| {
+            match font {
+                Font::Default => "minecraft:default",
+                Font::Uniform => "minecraft:uniform",
+                Font::Alt => "minecraft:alt",
+            }
+            .to_string()
+        });
+        comp.bold = text.bold;
+        comp.italic = text.italic;
+        comp.underlined = text.underlined;
+        comp.strikethrough = text.strikethrough;
+        comp.obfuscated = text.obfuscated;
+        comp.insertion = text.insertion.map(Cow::into_owned);
+        comp.click_event = text.click_event.and_then(|click| match click {
+            ValenceClickEvent::OpenUrl(url) => Some(ClickEvent::OpenUrl(url.into_owned())),
+            ValenceClickEvent::OpenFile(_) => None,
+            ValenceClickEvent::RunCommand(command) => {
+                Some(ClickEvent::RunCommand(command.into_owned()))
+            }
+            ValenceClickEvent::SuggestCommand(command) => {
+                Some(ClickEvent::SuggestCommand(command.into_owned()))
+            }
+            ValenceClickEvent::ChangePage(page) => Some(ClickEvent::ChangePage(page.to_string())),
+            ValenceClickEvent::CopyToClipboard(text) => {
+                Some(ClickEvent::CopyToClipboard(text.into_owned()))
+            }
+        });
+        comp.hover_event = text.hover_event.and_then(|hover| match hover {
+            ValenceHoverEvent::ShowText(text) => Some(HoverEvent::show_text(text.into())),
+            ValenceHoverEvent::ShowItem { id, count, tag } => {
+                Some(HoverEvent::show_item(DisplayItemData {
+                    id: id.to_string(),
+                    count,
+                    tag: Some(tag.into_owned()).filter(|it| !it.is_empty()),
+                }))
+            }
+            ValenceHoverEvent::ShowEntity { id, kind, name } => {
+                Some(HoverEvent::show_entity(DisplayEntityData {
+                    name: name.map(Component::from),
+                    entity_type: kind?.to_string(),
+                    id,
+                }))
+            }
+        });
+        let extra = text
+            .extra
+            .into_iter()
+            .map(Component::from)
+            .collect::<Vec<_>>();
+        comp.with_extra(extra)
+    }
+}
@@ -0,0 +1,211 @@
+//! Serialization of components for the JSON format of older or newer Minecraft versions
+
+use crate::component::{ClickEvent, Component, HoverEvent, MessageContents, NamedColor};
+use serde_json::{Map, Value};
+
+/// A Minecraft version in which the JSON format of components changed, see
+/// [`Component::to_json_for()`]. Every version covers all the versions up to the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProtocolVersion {
+    /// Minecraft 1.8 up to 1.15, without hex colors and fonts, and with hover events
+    /// in the legacy `value` format, where items and entities are written as SNBT.
+    /// The only version in which achievement hover events are written.
+    V1_8,
+    /// Minecraft 1.16, which added hex colors, fonts and the `contents` of hover events
+    V1_16,
+    /// Minecraft 1.19.4, which added the `fallback` of translatable components
+    V1_19_4,
+    /// Minecraft 1.20.3, which sends components as NBT instead of JSON, see `to_nbt()`
+    /// with the `nbt` feature. The JSON format is the same as in 1.19.4.
+    V1_20_3,
+    /// Minecraft 1.21.4, which added the `shadow_color` of text
+    V1_21_4,
+    /// Minecraft 1.21.5, which renamed the events to `click_event` and `hover_event`,
+    /// and gave every action its own fields, e.g. an integer `page` for `change_page`
+    V1_21_5,
+}
+
+impl Component {
+    /// Serializes this component as JSON in the format of the provided Minecraft version.
+    ///
+    /// Anything the version does not support is left out, e.g. the shadow color before
+    /// 1.21.4, while hex colors are downsampled to the nearest named color before 1.16,
+    /// see [`NamedColor::nearest()`]. Unlike [`ToString::to_string()`], which always
+    /// writes the 1.21.4 format, the fields are written in alphabetical order.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::{Colored, Component, ProtocolVersion};
+    ///
+    /// let comp = Component::text("Hi").color(0xFFAA11);
+    /// assert_eq!(comp.to_json_for(ProtocolVersion::V1_8), r#"{"color":"gold","text":"Hi"}"#);
+    /// assert_eq!(comp.to_json_for(ProtocolVersion::V1_16), r##"{"color":"#FFAA11","text":"Hi"}"##);
+    /// ```
+    pub fn to_json_for(&self, version: ProtocolVersion) -> String {
+        self.to_value_for(version).to_string()
+    }
+
+    fn to_value_for(&self, version: ProtocolVersion) -> Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        let Some(fields) = value.as_object_mut() else {
+            return value;
+        };
+        if let Some(extra) = &self.extra {
+            let extra = extra.iter().map(|it| it.to_value_for(version)).collect();
+            fields.insert("extra".into(), Value::Array(extra));
+        }
+        if let MessageContents::Translate(translated) = &self.contents {
+            if let Some(with) = &translated.with {
+                let with = with.iter().map(|it| it.to_value_for(version)).collect();
+                fields.insert("with".into(), Value::Array(with));
+            }
+            if version < ProtocolVersion::V1_19_4 {
+                fields.remove("fallback");
+            }
+        }
+        if version < ProtocolVersion::V1_21_4 {
+            fields.remove("shadow_color");
+        }
+        if version < ProtocolVersion::V1_16 {
+            fields.remove("font");
+            if let Some(rgb) = self.color.as_ref().and_then(|color| color.to_rgb()) {
+                let named = NamedColor::nearest(rgb).to_string();
+                fields.insert("color".into(), Value::String(named));
+            }
+        }
+        let (click_field, hover_field) = match version >= ProtocolVersion::V1_21_5 {
+            true => ("click_event", "hover_event"),
+            false => ("clickEvent", "hoverEvent"),
+        };
+        fields.remove("clickEvent");
+        if let Some(click) = self
+            .click_event
+            .as_ref()
+            .and_then(|it| click_value(it, version))
+        {
+            fields.insert(click_field.into(), click);
+        }
+        fields.remove("hoverEvent");
+        if let Some(hover) = self
+            .hover_event
+            .as_ref()
+            .and_then(|it| hover_value(it, version))
+        {
+            fields.insert(hover_field.into(), hover);
+        }
+        value
+    }
+}
+
+/// Serializes the click event, or returns [None] if it can not be written in the version
+fn click_value(click: &ClickEvent, version: ProtocolVersion) -> Option<Value> {
+    if version < ProtocolVersion::V1_21_5 {
+        return serde_json::to_value(click).ok();
+    }
+    let (action, field, value) = match click {
+        ClickEvent::OpenUrl(url) => ("open_url", "url", Value::from(url.as_str())),
+        ClickEvent::RunCommand(command) => {
+            ("run_command", "command", Value::from(command.as_str()))
+        }
+        ClickEvent::SuggestCommand(command) => {
+            ("suggest_command", "command", Value::from(command.as_str()))
+        }
+        // the page is an integer now, so pages that are not numbers are no longer valid
+        ClickEvent::ChangePage(page) => (
+            "change_page",
+            "page",
+            Value::from(page.parse::<i32>().ok()?),
+        ),
+        ClickEvent::CopyToClipboard(text) => {
+            ("copy_to_clipboard", "value", Value::from(text.as_str()))
+        }
+    };
+    Some(event(action, [(field, value)]))
+}
+
+/// Serializes the hover event, with the hover text in the format of the version as well,
+/// or returns [None] if it can not be written in the version
+fn hover_value(hover: &HoverEvent, version: ProtocolVersion) -> Option<Value> {
+    let value = match hover {
+        HoverEvent::ShowText { contents } => {
+            let legacy = version < ProtocolVersion::V1_16 || version >= ProtocolVersion::V1_21_5;
+            let field = if legacy { "value" } else { "contents" };
+            event("show_text", [(field, contents.to_value_for(version))])
+        }
+        HoverEvent::ShowItem { contents: item } if version < ProtocolVersion::V1_16 => {
+            let mut snbt = format!(
+                "{{id:{},Count:{}b",
+                snbt_string(&item.id),
+                item.count.unwrap_or(1)
+            );
+            if let Some(tag) = &item.tag {
+                snbt.push_str(",tag:");
+                snbt.push_str(tag);
+            }
+            snbt.push('}');
+            event("show_item", [("value", Value::String(snbt))])
+        }
+        HoverEvent::ShowEntity { contents: entity } if version < ProtocolVersion::V1_16 => {
+            let mut snbt = format!(
+                "{{type:{},id:{}",
+                snbt_string(&entity.entity_type),
+                snbt_string(&entity.id.to_string())
+            );
+            if let Some(name) = &entity.name {
+                snbt.push_str(",name:");
+                snbt.push_str(&snbt_string(&name.to_value_for(version).to_string()));
+            }
+            snbt.push('}');
+            event("show_entity", [("value", Value::String(snbt))])
+        }
+        HoverEvent::ShowItem { contents: item } if version >= ProtocolVersion::V1_21_5 => {
+            // the NBT tag of the item has no equivalent in the item components of 1.21.5
+            let mut fields = vec![("id", Value::from(item.id.as_str()))];
+            if let Some(count) = item.count {
+                fields.push(("count", Value::from(count)));
+            }
+            event("show_item", fields)
+        }
+        HoverEvent::ShowEntity { contents: entity } if version >= ProtocolVersion::V1_21_5 => {
+            let mut fields = vec![
+                ("id", Value::from(entity.entity_type.as_str())),
+                ("uuid", Value::from(entity.id.to_string())),
+            ];
+            if let Some(name) = &entity.name {
+                fields.push(("name", name.to_value_for(version)));
+            }
+            event("show_entity", fields)
+        }
+        HoverEvent::ShowItem { .. } => serde_json::to_value(hover).unwrap_or_default(),
+        HoverEvent::ShowEntity { contents: entity } => {
+            let mut value = serde_json::to_value(hover).unwrap_or_default();
+            if let (Some(name), Some(field)) = (&entity.name, value.pointer_mut("/contents/name")) {
+                *field = name.to_value_for(version);
+            }
+            value
+        }
+        // achievements were replaced with advancements in 1.12
+        HoverEvent::ShowAchievement { .. } if version < ProtocolVersion::V1_16 => {
+            serde_json::to_value(hover).unwrap_or_default()
+        }
+        HoverEvent::ShowAchievement { .. } => return None,
+    };
+    Some(value)
+}
+
+/// Builds an event object with the action and the fields of the action
+fn event<'a, I>(action: &str, fields: I) -> Value
+where
+    I: IntoIterator<Item = (&'a str, Value)>,
+{
+    let mut event = Map::new();
+    event.insert("action".into(), Value::from(action));
+    for (name, value) in fields {
+        event.insert(name.into(), value);
+    }
+    Value::Object(event)
+}
+
+/// Quotes a string for SNBT in double quotes, escaping the quotes and backslashes inside of it
+fn snbt_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -13,9 +13,9 @@
 //!                     .color(0xFFAAFF)
 //!                     .click_event(ClickEvent::open_url("https://github.com/Maxuss/lobster"))
 //!                     .append(
-//!                         Component::translatable("my.translation.key")
+//!                         Component::translate::<&str, Component>("my.translation.key", None)
 //!                         .color(NamedColor::Gold)
-//!                         .hover_event(HoverEvent::ShowText(Component::text("Click for surprise!")))
+//!                         .hover_event(HoverEvent::show_text(Component::text("Click for surprise!")))
 //!                         .insert_text("I love lobsterchat!")
 //!                     )
 //!                     .append(
@@ -43,12 +43,13 @@
 //!         ),
 //!         (
 //!             "second",
-//!             Component::translatable("translated.text.key")
+//!             Component::translate::<&str, Component>("translated.text.key", None)
 //!         )
-//!     ])
+//!     ]);
 //! ```
 //!
-//! Enable minimessage with the `minimessage` crate feature
+//! Enable minimessage with the `minimessage` crate feature, and conversions
+//! to and from the text of `valence_protocol` with the `valence` crate feature
 //!
 //! ### Speed:
 //!
@@ -79,19 +80,34 @@
 pub mod component;
 #[cfg(feature = "minimessage")]
 pub mod message;
+#[cfg(feature = "nbt")]
+pub mod nbt;
 #[cfg(feature = "minimessage")]
-pub use message::{lobster, placeholder_lobster};
+pub use message::{
+    lobster, lobster_lenient, lobster_strict, lobster_with_legacy, placeholder_lobster,
+    placeholder_lobster_str,
+};
 
 #[cfg(test)]
 #[cfg(feature = "minimessage")]
 mod tests {
     #![allow(soft_unstable)]
 
-    use crate::component::{AsComponent, ClickEvent, Component, HoverEvent};
+    use crate::component::{
+        AsComponent, Book, ClickEvent, Colored, Component, ComponentKind, DisplayEntityData,
+        DisplayItemData, FontMetrics, Formatting, HoverEvent, NamedColor, ResolvedStyle,
+        SanitizePolicy, Style, TextColor, SPACE_FONT,
+    };
+    use crate::message::reader::parse_chunked;
     use crate::message::tokens::{MessageToken, Parser};
-    use crate::{lobster, placeholder_lobster};
+    use crate::message::{Limit, MiniMessageParser, ParseError};
+    use crate::{
+        lobster, lobster_lenient, lobster_strict, lobster_with_legacy, placeholder_lobster,
+        placeholder_lobster_str,
+    };
     use logos::Lexer;
     use logos::Logos;
+    use std::collections::HashSet;
 
     #[test]
     fn test_components() {
@@ -99,16 +115,16 @@ mod tests {
             .insert_text("Some text")
             .hover_event(HoverEvent::show_text("Some text".as_component()))
             .click_event(ClickEvent::open_url("https://github.com/Maxuss/lobster"));
-        println!("{}", cmp.to_string())
+        println!("{}", cmp)
     }
 
     #[test]
     #[cfg(feature = "minimessage")]
     fn test_lexer() {
-        let mut lexer: Lexer<MessageToken> =
+        let lexer: Lexer<MessageToken> =
             MessageToken::lexer("<#AABBCC>Hex text<reset>Stop hex text");
 
-        while let Some(tk) = lexer.next() {
+        for tk in lexer {
             println!("{:?}", tk)
         }
     }
@@ -116,10 +132,11 @@ mod tests {
     #[test]
     #[cfg(feature = "minimessage")]
     fn test_parser() {
+        let options = MiniMessageParser::new();
         let lexer: Lexer<MessageToken> = MessageToken::lexer("<red>Red text");
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer, &options);
 
-        while let Ok(_) = parser.advance() {
+        while parser.advance().is_ok() {
             // no-op
         }
         let out = parser.finish();
@@ -154,6 +171,3428 @@ mod tests {
         println!("{}", message.flatten())
     }
 
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_long_message() {
+        let segment =
+            "<red>Red text <green>Green text <italic><yellow>Yellow italic text. <bold>BOLD. ";
+        let mut message = lobster(segment.repeat(200));
+
+        assert_eq!(
+            message.flatten(),
+            "Red text Green text Yellow italic text. BOLD. ".repeat(200)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_explicit_formatting() {
+        let message = lobster("<bold><italic:false>text<bold:false> more");
+
+        assert_eq!(
+            message.to_string(),
+            r#"{"extra":[{"extra":[{"bold":false,"text":" more"}],"bold":true,"italic":false,"text":"text"}],"text":""}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_strict() {
+        assert_eq!(
+            lobster_strict("<red>Red <bold>text</bold></red> plain"),
+            Ok(lobster("<red>Red <bold>text</bold></red> plain"))
+        );
+        assert_eq!(
+            lobster_strict("<red>Some <bWe>typo"),
+            Err(ParseError::UnknownTag {
+                tag: "<bWe>".into(),
+                offset: 10
+            })
+        );
+        assert_eq!(
+            lobster_strict("<#GGAAFF>text"),
+            Err(ParseError::MalformedHex {
+                tag: "<#GGAAFF>".into(),
+                offset: 0
+            })
+        );
+        assert_eq!(
+            lobster_strict("text<#FFF>"),
+            Err(ParseError::MalformedHex {
+                tag: "<#FFF>".into(),
+                offset: 4
+            })
+        );
+        assert_eq!(
+            lobster_strict("<red>text</bold>"),
+            Err(ParseError::UnbalancedTag {
+                tag: "</bold>".into(),
+                offset: 9
+            })
+        );
+        assert_eq!(
+            lobster_strict("<red>text</blue>"),
+            Err(ParseError::UnbalancedTag {
+                tag: "</blue>".into(),
+                offset: 9
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_closing_tags() {
+        let message = lobster("<bold><red>Red</red> not red");
+
+        assert_eq!(
+            message.to_string(),
+            r#"{"extra":[{"bold":true,"color":"red","text":"Red"},{"bold":true,"text":" not red"}],"text":""}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_lenient() {
+        let mut message = lobster_lenient("I <3 <red>lobsters</blue> <unknown> > <#GG>!");
+        assert_eq!(message.flatten(), "I <3 lobsters</blue> <unknown> > <#GG>!");
+
+        assert_eq!(
+            lobster_lenient("<gold><bold>Known</bold> tags"),
+            lobster("<gold><bold>Known</bold> tags")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_formatting_aliases() {
+        assert_eq!(lobster("<b>x"), lobster("<bold>x"));
+        assert_eq!(
+            lobster("<i>a</i><u>b</u><st>c</st><obf>d</obf>"),
+            lobster("<italic>a</italic><underline>b</underline><strikethrough>c</strikethrough><obfuscated>d</obfuscated>")
+        );
+        assert!(lobster_strict("<b>mixed</bold>").is_ok());
+    }
+
+    #[test]
+    fn test_gradient() {
+        let gradient = Component::gradient("abc", &[0xFF0000, 0x0000FF]);
+
+        assert_eq!(
+            gradient,
+            Component::default()
+                .append(Component::text("a").color(0xFF0000))
+                .append(Component::text("b").color(0x800080))
+                .append(Component::text("c").color(0x0000FF))
+        );
+        assert_eq!(
+            lobster("<gradient:#FF0000:#0000FF>abc"),
+            Component::default().append(gradient)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_pride() {
+        let flag = [0xE50000, 0xFF8D00, 0xFFEE00, 0x028121, 0x004CFF, 0x770088];
+        assert_eq!(
+            lobster("<pride>abcdef"),
+            Component::default().append(Component::gradient("abcdef", &flag))
+        );
+        assert_eq!(
+            lobster("<pride:bi>abc</pride> plain"),
+            Component::default()
+                .append(Component::gradient("abc", &[0xD60270, 0x9B4F96, 0x0038A8]))
+                .append(Component::text(" plain"))
+        );
+        assert_eq!(
+            lobster_strict("<pride:unknown>text"),
+            Err(ParseError::InvalidArgument {
+                tag: "<pride:unknown>".into(),
+                offset: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_append_to_last_child() {
+        let empty = Component::text("root").append_to_last_child(Component::text("child"));
+        assert_eq!(empty, Component::text("root").append("child"));
+
+        let single = Component::text("root")
+            .append("first")
+            .append_to_last_child(Component::text("second"));
+        assert_eq!(
+            single,
+            Component::text("root").append(Component::text("first").append("second"))
+        );
+
+        let nested = Component::text("a")
+            .append(Component::text("b").append(Component::text("c").append("d")))
+            .append_to_last_child(Component::text("e"));
+        assert_eq!(
+            nested,
+            Component::text("a").append(
+                Component::text("b")
+                    .append(Component::text("c").append(Component::text("d").append("e")))
+            )
+        );
+
+        let keybind = Component::text("root")
+            .append(Component::keybind("key.jump"))
+            .append_to_last_child(Component::text("after"));
+        assert_eq!(
+            keybind,
+            Component::text("root")
+                .append(Component::keybind("key.jump"))
+                .append("after")
+        );
+    }
+
+    #[test]
+    fn test_inherited_color() {
+        let parent = TextColor::Named(NamedColor::Gold);
+        let plain = Component::text("plain");
+        let red = Component::text("red").color(NamedColor::Red);
+
+        assert_eq!(
+            plain.get_color_inherited(None),
+            TextColor::Named(NamedColor::White)
+        );
+        assert_eq!(plain.get_color_inherited(Some(&parent)), parent);
+        assert_eq!(
+            red.get_color_inherited(Some(&parent)),
+            TextColor::Named(NamedColor::Red)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_nested_placeholders() {
+        let mut nested =
+            placeholder_lobster_str("<first>", [("first", "<red><second>"), ("second", "hi")]);
+        assert_eq!(nested.flatten(), "hi");
+
+        let mut cyclic =
+            placeholder_lobster_str("<first>", [("first", "1<second>"), ("second", "2<first>")]);
+        assert_eq!(cyclic.flatten(), "12121212<first>");
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_placeholder_isolation() {
+        fn assert_no_empty_leaves(value: &serde_json::Value) {
+            for child in value["extra"].as_array().into_iter().flatten() {
+                assert!(child["text"] != "" || child.get("extra").is_some());
+                assert_no_empty_leaves(child);
+            }
+        }
+
+        let message = placeholder_lobster(
+            "<red>Before <ph> after <bold>bold <ph>",
+            [("ph", lobster("<blue>placeholder"))],
+        );
+        assert_no_empty_leaves(&serde_json::to_value(&message).unwrap());
+        assert_eq!(
+            message,
+            Component::default()
+                .append(Component::text("Before ").color(NamedColor::Red))
+                .append(lobster("<blue>placeholder"))
+                .append(
+                    Component::text(" after ")
+                        .color(NamedColor::Red)
+                        .append(Component::text("bold ").bold(true))
+                )
+                .append(lobster("<blue>placeholder"))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_minimessage_parser() {
+        let parser = MiniMessageParser::new()
+            .strict(true)
+            .placeholder("player", Component::text("Maxus").color(NamedColor::Gold));
+
+        assert_eq!(
+            parser.parse("<player> <gray>joined"),
+            Ok(placeholder_lobster(
+                "<player> <gray>joined",
+                [("player", Component::text("Maxus").color(NamedColor::Gold))]
+            ))
+        );
+        assert!(parser.parse("<other> <gray>joined").is_err());
+
+        let parser = parser.strict(false).lenient(true);
+        assert_eq!(
+            parser.parse("<other> <gray>joined"),
+            Ok(lobster_lenient("<other> <gray>joined"))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "minimessage")]
+    fn test_custom_tags() {
+        let parser = MiniMessageParser::new()
+            .strict(true)
+            .tag("server_name", |_| {
+                Component::text("Lobster").color(NamedColor::Gold)
+            })
+            .tag("repeat", |args| Component::text(args[0].repeat(args.len())));
+
+        assert_eq!(
+            parser.parse("<red>Welcome to <server_name>!"),
+            Ok(Component::default()
+                .append(Component::text("Welcome to ").color(NamedColor::Red))
+                .append(Component::text("Lobster").color(NamedColor::Gold))
+                .append(Component::text("!").color(NamedColor::Red)))
+        );
+        assert_eq!(
+            parser.parse("<repeat:ab:cd:ef>").unwrap().flatten(),
+            "ababab"
+        );
+        assert!(parser.parse("<unknown>").is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "minimessage")]
+    fn test_custom_tag_collision() {
+        MiniMessageParser::new().tag("bold", |_| Component::text("not bold"));
+    }
+
+    #[test]
+    fn test_placeholder_resolver() {
+        let mut stats = std::collections::HashMap::new();
+        stats.insert("kills".to_string(), Component::text("12"));
+
+        let parser = MiniMessageParser::new()
+            .placeholder("player", Component::text("Maxuss"))
+            .placeholder_resolver(stats)
+            .placeholder_resolver(|name: &str| match name {
+                "player" | "kills" => Some(Component::text("shadowed")),
+                "deaths" => Some(Component::text("3")),
+                _ => None,
+            });
+
+        let mut message = parser
+            .parse("<player>: <kills> kills, <deaths> deaths")
+            .unwrap();
+        assert_eq!(message.flatten(), "Maxuss: 12 kills, 3 deaths");
+
+        let parser = parser.strict(true);
+        let err = parser.parse("<red>Hi, <unknown>").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UndefinedPlaceholder {
+                tag: "<unknown>".into(),
+                offset: 9
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Undefined placeholder '<unknown>' at offset 9"
+        );
+        let err = parser.parse("<unknown:arg>").unwrap_err();
+        assert!(matches!(err, ParseError::UnknownTag { .. }));
+        let err: Box<dyn std::error::Error> = Box::new(parser.parse("</red>").unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            "Unbalanced closing tag '</red>' at offset 0"
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        let parsed: Component = "<red>Hello, <bold>World!".parse().unwrap();
+        assert_eq!(parsed, lobster("<red>Hello, <bold>World!"));
+
+        let plain = Component::from("<red>Hello");
+        assert_eq!(plain, Component::text("<red>Hello"));
+
+        let err = "<red>Hello</blue>".parse::<Component>().unwrap_err();
+        assert!(matches!(err, ParseError::UnbalancedTag { .. }));
+    }
+
+    #[test]
+    fn test_hash_set() {
+        let mut set = std::collections::HashSet::new();
+        assert!(set.insert(lobster("<red>Hello, <bold>World!")));
+        assert!(set.insert(
+            Component::text("Hover me")
+                .hover_event(HoverEvent::show_text(Component::text("Hi")))
+                .click_event(ClickEvent::run_command("/hello"))
+        ));
+        assert!(set.insert(Component::text("Hello, World!")));
+
+        // logically equal components hash equally
+        assert!(!set.insert(lobster("<red>Hello, <bold>World!")));
+        assert!(set.contains(&Component::text("Hello, World!")));
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "valence")]
+    fn test_valence_text() {
+        use crate::component::DisplayEntityData;
+        use uuid::Uuid;
+        use valence_protocol::text::{ClickEvent as ValenceClickEvent, Color};
+        use valence_protocol::Text;
+
+        let comp = Component::text("Hello, ")
+            .color(NamedColor::Gold)
+            .bold(true)
+            .insert_text("hi")
+            .click_event(ClickEvent::change_page(2))
+            .hover_event(HoverEvent::show_entity(DisplayEntityData {
+                name: Some(Component::text("Steve")),
+                entity_type: "minecraft:player".into(),
+                id: Uuid::nil(),
+            }))
+            .append(Component::translate(
+                "chat.type.text",
+                Some(vec![Component::keybind("key.jump")]),
+            ))
+            .append(Component::storage_nbt(
+                "path",
+                "minecraft:storage",
+                None,
+                None::<Component>,
+            ))
+            .append(Component::text("!").color(0x123456).font("minecraft:alt"));
+        let text = Text::from(comp.clone());
+        assert_eq!(text.color, Some(Color::GOLD));
+        assert_eq!(text.click_event, Some(ValenceClickEvent::ChangePage(2)));
+        assert_eq!(text.extra[2].color, Some(Color::rgb(0x12, 0x34, 0x56)));
+        // everything both models support survives the round trip
+        assert_eq!(Component::from(text), comp);
+
+        // lossy conversions
+        let lossy = Component::text("a")
+            .font("minecraft:custom")
+            .shadow_color(0xFF000000)
+            .click_event(ClickEvent::ChangePage("first".into()))
+            .hover_event(HoverEvent::show_achievement("achievement.openInventory"));
+        assert_eq!(Component::from(Text::from(lossy)), Component::text("a"));
+        let mut reset = Text::text("b");
+        reset.color = Some(Color::Reset);
+        reset.click_event = Some(ValenceClickEvent::OpenFile("/etc/passwd".into()));
+        assert_eq!(Component::from(reset), Component::text("b"));
+    }
+
+    #[test]
+    fn test_child_manipulation() {
+        let mut parent = Component::text("parent");
+        parent.insert_child(0, Component::text("b"));
+        parent.insert_child(0, Component::text("a"));
+        // out of range indices append the child
+        parent.insert_child(100, Component::text("d"));
+        parent.insert_child(2, Component::text("c"));
+        assert_eq!(parent.flatten(), "parentabcd");
+
+        assert_eq!(parent.remove_child(1), Some(Component::text("b")));
+        assert_eq!(parent.remove_child(3), None);
+        assert_eq!(parent.flatten(), "parentacd");
+
+        parent.clear_children();
+        assert_eq!(parent, Component::text("parent"));
+        assert_eq!(parent.remove_child(0), None);
+
+        // removing the last child leaves no empty `extra` behind
+        let mut single = Component::text("single");
+        single.insert_child(0, Component::text("child"));
+        single.remove_child(0);
+        assert_eq!(single.to_string(), r#"{"text":"single"}"#);
+    }
+
+    #[test]
+    fn test_nearest_perceptual() {
+        use crate::component::distance::{ciede2000, srgb_to_lab};
+
+        let close = |a: [f64; 3], b: [f64; 3]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-2);
+        assert!(close(srgb_to_lab(0xFFFFFF), [100.0, 0.0, 0.0]));
+        assert!(close(srgb_to_lab(0x000000), [0.0, 0.0, 0.0]));
+        assert!(close(srgb_to_lab(0xFF0000), [53.2408, 80.0925, 67.2032]));
+
+        // reference pairs from Sharma et al., "The CIEDE2000 Color-Difference Formula"
+        for (lab1, lab2, expected) in [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            (
+                [60.2574, -34.0099, 36.2677],
+                [60.4626, -34.1751, 39.4387],
+                1.2644,
+            ),
+            ([2.0776, 0.0795, -1.135], [0.9033, -0.0636, -0.5514], 0.9082),
+        ] {
+            assert!((ciede2000(lab1, lab2) - expected).abs() < 1e-4);
+            assert!((ciede2000(lab2, lab1) - expected).abs() < 1e-4);
+        }
+
+        // both agree on the obvious cases
+        for named in NamedColor::all() {
+            assert_eq!(NamedColor::nearest(named.to_rgb()), named);
+            assert_eq!(NamedColor::nearest_perceptual(named.to_rgb()), named);
+        }
+        assert_eq!(NamedColor::nearest_perceptual(0xFFA500), NamedColor::Gold);
+
+        // light and saturated colors are pulled towards gray by the RGB distance
+        assert_eq!(NamedColor::nearest(0xFF99AA), NamedColor::Gray);
+        assert_eq!(NamedColor::nearest_perceptual(0xFF99AA), NamedColor::Red);
+        assert_eq!(NamedColor::nearest(0x99FF99), NamedColor::Gray);
+        assert_eq!(NamedColor::nearest_perceptual(0x99FF99), NamedColor::Green);
+        assert_eq!(NamedColor::nearest(0xDD00DD), NamedColor::DarkPurple);
+        assert_eq!(
+            NamedColor::nearest_perceptual(0xDD00DD),
+            NamedColor::LightPurple
+        );
+    }
+
+    #[test]
+    fn test_oklab() {
+        use crate::component::gradient::{oklab_to_srgb, sample, srgb_to_oklab};
+        use crate::component::ColorSpace;
+
+        let close = |[l1, a1, b1]: [f32; 3], [l2, a2, b2]: [f32; 3]| {
+            (l1 - l2).abs() < 1e-3 && (a1 - a2).abs() < 1e-3 && (b1 - b2).abs() < 1e-3
+        };
+        assert!(close(srgb_to_oklab(0xFFFFFF), [1.0, 0.0, 0.0]));
+        assert!(close(srgb_to_oklab(0x000000), [0.0, 0.0, 0.0]));
+        assert!(close(srgb_to_oklab(0xFF0000), [0.62796, 0.22486, 0.12585]));
+        assert!(close(srgb_to_oklab(0x00FF00), [0.86644, -0.23389, 0.1795]));
+        assert!(close(
+            srgb_to_oklab(0x0000FF),
+            [0.45201, -0.03246, -0.31153]
+        ));
+
+        for color in [0xFFFFFF, 0x000000, 0xFF0000, 0x00FF00, 0x0000FF, 0x7F3A9C] {
+            assert_eq!(oklab_to_srgb(srgb_to_oklab(color)), color);
+        }
+        // out of gamut colors are clamped
+        assert_eq!(oklab_to_srgb([2.0, 0.0, 0.0]), 0xFFFFFF);
+
+        // stops are kept as is, only the mid-tones differ
+        assert_eq!(
+            sample(&[0xFF0000, 0x0000FF], 0.0, ColorSpace::Oklab),
+            0xFF0000
+        );
+        assert_eq!(
+            sample(&[0xFF0000, 0x0000FF], 1.0, ColorSpace::Oklab),
+            0x0000FF
+        );
+        assert_eq!(
+            sample(&[0xFF0000, 0x0000FF], 0.5, ColorSpace::LinearRgb),
+            0x800080
+        );
+        assert_ne!(
+            sample(&[0xFF0000, 0x0000FF], 0.5, ColorSpace::Oklab),
+            0x800080
+        );
+
+        assert_eq!(
+            Component::gradient("Hello", &[0xFF0000, 0x0000FF]),
+            Component::gradient_in("Hello", &[0xFF0000, 0x0000FF], ColorSpace::LinearRgb)
+        );
+    }
+
+    #[test]
+    fn test_translation_fallback() {
+        let plain = Component::translate::<&str, Component>("block.minecraft.stone", None);
+        let json = plain.to_string();
+        assert_eq!(json, r#"{"translate":"block.minecraft.stone"}"#);
+        assert_eq!(serde_json::from_str::<Component>(&json).unwrap(), plain);
+
+        let fallback = plain.clone().fallback("Stone");
+        let json = fallback.to_string();
+        assert_eq!(
+            json,
+            r#"{"translate":"block.minecraft.stone","fallback":"Stone"}"#
+        );
+        assert_eq!(serde_json::from_str::<Component>(&json).unwrap(), fallback);
+
+        // only translatable components have a fallback
+        assert_eq!(
+            Component::text("Stone").fallback("Stone"),
+            Component::text("Stone")
+        );
+
+        assert_eq!(
+            lobster("<red><lang:block.minecraft.stone>"),
+            Component::default().append(plain.clone().color(NamedColor::Red))
+        );
+        assert_eq!(
+            lobster("<lang_or:block.minecraft.stone:Stone>"),
+            Component::default().append(fallback)
+        );
+        assert_eq!(
+            lobster("<lang:chat.type.text:Maxuss:Hello>"),
+            Component::default().append(Component::translate(
+                "chat.type.text",
+                Some(vec![Component::text("Maxuss"), Component::text("Hello")])
+            ))
+        );
+
+        let err = lobster_strict("<lang_or:block.minecraft.stone>").unwrap_err();
+        assert!(matches!(err, ParseError::InvalidArgument { offset: 0, .. }));
+    }
+
+    #[test]
+    #[cfg(feature = "nbt")]
+    fn test_nbt() {
+        use crate::nbt::{NbtError, NbtTag, MAX_DEPTH};
+
+        let component = Component::text("Hello").color(NamedColor::Red).bold(true);
+        let nbt = component.to_nbt();
+        assert_eq!(nbt.get("text"), Some(&NbtTag::String("Hello".into())));
+        assert_eq!(nbt.get("color"), Some(&NbtTag::String("red".into())));
+        assert_eq!(nbt.get("bold"), Some(&NbtTag::Byte(1)));
+        assert_eq!(nbt.get("italic"), None);
+
+        let bytes = component.to_nbt_bytes().unwrap();
+        assert_eq!(bytes[0], 10);
+        assert_eq!(Component::from_nbt_bytes(&bytes).unwrap(), component);
+
+        let nested = lobster("<red>Hello, <bold>Wörld 🦞!")
+            .hover_event(HoverEvent::show_text(Component::text("\0 null")));
+        let nbt = nested.to_nbt();
+        assert!(matches!(nbt.get("extra"), Some(NbtTag::List(children)) if children.len() == 1));
+        assert_eq!(
+            Component::from_nbt_bytes(&nested.to_nbt_bytes().unwrap()).unwrap(),
+            nested
+        );
+
+        assert_eq!(
+            Component::from_nbt(NbtTag::String("plain".into())).unwrap(),
+            Component::text("plain")
+        );
+        assert!(Component::from_nbt_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // untrusted input nested too deeply is rejected instead of overflowing the stack
+        let compounds = |depth: usize| {
+            let mut bytes = vec![10];
+            bytes.extend([10, 0, 0].repeat(depth - 1));
+            bytes.extend(vec![0; depth]);
+            bytes
+        };
+        assert!(NbtTag::from_bytes(&compounds(MAX_DEPTH)).is_ok());
+        assert!(matches!(
+            NbtTag::from_bytes(&compounds(MAX_DEPTH + 1)),
+            Err(NbtError::TooDeep)
+        ));
+        assert!(matches!(
+            Component::from_nbt_bytes(&compounds(200_000)),
+            Err(NbtError::TooDeep)
+        ));
+        let lists = [[9].as_slice(), &[9, 0, 0, 0, 1].repeat(200_000)].concat();
+        assert!(matches!(NbtTag::from_bytes(&lists), Err(NbtError::TooDeep)));
+
+        // strings longer than their length prefix can hold are not written
+        let long = Component::text("a".repeat(u16::MAX as usize + 1));
+        assert!(matches!(
+            long.to_nbt_bytes(),
+            Err(NbtError::StringTooLong(65536))
+        ));
+        assert!(Component::text("a".repeat(u16::MAX as usize))
+            .to_nbt_bytes()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_legacy_hover_events() {
+        let read = |json: &str| serde_json::from_str::<HoverEvent>(json).unwrap();
+
+        let modern = HoverEvent::show_text(Component::text("Hi").color(NamedColor::Red));
+        let json = serde_json::to_string(&modern).unwrap();
+        assert_eq!(
+            json,
+            r#"{"action":"show_text","contents":{"color":"red","text":"Hi"}}"#
+        );
+        assert_eq!(read(&json), modern);
+        assert_eq!(
+            read(r#"{"action":"show_text","value":{"text":"Hi","color":"red"}}"#),
+            modern
+        );
+        assert_eq!(
+            read(r#"{"action":"show_text","value":"Hi"}"#),
+            HoverEvent::show_text(Component::text("Hi"))
+        );
+        assert_eq!(
+            read(r#"{"action":"show_text","value":["Hi",{"text":"!"}]}"#),
+            HoverEvent::show_text(Component::text("Hi").append(Component::text("!")))
+        );
+
+        assert_eq!(
+            read(
+                r#"{"action":"show_item","value":"{id:\"minecraft:diamond_sword\",Count:1b,tag:{display:{Name:'{\"text\":\"Sword\"}'}}}"}"#
+            ),
+            HoverEvent::show_item(DisplayItemData {
+                id: "minecraft:diamond_sword".into(),
+                count: Some(1),
+                tag: Some(r#"{display:{Name:'{"text":"Sword"}'}}"#.into()),
+            })
+        );
+        assert_eq!(
+            read(r#"{"action":"show_item","value":{"text":"{id:stone}"}}"#),
+            HoverEvent::show_item(DisplayItemData {
+                id: "stone".into(),
+                count: None,
+                tag: None,
+            })
+        );
+
+        let uuid = uuid::Uuid::parse_str("d8d5a923-7b20-43d8-883b-1150148d6955").unwrap();
+        assert_eq!(
+            read(
+                r#"{"action":"show_entity","value":"{type:\"minecraft:pig\",id:\"d8d5a923-7b20-43d8-883b-1150148d6955\",name:\"{\\\"text\\\":\\\"Pig\\\"}\"}"}"#
+            ),
+            HoverEvent::show_entity(DisplayEntityData {
+                name: Some(Component::text("Pig")),
+                entity_type: "minecraft:pig".into(),
+                id: uuid,
+            })
+        );
+
+        assert!(serde_json::from_str::<HoverEvent>(r#"{"action":"show_text"}"#).is_err());
+        assert!(serde_json::from_str::<HoverEvent>(
+            r#"{"action":"show_item","value":"{Count:1b}"}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_colored_constructors() {
+        assert_eq!(
+            Component::colored("Hi", NamedColor::Red),
+            Component::text("Hi").color(NamedColor::Red)
+        );
+        assert_eq!(
+            Component::colored("Hi", 0x00AAFF).to_string(),
+            r##"{"color":"#00AAFF","text":"Hi"}"##
+        );
+        assert_eq!(
+            Component::colored("Hi", TextColor::Named(NamedColor::Gold)),
+            Component::text("Hi").color(NamedColor::Gold)
+        );
+        assert_eq!(Component::text_bold("Hi"), Component::text("Hi").bold(true));
+        assert_eq!(
+            Component::text_italic("Hi"),
+            Component::text("Hi").italic(true)
+        );
+    }
+
+    #[test]
+    fn test_text_color_conversions() {
+        assert_eq!(
+            TextColor::from(NamedColor::DarkAqua),
+            TextColor::Named(NamedColor::DarkAqua)
+        );
+        assert_eq!(TextColor::from(0xAAFF00), TextColor::Hex("#AAFF00".into()));
+        // hex colors are always zero-padded to six digits
+        assert_eq!(TextColor::from(0x0000FF), TextColor::Hex("#0000FF".into()));
+        assert_eq!(TextColor::from(0), TextColor::Hex("#000000".into()));
+        // and never longer than that, ignoring the bits above the color
+        assert_eq!(TextColor::from(0x1FFFFFF), TextColor::Hex("#FFFFFF".into()));
+        assert_eq!(TextColor::from(0x80AAFF00), TextColor::from(0xAAFF00));
+        assert_eq!(TextColor::from("red"), TextColor::Named(NamedColor::Red));
+        assert_eq!(
+            TextColor::from("light_purple"),
+            TextColor::Named(NamedColor::LightPurple)
+        );
+        assert_eq!(TextColor::from("#00aaff"), TextColor::Hex("#00aaff".into()));
+    }
+
+    #[test]
+    fn test_text_color_strings() {
+        use std::str::FromStr;
+
+        assert_eq!(
+            TextColor::from_str("#00aaFF"),
+            Ok(TextColor::Hex("#00aaFF".into()))
+        );
+        assert_eq!(
+            TextColor::from_str("#0af"),
+            Ok(TextColor::Hex("#00aaff".into()))
+        );
+        assert_eq!(
+            TextColor::from_str("dark_purple"),
+            Ok(TextColor::Named(NamedColor::DarkPurple))
+        );
+        for invalid in ["", "#", "#12", "#1234", "#GGGGGG", "purple", "Red"] {
+            let err = TextColor::from_str(invalid).unwrap_err();
+            assert!(err.to_string().contains(invalid));
+        }
+
+        assert_eq!(TextColor::from(0x00AAFF).to_string(), "#00AAFF");
+        assert_eq!(
+            TextColor::from(NamedColor::DarkGray).to_string(),
+            "dark_gray"
+        );
+
+        let colors = [
+            TextColor::from(0x000001),
+            TextColor::Hex("#abcdef".into()),
+            TextColor::Named(NamedColor::Red),
+            TextColor::Named(NamedColor::LightPurple),
+        ];
+        for color in colors {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+    }
+
+    #[test]
+    fn test_legacy_codes() {
+        use crate::component::Formatting;
+
+        assert_eq!(NamedColor::from_legacy_code('c'), Some(NamedColor::Red));
+        assert_eq!(NamedColor::from_legacy_code('6'), Some(NamedColor::Gold));
+        assert_eq!(NamedColor::from_legacy_code('F'), Some(NamedColor::White));
+        assert_eq!(NamedColor::from_legacy_code('g'), None);
+        assert_eq!(NamedColor::DarkAqua.to_legacy_code(), '3');
+        for code in "0123456789abcdef".chars() {
+            let color = NamedColor::from_legacy_code(code).unwrap();
+            assert_eq!(color.to_legacy_code(), code);
+        }
+
+        assert_eq!(Formatting::from_legacy_code('l'), Some(Formatting::Bold));
+        assert_eq!(
+            Formatting::from_legacy_code('K'),
+            Some(Formatting::Obfuscated)
+        );
+        assert_eq!(Formatting::from_legacy_code('c'), None);
+        for code in "klmnor".chars() {
+            let fmt = Formatting::from_legacy_code(code).unwrap();
+            assert_eq!(fmt.to_legacy_code(), code);
+        }
+    }
+
+    #[test]
+    fn test_style_getters() {
+        let mut plain = Component::text("plain");
+        assert_eq!(plain.get_color_opt(), None);
+        assert_eq!(plain.get_color(), TextColor::Named(NamedColor::White));
+        assert_eq!(plain.get_click_event(), None);
+        assert_eq!(plain.get_hover_event(), None);
+        assert_eq!(plain.get_insertion(), None);
+
+        let styled = Component::colored("styled", NamedColor::Red)
+            .click_event(ClickEvent::open_url("https://example.com"))
+            .hover_event(HoverEvent::show_text(Component::text("hover")))
+            .insert_text("inserted");
+        assert_eq!(
+            styled.get_color_opt(),
+            Some(&TextColor::Named(NamedColor::Red))
+        );
+        assert_eq!(
+            styled.get_click_event(),
+            Some(&ClickEvent::open_url("https://example.com"))
+        );
+        assert_eq!(
+            styled.get_hover_event(),
+            Some(&HoverEvent::show_text(Component::text("hover")))
+        );
+        assert_eq!(styled.get_insertion(), Some("inserted"));
+    }
+
+    #[test]
+    fn test_item_builder() {
+        let item = DisplayItemData::builder("minecraft:diamond_sword")
+            .count(2)
+            .name(Component::colored("Sword", NamedColor::Aqua))
+            .lore("It's sharp")
+            .lore(Component::text_italic("Very sharp"))
+            .enchantment("minecraft:sharpness", 5)
+            .enchantment("minecraft:unbreaking", 3)
+            .build()
+            .unwrap();
+        assert_eq!(item.id, "minecraft:diamond_sword");
+        assert_eq!(item.count, Some(2));
+        assert_eq!(
+            item.tag.as_deref(),
+            Some(
+                r#"{display:{Name:'{"color":"aqua","text":"Sword"}',Lore:['{"text":"It\'s sharp"}','{"italic":true,"text":"Very sharp"}']},Enchantments:[{id:"minecraft:sharpness",lvl:5s},{id:"minecraft:unbreaking",lvl:3s}]}"#
+            )
+        );
+
+        let plain = DisplayItemData::builder("minecraft:stone").build().unwrap();
+        assert_eq!(plain.tag, None);
+
+        assert!(DisplayItemData::builder("stone").build().is_err());
+        assert!(DisplayItemData::builder("minecraft:Dirt Block")
+            .build()
+            .is_err());
+        let err = DisplayItemData::builder("minecraft:stone")
+            .enchantment("sharpness", 1)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("'sharpness'"));
+    }
+
+    #[test]
+    fn test_resource_locations() {
+        use crate::component::ResourceLocation;
+
+        let stone: ResourceLocation = "minecraft:stone".parse().unwrap();
+        assert_eq!(stone.namespace(), "minecraft");
+        assert_eq!(stone.path(), "stone");
+        assert_eq!("stone".parse(), Ok(stone.clone()));
+        assert_eq!(stone.to_string(), "minecraft:stone");
+        assert_eq!(
+            "my_mod:textures/block/ore-1.png"
+                .parse::<ResourceLocation>()
+                .unwrap()
+                .to_string(),
+            "my_mod:textures/block/ore-1.png"
+        );
+
+        for invalid in [
+            "",
+            "Dirt Block",
+            "minecraft:",
+            ":stone",
+            "my/mod:stone",
+            "a:b:c",
+        ] {
+            let err = invalid.parse::<ResourceLocation>().unwrap_err();
+            assert!(err.to_string().contains(invalid));
+        }
+        assert!(ResourceLocation::new("minecraft", "Stone").is_err());
+
+        assert!(DisplayItemData::try_new("minecraft:dirt", Some(1), None).is_ok());
+        assert!(DisplayItemData::try_new("Dirt Block", Some(1), None).is_err());
+        assert!(DisplayEntityData::try_new(None, "minecraft:pig", uuid::Uuid::nil()).is_ok());
+        assert!(DisplayEntityData::try_new(None, "Pig", uuid::Uuid::nil()).is_err());
+        assert_eq!(
+            Component::try_translate::<&str, Component>("block.minecraft.stone", None),
+            Ok(Component::translate::<&str, Component>(
+                "block.minecraft.stone",
+                None
+            ))
+        );
+        assert!(Component::try_translate::<&str, Component>("Stone Block", None).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "html")]
+    fn test_html() {
+        assert_eq!(Component::text("plain").to_html(), "plain");
+        assert_eq!(
+            lobster("<red>Hello, <bold><underline>World</bold>!").to_html(),
+            concat!(
+                r#"<span style="color:#FF5555">Hello, "#,
+                r#"<span style="font-weight:bold;text-decoration:underline">World"#,
+                r#"<span style="font-weight:normal">!</span></span></span>"#
+            )
+        );
+        assert_eq!(
+            Component::text("<script>\"&'</script>\nline")
+                .color(0x00AAFF)
+                .italic(false)
+                .strikethrough(false)
+                .to_html(),
+            r#"<span style="color:#00AAFF;font-style:normal;text-decoration:none">&lt;script&gt;&quot;&amp;&#39;&lt;/script&gt;<br>line</span>"#
+        );
+        // malformed hex colors can not inject other declarations into the style
+        assert_eq!(
+            Component::text("bg")
+                .color(TextColor::from("#000;background:url(https://evil/x)"))
+                .bold(true)
+                .to_html(),
+            r#"<span style="font-weight:bold">bg</span>"#
+        );
+        assert_eq!(
+            Component::text("hex")
+                .color(TextColor::from("#00aaff"))
+                .to_html(),
+            r#"<span style="color:#00AAFF">hex</span>"#
+        );
+        assert_eq!(
+            Component::text("link")
+                .click_event(ClickEvent::open_url("https://example.com/?a=1&b=2"))
+                .hover_event(HoverEvent::show_text(lobster("<red>Open \"site\"")))
+                .to_html(),
+            r#"<a href="https://example.com/?a=1&amp;b=2" title="Open &quot;site&quot;">link</a>"#
+        );
+        assert_eq!(
+            Component::text("evil")
+                .click_event(ClickEvent::open_url("javascript:alert(1)"))
+                .to_html(),
+            "evil"
+        );
+    }
+
+    #[test]
+    fn test_strip_formatting() {
+        let styled = lobster("<red>Hello, <bold><underline>World</bold>!").append(
+            Component::colored(" link", 0x00AAFF)
+                .click_event(ClickEvent::open_url("https://example.com"))
+                .hover_event(HoverEvent::show_text(Component::text("hover")))
+                .insert_text("inserted"),
+        );
+        let stripped = styled.strip_formatting();
+        assert_eq!(
+            stripped.to_string(),
+            r#"{"extra":[{"extra":[{"extra":[{"text":"!"}],"text":"World"}],"text":"Hello, "},{"text":" link"}],"text":""}"#
+        );
+        assert_eq!(stripped.clone().flatten(), styled.clone().flatten());
+        assert_eq!(stripped.strip_formatting(), stripped);
+
+        // components nested in the contents are stripped as well
+        let arg = Component::colored("Steve", NamedColor::Gold)
+            .bold(true)
+            .click_event(ClickEvent::run_command("/op @a"));
+        let separator = Component::text(", ").obfuscated(true);
+        let nested = Component::translate("chat.type.text", Some(vec![arg]))
+            .append(Component::entity("@a", Some(separator)));
+        assert_eq!(
+            nested.strip_formatting(),
+            Component::translate("chat.type.text", Some(vec![Component::text("Steve")]))
+                .append(Component::entity("@a", Some(Component::text(", "))))
+        );
+    }
+
+    #[test]
+    fn test_legacy() {
+        let legacy = Component::from_legacy(
+            "§cRed §lbold§r plain §x§0§0§a§a§f§fhex §ounderlined&x §zunknown§",
+            '§',
+        );
+        assert_eq!(
+            legacy,
+            Component::default()
+                .append(Component::colored("Red ", NamedColor::Red))
+                .append(Component::colored("bold", NamedColor::Red).bold(true))
+                .append(" plain ")
+                .append(Component::colored("hex ", 0x00AAFF))
+                .append(Component::colored("underlined&x §zunknown§", 0x00AAFF).italic(true))
+        );
+
+        // incomplete hex codes are kept as literal text like unknown codes
+        assert_eq!(
+            Component::from_legacy("&x&f&fshort", '&'),
+            Component::default()
+                .append("&x")
+                .append(Component::colored("short", NamedColor::White))
+        );
+        assert_eq!(Component::from_legacy("a&xZZ", '&').flatten(), "a&xZZ");
+        assert_eq!(Component::from_legacy("", '&'), Component::default());
+
+        // code characters not followed by a known code are kept as they are
+        let plain = |text: &str| Component::from_legacy(text, '&').flatten();
+        assert_eq!(plain("Tom & Jerry"), "Tom & Jerry");
+        assert_eq!(plain("50&% off"), "50&% off");
+        assert_eq!(plain("&&cred"), "&red");
+        assert_eq!(
+            Component::from_legacy("&&cred", '&'),
+            Component::default()
+                .append("&")
+                .append(Component::colored("red", NamedColor::Red))
+        );
+
+        assert_eq!(
+            legacy.to_legacy('§'),
+            "§cRed §lbold§r plain §3hex §ounderlined&x §zunknown§"
+        );
+        assert_eq!(
+            legacy.to_legacy_bungee_hex('&'),
+            "&cRed &lbold&r plain &x&0&0&a&a&f&fhex &ounderlined&x §zunknown§"
+        );
+        let parsed = Component::from_legacy(&legacy.to_legacy_bungee_hex('§'), '§');
+        assert_eq!(parsed, legacy);
+
+        assert_eq!(
+            lobster("<gold>Hello, <bold>World</bold>!").to_legacy('&'),
+            "&6Hello, &lWorld&6!"
+        );
+    }
+
+    #[test]
+    fn test_motd() {
+        let motd = lobster(
+            "<click:run_command:/join><gold><bold>Lobster</bold> Network</gold>\n\
+             Now with <#00AAFF>hex</#00AAFF> colors!\nThird line",
+        );
+        assert_eq!(
+            motd.to_motd(),
+            "§6§lLobster§6 Network\n§rNow with §3hex§r colors!"
+        );
+        assert_eq!(Component::text("Single").to_motd(), "Single");
+        assert_eq!(Component::text("").to_motd(), "");
+        assert_eq!(
+            Component::colored("a\n", NamedColor::Red)
+                .append(Component::colored("b", NamedColor::Blue))
+                .to_motd(),
+            "§ca\n§9b"
+        );
+    }
+
+    #[test]
+    fn test_strip_color() {
+        let hover = HoverEvent::show_text(lobster("<red>Red <bold>hover"));
+        let tree = lobster("<gold><bold>Gold</bold> <#00AAFF><click:run_command:/a>hex")
+            .append(Component::colored("child", NamedColor::Red).hover_event(hover.clone()));
+        assert!(tree.to_string().contains("\"color\""));
+
+        let stripped = tree.strip_color();
+        assert_eq!(stripped.colors_used(), HashSet::new());
+        assert_eq!(stripped.strip_formatting(), tree.strip_formatting());
+        assert!(stripped.semantically_eq(&stripped.strip_color()));
+        let runs = stripped.runs().collect::<Vec<_>>();
+        assert!(runs[0].1.bold);
+        assert_eq!(runs[2].1.click_event, Some(ClickEvent::run_command("/a")));
+        // hover contents keep their colors unless asked otherwise
+        assert_eq!(runs[3].1.hover_event, Some(hover));
+        assert!(stripped.to_string().contains("\"color\""));
+
+        let stripped = tree.strip_color_with_hover();
+        assert!(!stripped.to_string().contains("\"color\""));
+        assert!(stripped.to_string().contains("\"bold\""));
+    }
+
+    #[test]
+    fn test_paint() {
+        let hover = HoverEvent::show_text(Component::colored("hover", NamedColor::Red));
+        let tree = Component::colored("root", NamedColor::Red).append(
+            Component::colored("child", 0x00AAFF)
+                .hover_event(hover.clone())
+                .append(Component::text("grandchild").bold(true)),
+        );
+
+        let painted = tree.clone().paint(NamedColor::Gold);
+        let gold = TextColor::Named(NamedColor::Gold);
+        assert_eq!(
+            painted,
+            Component::colored("root", NamedColor::Gold).append(
+                Component::colored("child", NamedColor::Gold)
+                    .hover_event(hover)
+                    .append(Component::colored("grandchild", NamedColor::Gold).bold(true))
+            )
+        );
+        assert_eq!(painted.get_color_opt(), Some(&gold));
+
+        assert_eq!(
+            tree.clone().paint_with_hover(NamedColor::Gold),
+            Component::colored("root", NamedColor::Gold).append(
+                Component::colored("child", NamedColor::Gold)
+                    .hover_event(HoverEvent::show_text(Component::colored(
+                        "hover",
+                        NamedColor::Gold
+                    )))
+                    .append(Component::colored("grandchild", NamedColor::Gold).bold(true))
+            )
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let link =
+            Component::text("click here").click_event(ClickEvent::open_url("https://example.com"));
+        let tree = Component::text("Visit http://first")
+            .append(
+                Component::text("nested ")
+                    .append(link.clone())
+                    .append(Component::text("http://third")),
+            )
+            .append(Component::text("http://fourth"));
+
+        assert_eq!(
+            tree.find_first(|it| it.get_click_event().is_some()),
+            Some(&link)
+        );
+        assert_eq!(tree.find_first(|it| it.get_bold()), None);
+
+        let has_url = |it: &Component| {
+            it.get_text_content()
+                .is_some_and(|text| text.contains("http"))
+        };
+        let texts = tree
+            .find_all(has_url)
+            .into_iter()
+            .map(|it| it.get_text_content().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            ["Visit http://first", "http://third", "http://fourth"]
+        );
+        assert_eq!(tree.find_first(has_url), Some(&tree));
+    }
+
+    #[test]
+    fn test_io() {
+        let component = lobster("<red>Hello, <bold>World!");
+
+        // e.g. a packet buffer with a length prefix already written
+        let mut buf = vec![0u8; 2];
+        component.to_writer(&mut buf).unwrap();
+        assert_eq!(&buf[2..], component.to_string().as_bytes());
+
+        assert_eq!(Component::from_reader(&buf[2..]).unwrap(), component);
+        assert_eq!(
+            Component::from_json(&component.to_string()).unwrap(),
+            component
+        );
+        assert!(Component::from_reader(&buf[2..buf.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_gradient_across_runs() {
+        let stops = [0xFF0000, 0x0000FF];
+        let char_colors = |comp: &Component| {
+            comp.find_all(|it| {
+                it.get_text_content()
+                    .is_some_and(|text| text.chars().count() == 1)
+            })
+            .into_iter()
+            .map(|it| it.get_color_opt().cloned())
+            .collect::<Vec<_>>()
+        };
+
+        let mixed = lobster("<gradient:#FF0000:#0000FF><bold>Bold</bold> normal");
+        assert_eq!(
+            char_colors(&mixed),
+            char_colors(&Component::gradient("Bold normal", &stops))
+        );
+        assert_eq!(mixed.clone().flatten(), "Bold normal");
+        // the formatting is kept on the runs
+        assert!(mixed.find_first(|it| it.get_bold()).is_some());
+        assert_eq!(
+            mixed.find_all(|it| it.get_text_content().as_deref() == Some("B"))[0].get_color_opt(),
+            Some(&TextColor::from(0xFF0000))
+        );
+        assert_eq!(
+            mixed.find_all(|it| it.get_text_content().as_deref() == Some("l"))[1].get_color_opt(),
+            Some(&TextColor::from(0x0000FF))
+        );
+
+        // text after the scope is not colored, and nested gradients take over their own runs
+        let nested = lobster(
+            "<gradient:#FF0000:#0000FF>ab<gradient:#00FF00:#00FF00>cd</gradient>ef</gradient>gh",
+        );
+        assert_eq!(
+            char_colors(&nested)[..6],
+            [0xFF0000, 0xAA0055, 0x00FF00, 0x00FF00, 0x5500AA, 0x0000FF]
+                .map(|it| Some(TextColor::from(it)))
+        );
+        assert_eq!(
+            nested
+                .find_first(|it| it.get_text_content().as_deref() == Some("gh"))
+                .unwrap()
+                .get_color_opt(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_word_gradient() {
+        let text = "The quick  brown fox";
+        let words = Component::gradient_words(text, &[0xFF0000, 0x0000FF]);
+        let parts = words
+            .children()
+            .map(|it| {
+                (
+                    it.get_text_content().unwrap(),
+                    it.get_color_opt().unwrap().clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parts,
+            [
+                ("The ".to_string(), TextColor::from(0xFF0000)),
+                ("quick  ".to_string(), TextColor::from(0xAA0055)),
+                ("brown ".to_string(), TextColor::from(0x5500AA)),
+                ("fox".to_string(), TextColor::from(0x0000FF)),
+            ]
+        );
+        assert_eq!(words.clone().flatten(), text);
+        // the tree scales with the words instead of the characters
+        assert_eq!(words.count_leaves(), 4);
+        assert_eq!(
+            Component::gradient(text, &[0xFF0000, 0x0000FF]).count_leaves(),
+            20
+        );
+        assert_eq!(Component::rainbow_words(text.repeat(10)).count_leaves(), 31);
+        assert_eq!(
+            Component::gradient_words("  lead", &[0xFF0000, 0x0000FF]),
+            Component::default().append(Component::text("  lead").color(0xFF0000))
+        );
+        assert_eq!(Component::rainbow_words(""), Component::default());
+
+        assert_eq!(
+            lobster("<gradient:red:blue:word>The quick  brown fox"),
+            Component::default().append(Component::gradient_words(text, &[0xFF5555, 0x5555FF]))
+        );
+        assert_eq!(
+            lobster("<rainbow:word>The quick  brown fox"),
+            Component::default().append(Component::rainbow_words(text))
+        );
+        let reversed = lobster("<rainbow:!:word>a b");
+        assert_eq!(
+            reversed
+                .descendants()
+                .filter_map(|it| it.get_color_opt())
+                .collect::<Vec<_>>(),
+            [&TextColor::from(0x00FFFF), &TextColor::from(0xFF0000)]
+        );
+
+        // words split between runs share their color
+        let split = lobster("<gradient:#FF0000:#0000FF:word>Hel<bold>lo wor</bold>ld !");
+        let colors = split
+            .descendants()
+            .filter(|it| it.get_color_opt().is_some())
+            .map(|it| {
+                (
+                    it.get_text_content().unwrap(),
+                    it.get_color_opt().unwrap().clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colors,
+            [
+                ("Hel".to_string(), TextColor::from(0xFF0000)),
+                ("lo ".to_string(), TextColor::from(0xFF0000)),
+                ("wor".to_string(), TextColor::from(0x800080)),
+                ("ld ".to_string(), TextColor::from(0x800080)),
+                ("!".to_string(), TextColor::from(0x0000FF)),
+            ]
+        );
+        assert!(lobster_strict("<gradient:red:word>a b").is_err());
+    }
+
+    #[test]
+    fn test_empty_input() {
+        for empty in [
+            "",
+            "<red>",
+            "<red></red>",
+            "<bold></bold>",
+            "</bold>",
+            "<gradient:#FF0000:#0000FF></gradient>",
+            "<pride><#00AAFF></pride>",
+        ] {
+            let parsed = lobster(empty);
+            assert_eq!(parsed, Component::default(), "{:?}", empty);
+            assert_eq!(parsed.to_string(), r#"{"text":""}"#);
+            assert!(parsed.is_empty());
+        }
+
+        let whitespace = lobster("  \t ");
+        assert_eq!(whitespace, Component::default().append("  \t "));
+        assert!(!whitespace.is_empty());
+        assert_eq!(
+            lobster("<bold> </bold>"),
+            Component::default().append(Component::text(" ").bold(true))
+        );
+
+        assert!(Component::text("").append("").is_empty());
+        assert!(!Component::text("").append("text").is_empty());
+        assert!(!Component::translate::<&str, Component>("key", None).is_empty());
+    }
+
+    #[test]
+    fn test_reversed_gradients() {
+        let colors = |comp: &Component| {
+            comp.find_all(|it| {
+                it.get_text_content()
+                    .is_some_and(|text| text.chars().count() == 1)
+            })
+            .into_iter()
+            .map(|it| it.get_color_opt().cloned().unwrap())
+            .collect::<Vec<_>>()
+        };
+
+        let stops = [0xFF0000, 0x00FF00, 0x0000FF];
+        let forward = colors(&Component::gradient("Gradient", &stops));
+        let reversed = colors(&Component::gradient_rev("Gradient", &stops));
+        assert_eq!(forward.first(), reversed.last());
+        assert_eq!(forward.last(), reversed.first());
+        assert_eq!(
+            colors(&lobster("<gradient:#FF0000:#00FF00:#0000FF:!>Gradient")),
+            reversed
+        );
+
+        let forward = colors(&lobster("<rainbow>Rainbow"));
+        let reversed = colors(&lobster("<rainbow:!>Rainbow"));
+        assert_eq!(forward, colors(&Component::rainbow("Rainbow")));
+        assert_eq!(reversed, colors(&Component::rainbow_rev("Rainbow")));
+        assert_eq!(forward[0], TextColor::from(0xFF0000));
+        assert_eq!(forward.first(), reversed.last());
+        assert_eq!(forward.last(), reversed.first());
+
+        // the rainbow is spread over all the runs in its scope
+        assert_eq!(
+            colors(&lobster("<rainbow>Rain<bold>bow</bold></rainbow> plain")),
+            forward
+        );
+        assert!(lobster_strict("<rainbow:?>Rainbow").is_err());
+    }
+
+    #[test]
+    fn test_gradient_phase() {
+        let stops = [0xFF0000, 0x0000FF];
+        let plain = Component::gradient("Gradient", &stops);
+        assert_eq!(
+            Component::gradient_with_phase("Gradient", &stops, 0.0),
+            plain
+        );
+        assert_eq!(
+            Component::gradient_with_phase("Gradient", &stops, 1.0),
+            plain
+        );
+        assert_eq!(
+            Component::gradient_with_phase("Gradient", &stops, -1.0),
+            plain
+        );
+
+        let colors = |comp: Component| {
+            comp.children()
+                .map(|it| it.get_color_opt().cloned().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let plain = colors(Component::gradient("abcde", &stops));
+        // a quarter starts a quarter of the way through, and wraps around past the end
+        let shifted = Component::gradient_with_phase("abcde", &stops, 0.25);
+        let quarter = colors(shifted.clone());
+        assert_eq!(quarter[0], plain[1]);
+        assert_eq!(quarter[1], plain[2]);
+        assert_eq!(quarter[3], plain[4]);
+        assert_eq!(quarter[4], plain[1]);
+        // a half starts at the midpoint of the gradient
+        let half = colors(Component::gradient_with_phase("abcde", &stops, 0.5));
+        assert_eq!(half[0], TextColor::from(0x800080));
+        assert_eq!(half[0], plain[2]);
+        assert_eq!(half[2], plain[4]);
+        assert_eq!(half[3], plain[1]);
+
+        assert_eq!(
+            lobster("<gradient:red:blue:0.25>abcde"),
+            lobster("<gradient:red:blue:1.25>abcde")
+        );
+        assert_eq!(
+            lobster("<gradient:#FF0000:#0000FF:0.25>abcde"),
+            Component::default().append(shifted)
+        );
+        assert_eq!(
+            lobster("<gradient:#FF0000:#0000FF:0>Gradient"),
+            lobster("<gradient:#FF0000:#0000FF>Gradient")
+        );
+        assert_eq!(
+            lobster("<gradient:#FF0000:#0000FF:0.5:!>Gradient"),
+            lobster("<gradient:#0000FF:#FF0000:0.5>Gradient")
+        );
+        assert!(lobster_strict("<gradient:red:0.5>a").is_err());
+        assert!(lobster_strict("<gradient:red:blue:0.5:word>a b").is_ok());
+    }
+
+    #[test]
+    fn test_semantic_equality() {
+        let flat = Component::text("Hello, World!")
+            .color(NamedColor::Red)
+            .bold(true);
+        let nested = Component::default()
+            .append(
+                Component::text("Hello")
+                    .color(TextColor::Hex("#ff5555".into()))
+                    .bold(true)
+                    .append(Component::text(", ").italic(false)),
+            )
+            .append(Component::text(""))
+            .append(Component::text("World!").color(0xFF5555).bold(true));
+        assert_ne!(flat, nested);
+        assert!(flat.semantically_eq(&nested));
+        assert!(nested.semantically_eq(&flat));
+
+        // differences in the displayed text or style are still relevant
+        assert!(!flat.semantically_eq(&Component::text("Hello, World!").color(NamedColor::Red)));
+        assert!(!flat.semantically_eq(&Component::text("Hello World!").color(NamedColor::Red)));
+        assert!(!Component::text("Hi")
+            .click_event(ClickEvent::RunCommand("/hi".into()))
+            .semantically_eq(&Component::text("Hi")));
+
+        // hover text is compared semantically as well
+        let hover =
+            |comp: Component| Component::text("Hover").hover_event(HoverEvent::show_text(comp));
+        assert!(
+            hover(Component::text("A").append("B")).semantically_eq(&hover(Component::text("AB")))
+        );
+
+        // round trips through the parser keep the components semantically equal
+        let parsed = lobster("<red><bold>Hello</bold>, <#00AAFF>World!");
+        assert!(Component::from_json(&parsed.to_string())
+            .unwrap()
+            .semantically_eq(&parsed));
+        let expected = Component::text("Hello")
+            .color(NamedColor::Red)
+            .bold(true)
+            .append(Component::text(", ").color(0xFF5555).bold(false))
+            .append(Component::text("World!").color(0x00AAFF).bold(false));
+        assert!(parsed.semantically_eq(&expected));
+    }
+
+    #[test]
+    fn test_magic_alias() {
+        assert_eq!(lobster("<magic>x"), lobster("<obfuscated>x"));
+        assert_eq!(
+            lobster("<magic>Magic</magic> text"),
+            lobster("<obfuscated>Magic</obfuscated> text")
+        );
+        assert_eq!(lobster("<magic>x</obf>"), lobster("<obf>x</magic>"));
+        assert_eq!("magic".parse(), Ok(Formatting::Obfuscated));
+        assert_eq!(
+            Component::from_legacy("§kx", '§'),
+            Component::default().append(Component::text("x").obfuscated(true))
+        );
+    }
+
+    #[test]
+    fn test_runs() {
+        let comp = Component::text("Red ")
+            .color(NamedColor::Red)
+            .append(
+                Component::text("bold ")
+                    .bold(true)
+                    .append(Component::text("blue").color(0x5555FF)),
+            )
+            .append(Component::text(""))
+            .append(Component::text(" plain").color(NamedColor::White));
+        let runs = comp.runs().collect::<Vec<_>>();
+        let texts = runs
+            .iter()
+            .map(|(text, _)| text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["Red ", "bold ", "blue", " plain"]);
+
+        let red = TextColor::Named(NamedColor::Red);
+        assert_eq!(runs[0].1.color, Some(red.clone()));
+        assert!(!runs[0].1.bold);
+        assert_eq!(runs[1].1.color, Some(red));
+        assert!(runs[1].1.bold);
+        assert_eq!(runs[2].1.color, Some(TextColor::from(0x5555FF)));
+        assert!(runs[2].1.bold);
+        assert_eq!(runs[3].1.color, Some(TextColor::Named(NamedColor::White)));
+        assert!(!runs[3].1.bold && !runs[3].1.italic);
+
+        let hover = HoverEvent::show_text(Component::text("Hover"));
+        let hovered = Component::text("a")
+            .hover_event(hover.clone())
+            .append(Component::text("b"));
+        assert!(hovered
+            .runs()
+            .all(|(_, style)| style.hover_event.as_ref() == Some(&hover)));
+    }
+
+    #[test]
+    fn test_prepend_extend() {
+        let texts = |comp: &Component| comp.runs().map(|(text, _)| text).collect::<Vec<_>>();
+
+        let prepended = Component::default()
+            .prepend("c")
+            .prepend(Component::text("b"))
+            .prepend("a");
+        assert_eq!(texts(&prepended), ["a", "b", "c"]);
+        // the parent's own text is still displayed before all of its children
+        assert_eq!(
+            texts(&Component::text("parent").append("b").prepend("a")),
+            ["parent", "a", "b"]
+        );
+
+        let extended = Component::default()
+            .extend(["a", "b"].map(Component::text))
+            .extend(vec![Component::text("c")])
+            .extend(std::iter::empty());
+        assert_eq!(texts(&extended), ["a", "b", "c"]);
+        assert_eq!(
+            extended,
+            Component::default().append("a").append("b").append("c")
+        );
+        // an empty list of children is never serialized
+        let empty = Component::text("a").extend(std::iter::empty());
+        assert_eq!(empty, Component::text("a"));
+        assert_eq!(serde_json::to_string(&empty).unwrap(), r#"{"text":"a"}"#);
+    }
+
+    #[test]
+    fn test_reset_closes_all() {
+        let runs = |comp: &Component| {
+            comp.runs()
+                .map(|(text, style)| (text, style.color, style.bold))
+                .collect::<Vec<_>>()
+        };
+        let red = Some(TextColor::Named(NamedColor::Red));
+        assert_eq!(
+            runs(&lobster("<red><bold>a<reset>b")),
+            [
+                ("a".to_string(), red.clone(), true),
+                ("b".to_string(), None, false)
+            ]
+        );
+        // tags opened after the reset apply as usual
+        assert_eq!(
+            runs(&lobster("<bold>a<reset><red>b</red>c")),
+            [
+                ("a".to_string(), None, true),
+                ("b".to_string(), red, false),
+                ("c".to_string(), None, false)
+            ]
+        );
+
+        let reset = lobster("<gradient:red:blue><click:run_command:/a>ab<reset>c");
+        let (_, style) = reset.runs().last().unwrap();
+        assert_eq!(style, ResolvedStyle::default());
+
+        // closing tags after the reset have nothing left to close
+        assert!(lobster_strict("<red>a<reset>b").is_ok());
+        assert!(matches!(
+            lobster_strict("<red>a<reset>b</red>"),
+            Err(ParseError::UnbalancedTag { offset: 14, .. })
+        ));
+        assert!(matches!(
+            lobster_strict("<bold>a<reset>b</bold>"),
+            Err(ParseError::UnbalancedTag { offset: 15, .. })
+        ));
+    }
+
+    #[test]
+    fn test_with_extra() {
+        let children = vec![
+            Component::text("a"),
+            Component::text("b").color(NamedColor::Red),
+            Component::keybind("key.jump"),
+        ];
+        let appended = Component::text("root")
+            .append("a")
+            .append(Component::text("b").color(NamedColor::Red))
+            .append(Component::keybind("key.jump"));
+        assert_eq!(Component::text("root").with_extra_ref(&children), appended);
+        assert_eq!(Component::text("root").with_extra(children), appended);
+
+        // replaces the existing children, and an empty vector removes them
+        assert_eq!(
+            appended.clone().with_extra(vec![Component::text("c")]),
+            Component::text("root").append("c")
+        );
+        assert_eq!(appended.with_extra(Vec::new()), Component::text("root"));
+    }
+
+    #[test]
+    fn test_children_iter() {
+        let comp = Component::text("root")
+            .append(Component::text("a").append("a1").append("a2"))
+            .append("b");
+
+        let mut texts = Vec::new();
+        for child in &comp {
+            texts.push(child.get_text_content().unwrap());
+        }
+        assert_eq!(texts, ["a", "b"]);
+        assert_eq!(comp.children().len(), 2);
+
+        let deep = comp
+            .descendants()
+            .filter_map(Component::get_text_content)
+            .collect::<Vec<_>>();
+        assert_eq!(deep, ["a", "a1", "a2", "b"]);
+
+        let owned = comp.into_iter().collect::<Vec<_>>();
+        assert_eq!(owned[1], Component::text("b"));
+
+        let leaf = Component::text("leaf");
+        assert_eq!((&leaf).into_iter().count(), 0);
+        assert_eq!(leaf.descendants().count(), 0);
+        assert_eq!(leaf.into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_flatten_visible() {
+        let comp = Component::text("[")
+            .append(Component::text("\u{E001}").font("mypack:icons"))
+            .append("] ")
+            .append(
+                Component::text("Steve")
+                    .font("minecraft:uniform")
+                    .append(Component::text(" whispers ").font("alt"))
+                    .append(": hi"),
+            );
+        assert_eq!(comp.flatten_visible(), "[\u{E001}] Steve: hi");
+        assert_eq!(
+            comp.flatten_visible_with(&["mypack:icons", "minecraft:alt"]),
+            "[] Steve: hi"
+        );
+        assert_eq!(comp.flatten_visible_with(&[]), comp.clone().flatten());
+        // fonts are inherited by the children, unless they set their own
+        assert_eq!(
+            comp.flatten_visible_with(&["uniform"]),
+            "[\u{E001}]  whispers "
+        );
+
+        assert_eq!(
+            comp.to_string(),
+            Component::from_json(&comp.to_string()).unwrap().to_string()
+        );
+        assert!(comp.to_string().contains(r#""font":"mypack:icons""#));
+        assert!(!Component::text("a")
+            .font("alt")
+            .semantically_eq(&Component::text("a")));
+    }
+
+    #[test]
+    fn test_colors_used() {
+        let red = TextColor::Named(NamedColor::Red);
+        let hex_red = TextColor::Hex("#ff5555".into());
+        let gold = TextColor::Named(NamedColor::Gold);
+        let comp = Component::text("")
+            .color(NamedColor::Gold)
+            .append(Component::text("a").color(NamedColor::Red))
+            .append(
+                Component::text("b")
+                    .color(hex_red.clone())
+                    .append(Component::text("c").color(NamedColor::Red)),
+            )
+            .append(Component::text("d").hover_event(HoverEvent::show_text(
+                Component::text("hover").color(NamedColor::Blue),
+            )));
+
+        assert_eq!(
+            comp.colors_used(),
+            HashSet::from([gold.clone(), red.clone(), hex_red.clone()])
+        );
+        // the gold text is empty, but its color is inherited by `d`
+        assert_eq!(
+            comp.colors_used_inherited(),
+            HashSet::from([red.clone(), hex_red.clone(), gold.clone()])
+        );
+        assert_eq!(
+            Component::text("")
+                .color(NamedColor::Gold)
+                .append(Component::text("a").color(NamedColor::Red))
+                .colors_used_inherited(),
+            HashSet::from([red.clone()])
+        );
+
+        assert!(comp.uses_color(&red, false));
+        assert!(comp.uses_color(&hex_red, false));
+        assert!(!comp.uses_color(&TextColor::Hex("#FF5555".into()), false));
+        assert!(comp.uses_color(&TextColor::Hex("#FF5555".into()), true));
+        assert!(!comp.uses_color(&TextColor::Named(NamedColor::Blue), true));
+        assert!(Component::text("a").color(0xFFAA00).uses_color(&gold, true));
+        assert!(!Component::text("a").uses_color(&gold, true));
+    }
+
+    #[test]
+    fn test_deserialize_color() {
+        let read = |json: &str| serde_json::from_str::<TextColor>(json);
+        assert_eq!(read(r#""c""#).unwrap(), TextColor::Named(NamedColor::Red));
+        assert_eq!(read(r#""C""#).unwrap(), TextColor::Named(NamedColor::Red));
+        assert_eq!(read(r#""0""#).unwrap(), TextColor::Named(NamedColor::Black));
+        assert_eq!(read(r#""red""#).unwrap(), TextColor::Named(NamedColor::Red));
+        assert_eq!(
+            read(r##""#ff0000""##).unwrap(),
+            TextColor::Hex("#ff0000".into())
+        );
+        assert_eq!(
+            read(r##""#f00""##).unwrap(),
+            TextColor::Hex("#ff0000".into())
+        );
+        assert_eq!(read("16711680").unwrap(), TextColor::Hex("#FF0000".into()));
+
+        for invalid in [
+            r#""g""#,
+            r#""crimson""#,
+            r##""#ff00""##,
+            r#""""#,
+            "16777216",
+            "-1",
+        ] {
+            assert!(read(invalid).is_err(), "{} should be rejected", invalid);
+        }
+        let err = read(r#""crimson""#).unwrap_err().to_string();
+        assert!(err.contains("Invalid color 'crimson'"), "{}", err);
+
+        // legacy codes work inside of components as well
+        let comp = Component::from_json(r#"{"text":"a","color":"6"}"#).unwrap();
+        assert_eq!(comp, Component::text("a").color(NamedColor::Gold));
+        assert!(Component::from_json(r#"{"text":"a","color":"nope"}"#).is_err());
+    }
+
+    #[test]
+    fn test_lines() {
+        let comp = Component::text("first ")
+            .color(NamedColor::Red)
+            .append(Component::text("line\nsecond").bold(true))
+            .append(" line\n")
+            .append(Component::keybind("key.jump").color(NamedColor::Blue))
+            .append(Component::text("\n\n"));
+        let lines = comp.lines();
+        assert_eq!(
+            lines,
+            vec![
+                Component::default()
+                    .append(Component::text("first ").color(NamedColor::Red))
+                    .append(Component::text("line").color(NamedColor::Red).bold(true)),
+                Component::default()
+                    .append(Component::text("second").color(NamedColor::Red).bold(true))
+                    .append(Component::text(" line").color(NamedColor::Red)),
+                Component::default().append(Component::keybind("key.jump").color(NamedColor::Blue)),
+                Component::default(),
+                Component::default(),
+            ]
+        );
+        for (line, text) in lines.iter().zip(comp.clone().flatten().split('\n')) {
+            assert_eq!(line.clone().flatten(), text);
+        }
+
+        assert_eq!(
+            Component::text("no newlines").lines(),
+            vec![Component::default().append("no newlines")]
+        );
+        assert_eq!(Component::default().lines(), vec![Component::default()]);
+    }
+
+    #[test]
+    fn test_contrast_color() {
+        let white = TextColor::Named(NamedColor::White);
+        let black = TextColor::Named(NamedColor::Black);
+        assert_eq!(TextColor::contrast_on(&TextColor::from(0x202020)), white);
+        assert_eq!(TextColor::contrast_on(&NamedColor::DarkBlue.into()), white);
+        assert_eq!(TextColor::contrast_on(&TextColor::from(0xF0F0F0)), black);
+        assert_eq!(TextColor::contrast_on(&NamedColor::Yellow.into()), black);
+        // mid gray is slightly more readable with black text
+        assert_eq!(TextColor::contrast_on(&TextColor::from(0x808080)), black);
+
+        assert_eq!(black.luminance(), 0.0);
+        assert!((white.luminance() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_blend_colors() {
+        let black = TextColor::Named(NamedColor::Black);
+        let white = TextColor::from(0xFFFFFF);
+        assert_eq!(black.blend(&white, 0.5), TextColor::from(0x808080));
+        assert_eq!(black.blend(&white, 0.0), TextColor::from(0x000000));
+        assert_eq!(black.blend(&white, 1.0), white);
+        // out of range fractions are clamped
+        assert_eq!(black.blend(&white, 2.0), white);
+        assert_eq!(white.blend(&black, -1.0), white);
+
+        let red = TextColor::Named(NamedColor::Red);
+        assert_eq!(red.lighten(0.5), TextColor::from(0xFFAAAA));
+        assert_eq!(red.darken(0.5), TextColor::from(0x802B2B));
+        assert_eq!(red.lighten(0.0), TextColor::from(0xFF5555));
+        assert_eq!(red.darken(1.0), TextColor::from(0x000000));
+
+        // invalid colors are treated as white
+        assert_eq!(
+            TextColor::Hex("nope".into()).darken(0.5),
+            TextColor::from(0x808080)
+        );
+    }
+
+    #[test]
+    fn test_clear_style() {
+        let mut comp = Component::text("a")
+            .color(NamedColor::Red)
+            .bold(true)
+            .italic(false)
+            .underlined(true)
+            .append(Component::text("b").bold(true));
+
+        comp.clear_color();
+        assert_eq!(comp.get_color_opt(), None);
+        comp.clear_formatting(Formatting::Italic);
+        assert_eq!(
+            comp.to_string(),
+            r#"{"extra":[{"bold":true,"text":"b"}],"bold":true,"underlined":true,"text":"a"}"#
+        );
+
+        comp.clear_all_formatting();
+        assert_eq!(
+            comp.to_string(),
+            r#"{"extra":[{"bold":true,"text":"b"}],"text":"a"}"#
+        );
+        assert_eq!(
+            comp,
+            Component::text("a").append(Component::text("b").bold(true))
+        );
+
+        // clearing what was never set does nothing
+        let mut plain = Component::text("c");
+        plain.clear_color();
+        plain.clear_formatting(Formatting::Bold);
+        assert_eq!(plain, Component::text("c"));
+    }
+
+    #[test]
+    fn test_wrap_in() {
+        let child = Component::text("child").bold(true).color(NamedColor::Gold);
+        let parent = Component::text("")
+            .color(NamedColor::Red)
+            .italic(true)
+            .append("sibling");
+        let wrapped = child.clone().wrap_in(parent);
+        assert_eq!(wrapped.children().next(), Some(&child));
+
+        let runs = wrapped.runs().collect::<Vec<_>>();
+        assert_eq!(runs[0].0, "child");
+        assert_eq!(runs[0].1.color, Some(TextColor::Named(NamedColor::Gold)));
+        assert!(runs[0].1.bold && runs[0].1.italic);
+        assert_eq!(runs[1].0, "sibling");
+        assert_eq!(runs[1].1.color, Some(TextColor::Named(NamedColor::Red)));
+        assert!(!runs[1].1.bold && runs[1].1.italic);
+    }
+
+    #[test]
+    fn test_hoist_common_styles() {
+        let parsed = Component::text("")
+            .append(Component::colored("Hello, ", NamedColor::Red).bold(true))
+            .append(Component::colored("World", NamedColor::Red).italic(true))
+            .append(Component::colored("!", NamedColor::Red).bold(true));
+        let hoisted = parsed.hoist_common_styles();
+        assert!(hoisted.semantically_eq(&parsed));
+        assert!(hoisted.to_string().len() < parsed.to_string().len());
+        assert_eq!(
+            hoisted.get_color_opt(),
+            Some(&TextColor::Named(NamedColor::Red))
+        );
+        assert_eq!(hoisted.children().count(), 3);
+        assert!(hoisted.children().all(|it| it.get_color_opt().is_none()));
+
+        // overriding children keep their own styles
+        let mixed = Component::text("")
+            .append(Component::colored("a", NamedColor::Red).bold(true))
+            .append(Component::colored("b", NamedColor::Blue).bold(true));
+        let hoisted = mixed.hoist_common_styles();
+        assert!(hoisted.semantically_eq(&mixed));
+        assert_eq!(hoisted.get_color_opt(), None);
+        assert!(hoisted.get_bold());
+
+        // the text of the parent would change its style
+        let own_text = Component::text("parent")
+            .append(Component::colored("a", NamedColor::Red))
+            .append(Component::colored("b", NamedColor::Red));
+        assert_eq!(own_text.hoist_common_styles(), own_text);
+
+        // styles repeating the parent's are removed
+        let repeated = Component::text("parent")
+            .color(NamedColor::Red)
+            .append(Component::colored("a", NamedColor::Red))
+            .append(Component::colored("b", NamedColor::Blue));
+        let hoisted = repeated.hoist_common_styles();
+        assert!(hoisted.semantically_eq(&repeated));
+        assert_eq!(hoisted.children().next(), Some(&Component::text("a")));
+    }
+
+    #[test]
+    fn test_apply_formatting_recursive() {
+        let mut tree = Component::text("root")
+            .append(Component::text("child").bold(false))
+            .append(Component::text("nested").append(Component::text("leaf").italic(true)));
+        tree.apply_formatting_recursive(Formatting::Bold, true);
+        assert!(tree.get_bold());
+        assert_eq!(tree.descendants().count(), 3);
+        assert!(tree.descendants().all(|it| it.get_bold()));
+        assert!(tree
+            .runs()
+            .all(|(_, style)| style.bold && !style.underlined));
+
+        tree.apply_style_recursive(&Style {
+            color: Some(NamedColor::Aqua.into()),
+            bold: Some(false),
+            ..Default::default()
+        });
+        assert!(tree.runs().all(
+            |(_, style)| !style.bold && style.color == Some(TextColor::Named(NamedColor::Aqua))
+        ));
+        // unset fields are left as they are
+        let leaf = tree.descendants().last().unwrap();
+        assert_eq!(leaf.get_text_content().unwrap(), "leaf");
+        assert!(leaf.get_italic());
+    }
+
+    #[test]
+    fn test_display_width() {
+        let metrics = FontMetrics::vanilla();
+        // 'W' is 6 pixels wide, 'i' and '!' 2, 'l' 3, 't' 5 and the space 4
+        let comp = Component::text("Wil").append(Component::text("t !").bold(true));
+        assert_eq!(comp.display_width(&metrics), 6 + 2 + 3 + (5 + 4 + 2) + 3);
+        let obfuscated = Component::text("Wil").obfuscated(true);
+        assert_eq!(obfuscated.display_width(&metrics), 11);
+        assert_eq!(Component::default().display_width(&metrics), 0);
+        assert_eq!(FontMetrics::default(), metrics);
+
+        let cells = FontMetrics::new(1).range('\u{3040}'..='\u{30FF}', 2);
+        assert_eq!(lobster("<red>ab <bold>カナ").display_width(&cells), 7);
+    }
+
+    #[test]
+    fn test_collect_children() {
+        let children = vec![
+            Component::colored("Red", NamedColor::Red),
+            Component::text(" and "),
+            Component::colored("Blue", NamedColor::Blue).bold(true),
+        ];
+        let mut collected = children.clone().into_iter().collect::<Component>();
+        assert_eq!(collected.children().count(), 3);
+        assert_eq!(collected.flatten(), "Red and Blue");
+        assert!(collected.children().eq(children.iter()));
+        assert_eq!(collected.get_text_content().unwrap(), "");
+        assert_eq!(collected.get_color_opt(), None);
+        assert_eq!(Component::from(children), collected);
+
+        assert_eq!(Component::from(Vec::new()), Component::default());
+        let names = ["a", "b"]
+            .map(Component::text)
+            .into_iter()
+            .collect::<Component>();
+        assert_eq!(names, Component::text("").append("a").append("b"));
+    }
+
+    #[test]
+    fn test_repeat() {
+        let filled = Component::colored("|", NamedColor::Green).bold(true);
+        let empty = Component::colored("|", NamedColor::DarkGray);
+        let mut bar = Component::text("")
+            .append(filled.repeat(7))
+            .append(empty.repeat(3));
+        assert_eq!(bar.count_leaves(), 10);
+        assert_eq!(bar.flatten().chars().count(), 10);
+        let segments = bar
+            .descendants()
+            .filter(|it| it.get_text_content().is_some_and(|t| t == "|"));
+        assert_eq!(segments.count(), 10);
+        assert_eq!(filled.repeat(7).children().count(), 7);
+        assert!(filled.repeat(7).children().all(|it| *it == filled));
+
+        assert_eq!(filled.repeat(0), Component::default());
+        assert_eq!(Component::repeat_text("=-", 3), Component::text("=-=-=-"));
+        assert_eq!(Component::repeat_text("=", 0), Component::text(""));
+    }
+
+    #[test]
+    fn test_append_display() {
+        struct Rank(&'static str, u8);
+
+        impl std::fmt::Display for Rank {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "[{} {}]", self.0, self.1)
+            }
+        }
+
+        let mut comp = Component::text("")
+            .append_display(Rank("Knight", 3))
+            .append_str(" kills: ")
+            .append_display(-12);
+        assert_eq!(comp.flatten(), "[Knight 3] kills: -12");
+        assert_eq!(comp.children().last(), Some(&Component::text("-12")));
+
+        let built = Component::builder()
+            .append_display(4.5)
+            .append_display(NamedColor::Red.to_rgb())
+            .build();
+        assert_eq!(built.children().count(), 2);
+        assert_eq!(
+            built.children().next().unwrap().get_text_content().unwrap(),
+            "4.5"
+        );
+    }
+
+    #[test]
+    fn test_default_shadow() {
+        let shadow = |comp: Component| comp.clone().apply_default_shadow().get_shadow_color();
+        // white is the base color when there is none
+        assert_eq!(shadow(Component::text("a")), Some(0xFF3F3F3F));
+        assert_eq!(
+            shadow(Component::text("a").color(NamedColor::White)),
+            Some(0xFF3F3F3F)
+        );
+        assert_eq!(
+            shadow(Component::text("a").color(NamedColor::Black)),
+            Some(0xFF000000)
+        );
+        // every channel is scaled by a quarter and rounded down
+        assert_eq!(
+            shadow(Component::text("a").color(0x804020)),
+            Some(0xFF201008)
+        );
+        assert_eq!(
+            shadow(Component::text("a").color(0x030507)),
+            Some(0xFF000101)
+        );
+        assert_eq!(
+            shadow(Component::text("a").color(NamedColor::Red)),
+            Some(0xFF3F1515)
+        );
+        assert_eq!(
+            shadow(Component::text("a").color(TextColor::Hex("nope".into()))),
+            Some(0xFF3F3F3F)
+        );
+
+        let mut comp = Component::text("a").shadow_color(0x80FF0000);
+        assert_eq!(
+            comp.to_string(),
+            r#"{"shadow_color":2164195328,"text":"a"}"#
+        );
+        assert_eq!(Component::from_json(&comp.to_string()).unwrap(), comp);
+        assert_eq!(
+            comp.append("b").runs().last().unwrap().1.shadow_color,
+            Some(0x80FF0000)
+        );
+    }
+
+    #[test]
+    fn test_selector_tag() {
+        assert_eq!(
+            lobster("<selector:@e[type=zombie]>"),
+            Component::default().append(Component::entity("@e[type=zombie]", None::<Component>))
+        );
+
+        let json = lobster("<selector:@e[type=minecraft:zombie,limit=3]:, >").to_string();
+        assert_eq!(
+            json,
+            r#"{"extra":[{"selector":"@e[type=minecraft:zombie,limit=3]","separator":{"text":", "}}],"text":""}"#
+        );
+        assert_eq!(
+            lobster("<red><selector:@p>"),
+            Component::default()
+                .append(Component::entity("@p", None::<Component>).color(NamedColor::Red))
+        );
+        assert!(lobster_strict("<selector:>").is_err());
+    }
+
+    #[test]
+    fn test_score_tag() {
+        assert_eq!(
+            lobster("<score:Notch:kills>"),
+            Component::default().append(Component::score("Notch", "kills", None))
+        );
+        assert_eq!(
+            lobster("<score:@s:deaths:42>").to_string(),
+            r#"{"extra":[{"score":{"name":"@s","objective":"deaths","value":"42"}}],"text":""}"#
+        );
+        assert!(lobster_strict("<score:Notch>").is_err());
+        assert!(lobster_strict("<score:Notch::42>").is_err());
+        assert!(lobster_strict("<score:a:b:c:d>").is_err());
+    }
+
+    #[test]
+    fn test_nbt_tag() {
+        let none = None::<Component>;
+        assert_eq!(
+            lobster("<nbt:entity:@s:Health>"),
+            Component::default().append(Component::entity_nbt("Health", "@s", None, none.clone()))
+        );
+        assert_eq!(
+            lobster("<nbt:entity:@e[type=minecraft:pig,limit=1]:CustomName:interpret>"),
+            Component::default().append(Component::entity_nbt(
+                "CustomName",
+                "@e[type=minecraft:pig,limit=1]",
+                Some(true),
+                none.clone()
+            ))
+        );
+        assert_eq!(
+            lobster("<nbt:block:0 64 0:Items>").to_string(),
+            r#"{"extra":[{"nbt":"Items","block":"0 64 0"}],"text":""}"#
+        );
+        assert_eq!(
+            lobster("<nbt:storage:my:path:data>").to_string(),
+            r#"{"extra":[{"nbt":"data","storage":"my:path"}],"text":""}"#
+        );
+        assert_eq!(
+            lobster("<nbt:storage:my:path:data:interpret>"),
+            Component::default().append(Component::storage_nbt(
+                "data",
+                "my:path",
+                Some(true),
+                none
+            ))
+        );
+        assert!(lobster_strict("<nbt:entity:@s>").is_err());
+        assert!(lobster_strict("<nbt:player:@s:Health>").is_err());
+    }
+
+    #[test]
+    fn test_component_builder() {
+        let built = Component::builder()
+            .text("Items: ")
+            .color(NamedColor::Gray)
+            .child(|b| {
+                b.text("sword")
+                    .color(0x55FFFF)
+                    .formatted(Formatting::Underline, true)
+                    .hover_event(HoverEvent::show_text(Component::text("A sword")))
+                    .child(|b| b.text(" (enchanted)").italic(true))
+            })
+            .append(", ")
+            .child(|b| {
+                b.text("shield")
+                    .click_event(ClickEvent::RunCommand("/shield".into()))
+            })
+            .build();
+        let chained = Component::text("Items: ")
+            .color(NamedColor::Gray)
+            .append(
+                Component::text("sword")
+                    .color(0x55FFFF)
+                    .underlined(true)
+                    .hover_event(HoverEvent::show_text(Component::text("A sword")))
+                    .append(Component::text(" (enchanted)").italic(true)),
+            )
+            .append(", ")
+            .append(
+                Component::text("shield").click_event(ClickEvent::RunCommand("/shield".into())),
+            );
+        assert_eq!(built, chained);
+        assert_eq!(built.to_string(), chained.to_string());
+
+        // building leaves an empty builder behind
+        let mut builder = Component::builder();
+        builder.text("once");
+        assert_eq!(builder.build(), Component::text("once"));
+        assert_eq!(builder.build(), Component::default());
+    }
+
+    #[test]
+    fn test_gradient_named_stops() {
+        let expected = Component::gradient("Gradient", &[0xFF5555, 0x00FF00, 0xFFAA00]);
+        assert_eq!(
+            lobster("<gradient:red:#00ff00:gold>Gradient"),
+            Component::default().append(expected.clone())
+        );
+        assert_eq!(
+            lobster("<gradient:red:#0f0:gold>Gradient"),
+            Component::default().append(expected)
+        );
+        assert_eq!(
+            lobster("<gradient:#F55:blue:!>ab"),
+            Component::default().append(Component::gradient("ab", &[0x5555FF, 0xFF5555]))
+        );
+
+        assert_eq!(TextColor::from(NamedColor::Gold).to_rgb(), Some(0xFFAA00));
+        assert_eq!(TextColor::Hex("#00aaFF".into()).to_rgb(), Some(0x00AAFF));
+        assert_eq!(TextColor::Hex("nope".into()).to_rgb(), None);
+
+        // a gradient needs at least two valid stops
+        assert!(lobster_strict("<gradient:red>Gradient").is_err());
+        assert!(lobster_strict("<gradient:red:not_a_color>Gradient").is_err());
+        assert!(lobster_strict("<gradient:red:#12345>Gradient").is_err());
+        assert!(lobster_strict("<gradient:red:>Gradient").is_err());
+    }
+
+    #[test]
+    fn test_tree_metrics() {
+        let tree = Component::text("root")
+            .append(Component::text("a").append("a1").append("a2"))
+            .append("b")
+            .append(Component::text("c").append("c1").append("c2"));
+        assert_eq!(tree.count_leaves(), 5);
+        assert_eq!(tree.depth(), 3);
+
+        assert_eq!(Component::default().count_leaves(), 1);
+        assert_eq!(Component::default().depth(), 1);
+        let gradient = Component::gradient("Gradient", &[0xFF0000, 0x0000FF]);
+        assert_eq!(gradient.count_leaves(), 8);
+        assert_eq!(gradient.depth(), 2);
+
+        // hover text is nested, but its leaves are not counted
+        let hovered = Component::text("hover").hover_event(HoverEvent::show_text(tree));
+        assert_eq!(hovered.count_leaves(), 1);
+        assert_eq!(hovered.depth(), 4);
+    }
+
+    #[test]
+    fn test_default_separator() {
+        let entity = Component::entity("@a", None::<Component>).with_default_separator();
+        assert_eq!(entity, Component::entity("@a", Some(Component::text(", "))));
+        assert_eq!(
+            entity.to_string(),
+            r#"{"selector":"@a","separator":{"text":", "}}"#
+        );
+        assert_eq!(Component::from_json(&entity.to_string()).unwrap(), entity);
+
+        let nbt =
+            Component::entity_nbt("Health", "@e", None, None::<Component>).with_default_separator();
+        assert_eq!(
+            nbt.to_string(),
+            r#"{"nbt":"Health","separator":{"text":", "},"entity":"@e"}"#
+        );
+        assert_eq!(Component::from_json(&nbt.to_string()).unwrap(), nbt);
+
+        let custom = Component::storage_nbt(
+            "data",
+            "my:storage",
+            Some(true),
+            Some(Component::text(" | ")),
+        );
+        assert_eq!(Component::from_json(&custom.to_string()).unwrap(), custom);
+
+        // other components are left as they are
+        assert_eq!(
+            Component::text("a").with_default_separator(),
+            Component::text("a")
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        // inside of the first leaf
+        let long = Component::text("Hello, World!")
+            .color(NamedColor::Red)
+            .append(Component::text(" More").bold(true));
+        assert_eq!(
+            long.truncate(8, "..."),
+            Component::text("Hello...").color(NamedColor::Red)
+        );
+
+        // across multiple leaves, with the ellipsis styled like the last kept run
+        let truncated = long.truncate(16, "…");
+        assert_eq!(
+            truncated,
+            Component::text("Hello, World!")
+                .color(NamedColor::Red)
+                .append(Component::text(" M…").bold(true))
+        );
+        assert_eq!(
+            truncated
+                .runs()
+                .map(|(text, _)| text.chars().count())
+                .sum::<usize>(),
+            16
+        );
+
+        // short enough components are kept as they are
+        assert_eq!(long.truncate(18, "..."), long);
+        assert_eq!(long.truncate(100, "..."), long);
+
+        // multi-byte characters are never split
+        let wide = Component::text("ああああ").append(Component::text("いいい").italic(true));
+        assert_eq!(
+            wide.truncate(6, "."),
+            Component::text("ああああ").append(Component::text("い.").italic(true))
+        );
+        assert_eq!(wide.truncate(2, "..."), Component::text(".."));
+    }
+
+    #[test]
+    fn test_named_color_ordinals() {
+        let all = NamedColor::all();
+        for (idx, color) in all.iter().enumerate() {
+            assert_eq!(color.ordinal() as usize, idx);
+            assert_eq!(NamedColor::from_ordinal(color.ordinal()), Some(*color));
+            assert_eq!(
+                NamedColor::from_legacy_code(char::from_digit(idx as u32, 16).unwrap()),
+                Some(*color)
+            );
+        }
+        assert_eq!(all[0], NamedColor::Black);
+        assert_eq!(all[15], NamedColor::White);
+        assert_eq!(NamedColor::DarkRed.ordinal(), 4);
+        assert_eq!(NamedColor::from_ordinal(16), None);
+    }
+
+    #[test]
+    fn test_quoted_arguments() {
+        let hover = |text: &str| {
+            Component::default().append(Component::text("Hover").hover_event(
+                HoverEvent::show_text(Component::default().append(Component::text(text))),
+            ))
+        };
+        assert_eq!(
+            lobster(r"<hover:show_text:'It\'s here: now'>Hover"),
+            hover("It's here: now")
+        );
+        assert_eq!(
+            lobster(r#"<hover:show_text:"say \"hi\": \\o/">Hover"#),
+            hover(r#"say "hi": \o/"#)
+        );
+        // quoted arguments may contain tags, which are parsed as well
+        assert_eq!(
+            lobster("<hover:show_text:'<red>a:b</red>'>Hover"),
+            Component::default().append(Component::text("Hover").hover_event(
+                HoverEvent::show_text(
+                    Component::default().append(Component::text("a:b").color(NamedColor::Red))
+                )
+            ))
+        );
+        // quotes are only special at the start of an argument
+        assert_eq!(
+            lobster("<lang_or:item.key:Don't:'a:b'>"),
+            Component::default().append(
+                Component::translate("item.key", Some(vec![Component::text("a:b")]))
+                    .fallback("Don't")
+            )
+        );
+
+        let click = lobster("<click:run_command:'/say a:b'>Click</click> after");
+        assert_eq!(
+            click,
+            Component::default()
+                .append(Component::text("Click").click_event(ClickEvent::run_command("/say a:b")))
+                .append(Component::text(" after"))
+        );
+        assert_eq!(
+            lobster("<insert:'a:b'><bold>Insert"),
+            Component::default().append(Component::text("Insert").insert_text("a:b").bold(true))
+        );
+        assert!(lobster_strict("<click:explode:now>Click").is_err());
+        assert!(lobster_strict("<hover:show_item:'Not an ID'>Hover").is_err());
+        // unterminated quotes are kept as they are
+        assert_eq!(lobster("<hover:show_text:'open>Hover"), hover("'open"));
+    }
+
+    #[test]
+    fn test_strip_events() {
+        let hover = HoverEvent::show_text(
+            Component::text("Hover").click_event(ClickEvent::run_command("/hover")),
+        );
+        let tree = Component::text("root")
+            .color(NamedColor::Red)
+            .click_event(ClickEvent::run_command("/op @a"))
+            .append(
+                Component::text("child")
+                    .bold(true)
+                    .hover_event(hover.clone())
+                    .append(
+                        Component::text("link").click_event(ClickEvent::open_url("https://a.b")),
+                    ),
+            );
+
+        let stripped = tree.strip_events();
+        assert!(stripped
+            .find_first(|it| it.get_click_event().is_some() || it.get_hover_event().is_some())
+            .is_none());
+        assert_eq!(
+            stripped.strip_formatting(),
+            tree.strip_formatting().strip_events()
+        );
+        assert!(stripped.semantically_eq(
+            &Component::text("root")
+                .color(NamedColor::Red)
+                .append(Component::text("child").bold(true).append("link"))
+        ));
+
+        let commands = tree.strip_click_events_where(|e| matches!(e, ClickEvent::RunCommand(_)));
+        assert_eq!(commands.get_click_event(), None);
+        assert_eq!(
+            commands.find_all(|it| it.get_click_event().is_some()).len(),
+            1
+        );
+        // hover text is stripped as well, but the hover event is kept
+        let Some(HoverEvent::ShowText { contents }) = commands
+            .find_first(|it| it.get_hover_event().is_some())
+            .and_then(|it| it.get_hover_event())
+        else {
+            panic!("hover event was removed");
+        };
+        assert_eq!(contents.get_click_event(), None);
+
+        // the parser can ignore the event tags entirely
+        let parser = MiniMessageParser::new().strict(true).ignore_events(true);
+        let parsed = parser
+            .parse("<click:run_command:/op @a><hover:show_text:hi><red>Free</hover> op</click>!")
+            .unwrap();
+        assert!(parsed
+            .find_first(|it| it.get_click_event().is_some())
+            .is_none());
+        assert!(parsed
+            .find_first(|it| it.get_hover_event().is_some())
+            .is_none());
+        assert!(parsed.semantically_eq(&Component::text("Free op!").color(NamedColor::Red)));
+    }
+
+    #[test]
+    fn test_whitespace_fidelity() {
+        let mut parsed = lobster("a  <red>  b");
+        let runs = parsed
+            .runs()
+            .map(|(text, style)| (text, style.color))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            runs,
+            vec![
+                ("a  ".to_string(), None),
+                ("  b".to_string(), Some(TextColor::Named(NamedColor::Red)))
+            ]
+        );
+        assert_eq!(parsed.flatten(), "a    b");
+
+        // whitespace around closing tags, placeholders and at the edges is kept as well
+        let parser = MiniMessageParser::new().placeholder("p", Component::space(3));
+        let mut parsed = parser
+            .parse("  <bold> x </bold>\t<p>  <red><italic>   </italic> y  ")
+            .unwrap();
+        assert_eq!(parsed.flatten(), format!("   x \t{}y  ", " ".repeat(9)));
+        assert_eq!(lobster("   ").flatten(), "   ");
+
+        assert_eq!(Component::space(4), Component::text("    "));
+        assert_eq!(Component::space(0), Component::text(""));
+    }
+
+    #[test]
+    fn test_parser_limits() {
+        let nested = "<bold>a".repeat(100);
+        let parser = MiniMessageParser::new().max_depth(50);
+        let err = parser.parse(&nested).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::LimitExceeded {
+                limit: Limit::Depth,
+                offset: 49 * 7 + 6
+            }
+        );
+        let parsed = MiniMessageParser::new().parse(&nested).unwrap();
+        assert_eq!(parsed.depth(), 101);
+
+        let huge = format!("<gradient:red:blue>{}", "a".repeat(1000));
+        let parser = MiniMessageParser::new().max_leaves(500);
+        let err = parser.parse(&huge).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::LimitExceeded {
+                limit: Limit::Leaves,
+                ..
+            }
+        ));
+        assert!(MiniMessageParser::new().parse(&huge).is_ok());
+
+        let parser = MiniMessageParser::new()
+            .max_length(10)
+            .placeholder("p", Component::text("12345"));
+        assert!(parser.parse("<p> <red>1234").is_ok());
+        let err = parser.parse("<p> <red>12345").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::LimitExceeded {
+                limit: Limit::Length,
+                offset: 9
+            }
+        );
+        let err = parser.parse("<p><p><p>").unwrap_err();
+        assert_eq!(err.offset(), 6);
+
+        // the non-result functions just stop at the limit
+        assert_eq!(parser.parser("<p><p><p>").parse().count_leaves(), 2);
+        let huge = format!("<red>a<bold>{}", "a".repeat(300_000));
+        assert_eq!(lobster(&huge).flatten(), "a");
+        assert_eq!(lobster_lenient(&huge).flatten(), "a");
+        assert_eq!(lobster_with_legacy(&huge).flatten(), "a");
+        assert!(MiniMessageParser::new().lenient(true).parse(&huge).is_err());
+    }
+
+    #[test]
+    fn test_mixed_legacy_codes() {
+        assert!(lobster_with_legacy("&cHello <bold>World")
+            .semantically_eq(&lobster("<red>Hello <bold>World")));
+        assert!(lobster_with_legacy("§x§f§f§a§a§0§0Hex &lbold&r plain")
+            .semantically_eq(&lobster("<#ffaa00>Hex <bold>bold<reset> plain")));
+
+        // the color written last wins, and closing another color does not close it
+        let comp = lobster_with_legacy("<blue>Blue &cred</blue> still red");
+        let runs = comp.runs().collect::<Vec<_>>();
+        assert_eq!(runs[0].0, "Blue ");
+        assert_eq!(runs[0].1.color, Some(TextColor::Named(NamedColor::Blue)));
+        assert_eq!(runs[1].0, "red");
+        assert_eq!(runs[1].1.color, Some(TextColor::Named(NamedColor::Red)));
+        assert_eq!(runs[2].0, " still red");
+        assert_eq!(runs[2].1.color, Some(TextColor::Named(NamedColor::Red)));
+
+        let mut literal = lobster_with_legacy("Tom & Jerry &z &");
+        assert_eq!(literal.flatten(), "Tom & Jerry &z &");
+        let link = lobster_with_legacy("<click:open_url:'https://a.com/?a=1&b=2'>&alink");
+        let link = link.children().next().unwrap();
+        assert_eq!(
+            link.get_click_event(),
+            Some(&ClickEvent::open_url("https://a.com/?a=1&b=2"))
+        );
+        assert_eq!(
+            link.get_color_opt(),
+            Some(&TextColor::Named(NamedColor::Green))
+        );
+
+        let parser = MiniMessageParser::new().strict(true).legacy_codes(true);
+        let err = parser.parse("&c&lHi <nope>").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnknownTag {
+                tag: "<nope>".into(),
+                offset: 7
+            }
+        );
+
+        let msg = "&6Gold &x&1&2&3&4&5&6hex <bold>bold</bold> &k&cmagic &rplain §aend";
+        let expected = parser.parse(msg).unwrap();
+        for chunk_size in [1, 5, 13] {
+            let parsed = parse_chunked(&parser, msg.as_bytes(), chunk_size).unwrap();
+            assert!(parsed.semantically_eq(&expected), "chunks of {chunk_size}");
+        }
+        let msg = "&aaaaaaaaaaa <nope>";
+        let err = parse_chunked(&parser, msg.as_bytes(), 3).unwrap_err();
+        assert_eq!(err, parser.parse(msg).unwrap_err());
+        assert_eq!(err.offset(), 13);
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let hover = Component::text("Trust me")
+            .obfuscated(true)
+            .color(NamedColor::Green)
+            .click_event(ClickEvent::run_command("/hover"));
+        let hostile = Component::text("Free op ")
+            .color(NamedColor::Gold)
+            .bold(true)
+            .font("minecraft:alt")
+            .click_event(ClickEvent::run_command("/op @a"))
+            .hover_event(HoverEvent::show_text(hover))
+            .append(
+                Component::builder()
+                    .text("Shift-click")
+                    .insertion("/op @a")
+                    .build(),
+            )
+            .append(Component::entity("@a", None::<Component>).obfuscated(true))
+            .append(Component::text("x".repeat(1000)).shadow_color(0xFF000000u32));
+
+        let strict = hostile.sanitize(SanitizePolicy::strict());
+        assert_eq!(strict, hostile.sanitize(SanitizePolicy::default()));
+        assert_eq!(strict, strict.strip_formatting());
+        let text = strict.clone().flatten();
+        assert_eq!(text.chars().count(), 256);
+        assert!(text.starts_with("Free op Shift-click<entity message>xxx"));
+
+        let relaxed = hostile.sanitize(SanitizePolicy::relaxed());
+        assert_eq!(relaxed.get_color_opt(), Some(&NamedColor::Gold.into()));
+        assert!(relaxed.get_bold());
+        assert_eq!(relaxed.get_font(), None);
+        assert_eq!(relaxed.get_click_event(), None);
+        assert_eq!(relaxed.clone().flatten().chars().count(), 1035);
+        assert!(relaxed
+            .find_first(|it| {
+                it.get_obfuscated()
+                    || it.get_insertion().is_some()
+                    || it.get_text_content().is_none()
+            })
+            .is_none());
+        let Some(HoverEvent::ShowText { contents }) = relaxed.get_hover_event() else {
+            panic!("hover text was removed");
+        };
+        assert_eq!(
+            **contents,
+            Component::text("Trust me").color(NamedColor::Green)
+        );
+
+        // the arguments and separators of kept non-text contents are sanitized as well
+        let arg = Component::text("Free op")
+            .obfuscated(true)
+            .click_event(ClickEvent::run_command("/op @a"));
+        let hostile = Component::translate("chat.type.text", Some(vec![arg])).append(
+            Component::entity("@a", Some(Component::text(", ").obfuscated(true))),
+        );
+        let policy = SanitizePolicy {
+            keep_non_text: true,
+            ..SanitizePolicy::strict()
+        };
+        assert_eq!(
+            hostile.sanitize(policy),
+            Component::translate("chat.type.text", Some(vec![Component::text("Free op")]))
+                .append(Component::entity("@a", Some(Component::text(", "))))
+        );
+    }
+
+    #[test]
+    fn test_to_minimessage() {
+        let hover = Component::text("It's safe: \\o/")
+            .color(NamedColor::Green)
+            .append(Component::text(" trust me").italic(true));
+        let comp = Component::text("Click ")
+            .color(0x12AB34)
+            .append(
+                Component::text("here")
+                    .bold(true)
+                    .click_event(ClickEvent::run_command("/tell Steve 'hi': <3"))
+                    .hover_event(HoverEvent::show_text(hover))
+                    .append(Component::text(" or not").bold(false)),
+            )
+            .append(Component::builder().text("!").insertion("a:b").build())
+            .append(
+                Component::translate("chat.type.text", Some(vec![Component::text("A:B")]))
+                    .color(NamedColor::Gold),
+            )
+            .append(Component::entity(
+                "@e[type=minecraft:pig]",
+                None::<Component>,
+            ));
+
+        let serialized = comp.to_minimessage();
+        assert!(serialized.contains(
+            "<bold><click:run_command:'/tell Steve \\'hi\\': <3'><hover:show_text:'<green>It\\'s"
+        ));
+        let parsed = lobster_strict(&serialized).unwrap();
+        assert!(parsed.semantically_eq(&comp), "{serialized}");
+        assert_eq!(parsed.to_minimessage(), serialized);
+
+        let items = Component::text("Item")
+            .hover_event(HoverEvent::show_item(
+                DisplayItemData::try_new("minecraft:diamond", Some(3), None).unwrap(),
+            ))
+            .append(Component::score("@p", "kills", None).underlined(true));
+        assert_eq!(
+            items.to_minimessage(),
+            "<hover:show_item:'minecraft:diamond':3>Item<reset><underline><hover:show_item:'minecraft:diamond':3><score:'@p':'kills'>"
+        );
+        assert!(lobster_strict(items.to_minimessage())
+            .unwrap()
+            .semantically_eq(&items));
+
+        // text that looks like a tag can not be escaped, so it is parsed as one
+        let tagged = Component::text("use <red> tag");
+        assert_eq!(tagged.to_minimessage(), "use <red> tag");
+        assert!(!lobster(tagged.to_minimessage()).semantically_eq(&tagged));
+    }
+
+    #[test]
+    fn test_flatten_iter() {
+        let mut comp = Component::text("Hello, ")
+            .append(Component::translate::<&str, Component>(
+                "chat.type.text",
+                None,
+            ))
+            .append(
+                Component::text("")
+                    .append(Component::keybind("key.jump"))
+                    .append(Component::score("@p", "kills", None)),
+            )
+            .append(Component::entity("@a", None::<Component>).append("!"));
+        let parts = comp.flatten_iter().collect::<Vec<_>>();
+        assert_eq!(parts.len(), 7);
+        assert_eq!(parts.concat(), comp.flatten());
+        assert_eq!(
+            Component::text("leaf").flatten_iter().collect::<Vec<_>>(),
+            ["leaf"]
+        );
+    }
+
+    #[test]
+    fn test_try_keybind() {
+        let jump = Component::try_keybind("key.jump").unwrap();
+        assert_eq!(jump, Component::keybind("key.jump"));
+        assert!(Component::try_keybind("key.hotbar.9").is_ok());
+
+        let err = Component::try_keybind("key.jmup").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown keybind 'key.jmup', expected a vanilla keybind like `key.jump`"
+        );
+        // custom keybinds are only accepted by the lenient constructor
+        assert!(Component::try_keybind("key.mymod.dash").is_err());
+        assert_eq!(
+            Component::keybind("key.mymod.dash").flatten(),
+            "key.mymod.dash"
+        );
+    }
+
+    #[test]
+    fn test_to_json_minimal() {
+        let plain = Component::text("Hello, World!");
+        assert_eq!(plain.as_plain_if_unstyled(), Some("Hello, World!"));
+        assert_eq!(plain.to_json_minimal(), "\"Hello, World!\"");
+        assert!(plain.to_json_minimal().len() < plain.to_string().len());
+
+        let styled = Component::text("Hello, ")
+            .color(NamedColor::Gold)
+            .append("World")
+            .append(Component::text("!").bold(true))
+            .append(Component::translate(
+                "chat.type.text",
+                Some(vec![Component::text("Steve")]),
+            ))
+            .hover_event(HoverEvent::show_text(Component::text("hi")));
+        assert_eq!(styled.as_plain_if_unstyled(), None);
+        assert_eq!(
+            Component::text("a").append("b").as_plain_if_unstyled(),
+            None
+        );
+        let minimal = styled.to_json_minimal();
+        assert!(minimal.len() < styled.to_string().len());
+        assert!(minimal.contains(r#"["World",{"bold":true,"text":"!"},{"#));
+        assert!(minimal.contains(r#""with":["Steve"]"#));
+        assert!(minimal.contains(r#""contents":"hi""#));
+        // the object form is only kept where it is needed
+        let value: serde_json::Value = serde_json::from_str(&minimal).unwrap();
+        assert_eq!(value["color"], "gold");
+        assert_eq!(value["text"], "Hello, ");
+    }
+
+    #[test]
+    fn test_translate_arguments() {
+        let expected = Component::default().append(Component::translate(
+            "chat.type.text",
+            Some(vec![
+                Component::text("Steve").color(NamedColor::Red),
+                Component::text("Hello ")
+                    .color(NamedColor::Blue)
+                    .append(Component::text("world").bold(true)),
+            ]),
+        ));
+        for tag in ["tr", "translate", "lang"] {
+            let msg = format!("<{tag}:chat.type.text:'<red>Steve':'<blue>Hello <b>world'>");
+            assert!(
+                lobster_strict(&msg).unwrap().semantically_eq(&expected),
+                "{msg}"
+            );
+        }
+        assert_eq!(
+            lobster("<tr_or:item.key:Fallback:plain>"),
+            lobster("<lang_or:item.key:Fallback:plain>")
+        );
+
+        let parser = MiniMessageParser::new()
+            .strict(true)
+            .placeholder("player", Component::text("Alex").color(NamedColor::Gold));
+        let parsed = parser
+            .parse("<translate:death.attack.generic:'<player>'>")
+            .unwrap();
+        assert!(parsed.semantically_eq(&Component::translate(
+            "death.attack.generic",
+            Some(vec![Component::text("Alex").color(NamedColor::Gold)])
+        )));
+
+        let err = lobster_strict("<tr:chat.type.text:'Steve:Hello>").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnbalancedQuote {
+                tag: "<tr:chat.type.text:'Steve:Hello>".into(),
+                offset: 0
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Unbalanced quote in tag '<tr:chat.type.text:'Steve:Hello>' at offset 0"
+        );
+    }
+
+    #[test]
+    fn test_to_json_for() {
+        use crate::component::ProtocolVersion;
+        use serde_json::{json, Value};
+
+        let entity = DisplayEntityData::try_new(
+            Some(Component::text("Pig")),
+            "minecraft:pig",
+            uuid::Uuid::nil(),
+        )
+        .unwrap();
+        let comp = Component::text("Hi")
+            .color(0x12AB34)
+            .font("minecraft:uniform")
+            .shadow_color(0xFF000000)
+            .click_event(ClickEvent::change_page(2))
+            .hover_event(HoverEvent::show_text(
+                Component::text("hover").color(0xFF5555),
+            ))
+            .append(
+                Component::translate::<&str, Component>("item.key", None)
+                    .fallback("Item")
+                    .hover_event(HoverEvent::show_entity(entity)),
+            )
+            .append(Component::text("item").hover_event(HoverEvent::show_item(
+                DisplayItemData::try_new("minecraft:stone", Some(2), None).unwrap(),
+            )));
+        let json = |version| serde_json::from_str::<Value>(&comp.to_json_for(version)).unwrap();
+
+        let legacy = json(ProtocolVersion::V1_8);
+        assert_eq!(
+            legacy,
+            json!({
+                "color": "dark_green",
+                "text": "Hi",
+                "clickEvent": {"action": "change_page", "value": "2"},
+                "hoverEvent": {"action": "show_text", "value": {"color": "red", "text": "hover"}},
+                "extra": [
+                    {
+                        "translate": "item.key",
+                        "hoverEvent": {
+                            "action": "show_entity",
+                            "value": "{type:\"minecraft:pig\",id:\"00000000-0000-0000-0000-000000000000\",name:\"{\\\"text\\\":\\\"Pig\\\"}\"}"
+                        }
+                    },
+                    {
+                        "text": "item",
+                        "hoverEvent": {"action": "show_item", "value": "{id:\"minecraft:stone\",Count:2b}"}
+                    }
+                ]
+            })
+        );
+        // the legacy format is read back as the same events
+        let read = Component::from_json(&legacy.to_string()).unwrap();
+        assert_eq!(
+            read.get_hover_event(),
+            Some(&HoverEvent::show_text(
+                Component::text("hover").color(NamedColor::Red)
+            ))
+        );
+        assert_eq!(
+            read.children()
+                .map(Component::get_hover_event)
+                .collect::<Vec<_>>(),
+            comp.children()
+                .map(Component::get_hover_event)
+                .collect::<Vec<_>>()
+        );
+
+        let modern = json(ProtocolVersion::V1_16);
+        assert_eq!(modern["color"], "#12AB34");
+        assert_eq!(modern["font"], "minecraft:uniform");
+        assert_eq!(modern["hoverEvent"]["contents"]["color"], "#FF5555");
+        assert_eq!(modern.get("shadow_color"), None);
+        assert_eq!(modern["extra"][0].get("fallback"), None);
+        assert_eq!(
+            modern["extra"][0]["hoverEvent"]["contents"]["type"],
+            "minecraft:pig"
+        );
+
+        let nbt = json(ProtocolVersion::V1_20_3);
+        assert_eq!(nbt["extra"][0]["fallback"], "Item");
+        assert_eq!(nbt.get("shadow_color"), None);
+        assert_eq!(
+            nbt["clickEvent"],
+            json!({"action": "change_page", "value": "2"})
+        );
+        assert_eq!(
+            serde_json::from_str::<Value>(&comp.to_json_for(ProtocolVersion::V1_21_4)).unwrap(),
+            serde_json::from_str::<Value>(&comp.to_string()).unwrap()
+        );
+
+        let latest = json(ProtocolVersion::V1_21_5);
+        assert_eq!(
+            latest["click_event"],
+            json!({"action": "change_page", "page": 2})
+        );
+        assert_eq!(latest["hover_event"]["value"]["text"], "hover");
+        assert_eq!(
+            latest["extra"][0]["hover_event"],
+            json!({
+                "action": "show_entity",
+                "id": "minecraft:pig",
+                "uuid": "00000000-0000-0000-0000-000000000000",
+                "name": {"text": "Pig"}
+            })
+        );
+        assert_eq!(latest.get("clickEvent"), None);
+    }
+
+    #[test]
+    fn test_insert_around_match() {
+        let is_bold = |it: &Component| it.get_bold();
+        let mut comp = Component::text("")
+            .append("a")
+            .append(Component::text("b").bold(true))
+            .append(Component::text("c").bold(true));
+
+        assert!(comp.insert_before_match(is_bold, Component::text("<")));
+        assert!(comp.insert_after_match(is_bold, Component::text(">")));
+        assert_eq!(comp.flatten(), "a<b>c");
+        assert_eq!(comp.children().nth(3).unwrap(), &Component::text(">"));
+
+        // nothing is inserted without a match, including into a component without children
+        let unchanged = comp.clone();
+        assert!(!comp.insert_after_match(|it| it.get_italic(), Component::text("!")));
+        assert!(!comp.insert_before_match(|it| it.get_italic(), Component::text("!")));
+        assert_eq!(comp, unchanged);
+        let mut leaf = Component::text("leaf");
+        assert!(!leaf.insert_after_match(|_| true, Component::text("!")));
+        assert_eq!(leaf, Component::text("leaf"));
+    }
+
+    #[test]
+    fn test_rainbow_saturation() {
+        let colors = |comp: &Component| {
+            comp.find_all(|it| it.get_color_opt().is_some())
+                .into_iter()
+                .map(|it| it.get_color_opt().unwrap().clone())
+                .collect::<Vec<_>>()
+        };
+        let default = colors(&lobster("<rainbow>Rainbow"));
+        assert_eq!(colors(&lobster("<rainbow:s=1:v=1>Rainbow")), default);
+
+        let pastel = colors(&lobster("<rainbow:s=0.5>Rainbow"));
+        assert_eq!(pastel, colors(&Component::rainbow_hsv("Rainbow", 0.5, 1.0)));
+        assert_eq!(pastel.len(), default.len());
+        for (pastel, default) in pastel.iter().zip(&default) {
+            assert!(pastel.luminance() > default.luminance(), "{pastel:?}");
+        }
+        assert_eq!(pastel[0], TextColor::from(0xFF8080));
+
+        let dark = colors(&lobster("<rainbow:!:v=0.5:s=0.25:word>Dark rainbow"));
+        assert_eq!(dark[0], TextColor::from(0x608080));
+        assert_eq!(dark[1], TextColor::from(0x806060));
+        assert_eq!(
+            colors(&Component::rainbow_hsv("Rainbow", 0.0, 2.0))[0],
+            TextColor::from(0xFFFFFF)
+        );
+
+        for msg in [
+            "<rainbow:s=1.5>a",
+            "<rainbow:v=-0.1>a",
+            "<rainbow:x=1>a",
+            "<rainbow:s=>a",
+        ] {
+            let err = lobster_strict(msg).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidArgument { .. }), "{msg}");
+        }
+    }
+
+    #[test]
+    fn test_style_histogram() {
+        let tree = lobster(
+            "plain <red>red <bold>bold <#123456>hex</#123456></bold> <obf>secret</obf></red>",
+        );
+        let histogram = tree.style_histogram();
+        assert_eq!(histogram.total, tree.clone().flatten().chars().count());
+        assert_eq!(histogram.uncolored, "plain ".len());
+        assert_eq!(
+            histogram.colors[&NamedColor::Red.into()],
+            "red bold  secret".len()
+        );
+        assert_eq!(histogram.colors[&TextColor::from(0x123456)], "hex".len());
+        assert_eq!(histogram.colors.len(), 2);
+        assert_eq!(
+            histogram.count_formatted(Formatting::Bold),
+            "bold hex".len()
+        );
+        assert_eq!(
+            histogram.count_formatted(Formatting::Obfuscated),
+            "secret".len()
+        );
+        assert_eq!(histogram.count_formatted(Formatting::Italic), 0);
+        assert_eq!(histogram.formatting.len(), 2);
+
+        // explicitly disabled formatting is not counted
+        let unbolded = Component::text("ab")
+            .bold(true)
+            .append(Component::text("cd").bold(false));
+        assert_eq!(
+            unbolded.style_histogram().count_formatted(Formatting::Bold),
+            2
+        );
+        assert_eq!(Component::default().style_histogram(), Default::default());
+    }
+
+    #[test]
+    fn test_component_kind() {
+        let none = None::<Component>;
+        let cases = [
+            (Component::text("Hello"), ComponentKind::Text),
+            (Component::default(), ComponentKind::Text),
+            (Component::rainbow("Rainbow"), ComponentKind::Text),
+            (
+                Component::translate::<&str, Component>("item.key", None),
+                ComponentKind::Translate,
+            ),
+            (
+                Component::try_translate::<&str, Component>("item.key", None).unwrap(),
+                ComponentKind::Translate,
+            ),
+            (Component::score("@p", "kills", None), ComponentKind::Score),
+            (Component::entity("@a", none.clone()), ComponentKind::Entity),
+            (Component::keybind("key.jump"), ComponentKind::Keybind),
+            (
+                Component::try_keybind("key.jump").unwrap(),
+                ComponentKind::Keybind,
+            ),
+            (
+                Component::entity_nbt("Health", "@p", None, none.clone()),
+                ComponentKind::Nbt,
+            ),
+            (
+                Component::block_nbt("Items", "~ ~ ~", None, none.clone()),
+                ComponentKind::Nbt,
+            ),
+            (
+                Component::storage_nbt("data", "minecraft:storage", None, none),
+                ComponentKind::Nbt,
+            ),
+        ];
+        for (comp, kind) in cases {
+            assert_eq!(comp.kind(), kind, "{comp}");
+            assert_eq!(comp.is_plain(), kind == ComponentKind::Text);
+            assert_eq!(comp.is_translatable(), kind == ComponentKind::Translate);
+        }
+        // the kind only depends on the contents, not on the style or children
+        let styled = Component::keybind("key.jump").bold(true).append("!");
+        assert_eq!(styled.kind(), ComponentKind::Keybind);
+    }
+
+    #[test]
+    fn test_to_json_canonical() {
+        let comp = Component::translate("chat.type.text", Some(vec![Component::text("Steve")]))
+            .fallback("<Steve> hi")
+            .append(Component::text("!").shadow_color(0xFF000000))
+            .insert_text("Steve")
+            .font("minecraft:uniform")
+            .hover_event(HoverEvent::show_text(Component::text("hi").bold(true)))
+            .click_event(ClickEvent::suggest_command("/msg Steve "))
+            .obfuscated(false)
+            .underlined(true)
+            .bold(true)
+            .color(NamedColor::Gold);
+        let expected = concat!(
+            r#"{"translate":"chat.type.text","fallback":"<Steve> hi","with":[{"text":"Steve"}],"#,
+            r#""color":"gold","bold":true,"underlined":true,"obfuscated":false,"#,
+            r#""clickEvent":{"action":"suggest_command","value":"/msg Steve "},"#,
+            r#""hoverEvent":{"action":"show_text","contents":{"text":"hi","bold":true}},"#,
+            r#""insertion":"Steve","font":"minecraft:uniform","#,
+            r#""extra":[{"text":"!","shadow_color":4278190080}]}"#
+        );
+        assert_eq!(comp.to_json_canonical(), expected);
+        // the output only depends on the component, not on how it was built
+        let rebuilt = Component::from_json(&comp.to_string()).unwrap();
+        assert_eq!(rebuilt.to_json_canonical(), expected);
+        assert_eq!(Component::from_json(expected).unwrap(), comp);
+    }
+
+    #[test]
+    fn test_pixel_space() {
+        assert_eq!(
+            lobster("<space:4>"),
+            Component::default().append(Component::text("\u{F824}").font(SPACE_FONT))
+        );
+        assert_eq!(
+            lobster("a<space:-40>b"),
+            Component::default().append(
+                Component::text("a").append(
+                    Component::text("\u{F80A}\u{F808}")
+                        .font(SPACE_FONT)
+                        .append("b")
+                )
+            )
+        );
+        assert_eq!(
+            Component::pixel_space(1100).get_text_content().as_deref(),
+            Some("\u{F82F}\u{F82B}\u{F828}\u{F824}")
+        );
+        assert_eq!(
+            Component::pixel_space(0).get_text_content().as_deref(),
+            Some("")
+        );
+        assert_eq!(
+            lobster_strict("<space:wide>"),
+            Err(ParseError::InvalidArgument {
+                tag: "<space:wide>".into(),
+                offset: 0
+            })
+        );
+
+        // huge spaces are rejected by the length limit without building their text
+        assert_eq!(
+            lobster_strict("a<space:2147483647>"),
+            Err(ParseError::LimitExceeded {
+                limit: Limit::Length,
+                offset: 1
+            })
+        );
+        assert_eq!(lobster("a<space:-2147483648>b").flatten(), "a");
+        let limited = MiniMessageParser::new().max_length(3);
+        assert!(limited.parse("<space:3072>").is_ok());
+        assert!(limited.parse("<space:4096>").is_err());
+    }
+
+    #[test]
+    fn test_matches_template() {
+        let template = lobster("<yellow>player</yellow><gray> joined the game");
+        let joined = lobster("<yellow>Steve</yellow><gray> joined the game");
+        assert!(joined.matches_template(&template));
+        assert!(template.matches_template(&joined));
+        // the color differs
+        let renamed = lobster("<red>Steve</red><gray> joined the game");
+        assert!(!renamed.matches_template(&template));
+        // the tree is shaped differently
+        let flat = lobster("<yellow>Steve joined the game");
+        assert!(!flat.matches_template(&template));
+        // the hover text and the arguments of translations are templates as well
+        let translated = |name: &str| {
+            Component::translate(
+                "multiplayer.player.joined",
+                Some(vec![
+                    Component::text(name).hover_event(HoverEvent::show_text(Component::text(name)))
+                ]),
+            )
+            .color(NamedColor::Yellow)
+        };
+        assert!(translated("Steve").matches_template(&translated("Alex")));
+        assert!(!translated("Steve").matches_template(&Component::translate(
+            "multiplayer.player.left",
+            Some(vec![Component::text("Steve")])
+        )));
+        // only literal text is ignored
+        assert!(!Component::keybind("key.jump").matches_template(&Component::keybind("key.use")));
+    }
+
+    #[test]
+    fn test_color_from_str() {
+        assert_eq!(
+            Component::text("Hi").color("#ff00aa"),
+            Component::text("Hi").color(TextColor::Hex("#ff00aa".into()))
+        );
+        assert_eq!(
+            Component::text("Hi").color("red"),
+            Component::text("Hi").color(NamedColor::Red)
+        );
+        assert_eq!(
+            Component::text("Hi").color("#f0a"),
+            Component::text("Hi").color("#ff00aa")
+        );
+        // invalid colors leave the component unchanged
+        let colored = Component::text("Hi").color(NamedColor::Gold);
+        assert_eq!(colored.clone().color("#ff00zz"), colored);
+        assert_eq!(
+            Component::text("Hi").color("reddish"),
+            Component::text("Hi")
+        );
+        assert_eq!(
+            Component::text("Hi")
+                .try_color("reddish")
+                .unwrap_err()
+                .to_string(),
+            "Invalid color 'reddish', expected `#rrggbb`, `#rgb` or a named color"
+        );
+    }
+
+    #[test]
+    fn test_book_pages() {
+        let long = Component::text("a".repeat(1500))
+            .color(NamedColor::Red)
+            .append(Component::text("b".repeat(600)).bold(true));
+        let book = Book::new("Long", "Steve").page(long).page("Short");
+        let pages = book.split_pages();
+        assert_eq!(pages.len(), 4);
+        assert_eq!(
+            pages[0],
+            Component::default().append(Component::text("a".repeat(1024)).color(NamedColor::Red))
+        );
+        assert_eq!(
+            pages[1],
+            Component::default()
+                .append(Component::text("a".repeat(476)).color(NamedColor::Red))
+                .append(
+                    Component::text("b".repeat(548))
+                        .color(NamedColor::Red)
+                        .bold(true)
+                )
+        );
+        assert_eq!(pages[2].clone().flatten(), "b".repeat(52));
+        // pages short enough are kept as they are
+        assert_eq!(pages[3], Component::text("Short"));
+
+        // non-text contents are never split
+        let pages = Book::new("Keys", "Steve")
+            .max_page_chars(10)
+            .page(Component::text("Press ").append(Component::keybind("key.jump")))
+            .split_pages();
+        assert_eq!(
+            pages,
+            vec![
+                Component::default().append("Press "),
+                Component::default().append(Component::keybind("key.jump")),
+            ]
+        );
+        // text after a non-text part longer than a page starts on the next page
+        let pages = Book::new("Keys", "Steve")
+            .max_page_chars(5)
+            .page(Component::translate::<_, Component>("abcdefghij.key", None).append("xyz"))
+            .split_pages();
+        assert_eq!(
+            pages,
+            vec![
+                Component::default()
+                    .append(Component::translate::<_, Component>("abcdefghij.key", None)),
+                Component::default().append("xyz"),
+            ]
+        );
+
+        let tag = Book::new("It's", "Steve")
+            .max_page_chars(2)
+            .page("abc")
+            .build()
+            .unwrap()
+            .tag
+            .unwrap();
+        assert_eq!(
+            tag,
+            r#"{title:'It\'s',author:'Steve',pages:['{"extra":[{"text":"ab"}],"text":""}','{"extra":[{"text":"c"}],"text":""}']}"#
+        );
+        let err = Book::new("Long", "Steve")
+            .pages(vec![Component::text("a".repeat(101 * 1024))])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Book has 101 pages, but at most 100 are allowed"
+        );
+    }
+
+    #[test]
+    fn test_enforce_limits() {
+        let colors = [NamedColor::Red, NamedColor::Gold, NamedColor::Yellow];
+        let mut nested = Component::text("end");
+        for idx in 0..60 {
+            nested = Component::colored(idx.to_string(), colors[idx % 3])
+                .bold(idx % 2 == 0)
+                .append(Component::text("-"))
+                .append(nested);
+        }
+        assert_eq!(nested.depth(), 61);
+
+        let limited = nested.enforce_limits(8, 4);
+        assert!(limited.depth() <= 4);
+        assert!(limited.descendants().all(|it| it.children().len() <= 8));
+        assert!(limited.semantically_eq(&nested));
+
+        // too many differently styled parts to fit, so only the text is kept as is
+        let squashed = nested.enforce_limits(3, 3);
+        assert!(squashed.depth() <= 3);
+        assert!(squashed.descendants().all(|it| it.children().len() <= 3));
+        assert_eq!(squashed.clone().flatten(), nested.clone().flatten());
+        assert_eq!(
+            squashed.children().next().unwrap().children().next(),
+            Some(&Component::colored(
+                "59-58-57-56-55-54-53-",
+                NamedColor::Yellow
+            ))
+        );
+        let single = nested.enforce_limits(0, 5);
+        assert_eq!(single.depth(), 1);
+        assert_eq!(single.clone().flatten(), nested.clone().flatten());
+
+        // hover text is limited as well, and dropped if there is no room left for it
+        let hovered = Component::text("hi").hover_event(HoverEvent::show_text(nested.clone()));
+        let limited = hovered.enforce_limits(8, 5);
+        assert!(limited.depth() <= 5);
+        assert!(limited.semantically_eq(&hovered));
+        assert_eq!(hovered.enforce_limits(8, 1), Component::text("hi"));
+
+        // components within the limits are kept as they are
+        let small = Component::text("a").append(Component::text("b").bold(true));
+        assert_eq!(small.enforce_limits(1, 2), small);
+    }
+
+    #[test]
+    fn test_show_achievement() {
+        use crate::component::ProtocolVersion;
+
+        let comp = Component::text("Taking Inventory")
+            .hover_event(HoverEvent::show_achievement("achievement.openInventory"));
+        let json = comp.to_string();
+        assert_eq!(
+            json,
+            r#"{"text":"Taking Inventory","hoverEvent":{"action":"show_achievement","value":"achievement.openInventory"}}"#
+        );
+        assert_eq!(Component::from_json(&json).unwrap(), comp);
+        // the value may be written as a component as well
+        let legacy = r#"{"text":"","hoverEvent":{"action":"show_achievement","value":{"text":"achievement.mineWood"}}}"#;
+        assert_eq!(
+            Component::from_json(legacy).unwrap().get_hover_event(),
+            Some(&HoverEvent::show_achievement("achievement.mineWood"))
+        );
+        // only legacy versions display achievements
+        assert!(comp
+            .to_json_for(ProtocolVersion::V1_8)
+            .contains("show_achievement"));
+        assert!(!comp
+            .to_json_for(ProtocolVersion::V1_16)
+            .contains("hoverEvent"));
+        assert_eq!(comp.to_minimessage(), "Taking Inventory");
+    }
+
+    #[test]
+    fn test_reset_ends_gradient() {
+        let colors = |comp: &Component| {
+            comp.runs()
+                .map(|(text, style)| (text, style.color.and_then(|it| it.to_rgb())))
+                .collect::<Vec<_>>()
+        };
+        // the gradient is spread over the text before the reset only
+        assert_eq!(
+            colors(&lobster("<gradient:red:blue>Hi <reset>World")),
+            [
+                ("H".to_string(), Some(0xFF5555)),
+                ("i".to_string(), Some(0xAA55AA)),
+                (" ".to_string(), Some(0x5555FF)),
+                ("World".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            colors(&lobster("<rainbow:word>one two<reset> three")),
+            [
+                ("one ".to_string(), Some(0xFF0000)),
+                ("two".to_string(), Some(0x00FFFF)),
+                (" three".to_string(), None),
+            ]
+        );
+        // a gradient opened after the reset starts a new scope
+        assert_eq!(
+            colors(&lobster(
+                "<gradient:red:blue>a<reset>b<gradient:green:blue>cd"
+            )),
+            [
+                ("a".to_string(), Some(0xFF5555)),
+                ("b".to_string(), None),
+                ("c".to_string(), Some(0x55FF55)),
+                ("d".to_string(), Some(0x5555FF)),
+            ]
+        );
+        // nothing is left to color after a reset right after the gradient
+        assert_eq!(
+            lobster("<gradient:red:blue><reset>World"),
+            Component::default().append("World")
+        );
+        assert!(matches!(
+            lobster_strict("<gradient:red:blue>a<reset>b</gradient>"),
+            Err(ParseError::UnbalancedTag { offset: 28, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        use std::io::BufReader;
+
+        let lenient = MiniMessageParser::new().lenient(true);
+        let messages = [
+            "<red>Hello, <bold>World!</bold> plain <#ffaa00>hex <hover:show_text:'<green>a > b'>hover</hover> end",
+            "<gradient:red:blue>Some long gradient text</gradient> after <rainbow>rain</rainbow>!",
+            "<click:run_command:/say hi>click <u>me</u></click> <3 <reset>heart",
+            "ünïcödé <red>テキスト <i>です",
+        ];
+        for msg in messages {
+            let expected = lenient.parse(msg).unwrap();
+            for chunk_size in [1, 4, 16, 8192] {
+                let reader = BufReader::with_capacity(3, msg.as_bytes());
+                let parsed = parse_chunked(&lenient, reader, chunk_size).unwrap();
+                assert!(
+                    parsed.semantically_eq(&expected),
+                    "{msg} in chunks of {chunk_size}"
+                );
+            }
+        }
+        let parsed = Component::parse_minimessage_reader(messages[1].as_bytes()).unwrap();
+        assert!(parsed.semantically_eq(&messages[1].parse().unwrap()));
+
+        let parser = MiniMessageParser::new().strict(true);
+        let msg = "<red>aaaaaaa <bold>bbbb</bold> <unknown>b";
+        let expected = parser.parse(msg).unwrap_err();
+        let err = parse_chunked(&parser, msg.as_bytes(), 4).unwrap_err();
+        assert_eq!(err, expected);
+
+        let limited = MiniMessageParser::new().max_length(10);
+        let msg = "<red>12345</red> <blue>123456";
+        let expected = limited.parse(msg).unwrap_err();
+        let err = parse_chunked(&limited, msg.as_bytes(), 2).unwrap_err();
+        assert_eq!(err, expected);
+
+        let err = parser.parse_reader(&b"ab\xFFc"[..]).unwrap_err();
+        assert!(matches!(err, ParseError::Read { offset: 2, .. }));
+        let err = parser.parse_reader(&b"<red>ab\xE3\x81"[..]).unwrap_err();
+        assert!(matches!(err, ParseError::Read { offset: 7, .. }));
+    }
+
     // #[bench]
     // #[cfg(feature = "minimessage")]
     // fn benchmark_lobster(bencher: &mut Bencher) {
@@ -1,37 +1,153 @@
 //! This module contains minimessage implementation and related function
 
-use crate::{
-    component::{AsComponent, Component},
-    message::tokens::Parser,
-};
-use logos::Lexer;
+use crate::component::{AsComponent, Component};
 
+mod error;
+mod flags;
+mod legacy;
+mod parser;
+pub(crate) mod reader;
 pub(crate) mod tokens;
 
+pub use error::{Limit, ParseError};
+pub use parser::{MiniMessageParser, PlaceholderResolver};
+
+/// Default maximum count of leaf components in a parsed message, see [`MiniMessageParser::max_leaves()`]
+pub const DEFAULT_MAX_LEAVES: usize = 32_768;
+
+/// Default maximum nesting depth of a parsed message, see [`MiniMessageParser::max_depth()`]
+pub const DEFAULT_MAX_DEPTH: usize = 1024;
+
+/// Default maximum text length of a parsed message, see [`MiniMessageParser::max_length()`]
+pub const DEFAULT_MAX_LENGTH: usize = 262_144;
+
 /// Constructs a component from the provided minimessage string
 /// See [Adventure MiniMessage](https://docs.adventure.kyori.net/minimessage/index.html) for more info
+///
+/// The resulting component is always an empty text component, containing a child for each
+/// differently styled run of text. Empty messages, as well as messages consisting only of tags,
+/// result in the minimal [`Component::default()`] without any children, while whitespace
+/// is kept as is, like any other text.
+///
+/// A `<reset>` tag closes all the currently open tags, so the text after it has no color,
+/// formatting or events. Closing tags after a `<reset>` do not close anything anymore, and
+/// are treated like any other unbalanced closing tag, e.g. failing [`lobster_strict`].
+///
+/// The message is cut off at the first tag or run of text that would cross one of the
+/// limits of [`MiniMessageParser`], which is dropped along with the rest of the message,
+/// e.g. a run of text longer than [`DEFAULT_MAX_LENGTH`] is dropped entirely instead of truncated.
+/// Use [`MiniMessageParser::parse()`] to get a [`ParseError::LimitExceeded`] instead.
+///
+/// ```rust
+/// use lobsterchat::component::Component;
+/// use lobsterchat::lobster;
+///
+/// assert_eq!(lobster(""), Component::default());
+/// assert_eq!(lobster("<red><bold></bold></red>"), Component::default());
+/// assert!(lobster("<red></red>").is_empty());
+/// assert_eq!(lobster("   "), Component::default().append("   "));
+/// assert_eq!(lobster("<red>a<reset>b").flatten(), "ab");
+/// ```
 pub fn lobster<S: Into<String>>(msg: S) -> Component {
-    use logos::Logos;
     let st = msg.into();
-    let lexer: Lexer<tokens::MessageToken> = tokens::MessageToken::lexer(&st);
-    let parser = Parser::new(lexer);
+    MiniMessageParser::new().parser(&st).parse()
+}
+
+/// Constructs a component from the provided minimessage string, failing on the first
+/// unknown tag, malformed hex color or unbalanced closing tag instead of silently
+/// skipping the rest of the message.
+/// See [Adventure MiniMessage](https://docs.adventure.kyori.net/minimessage/index.html) for more info
+pub fn lobster_strict<S: Into<String>>(msg: S) -> Result<Component, ParseError> {
+    let st = msg.into();
+    MiniMessageParser::new().strict(true).parse(&st)
+}
+
+/// Constructs a component from the provided minimessage string, keeping unknown tags,
+/// stray angle brackets (e.g. `<3`) and unbalanced closing tags as literal text.
+/// The message is still cut off at the parser limits, the same way as in [`lobster`].
+/// See [Adventure MiniMessage](https://docs.adventure.kyori.net/minimessage/index.html) for more info
+pub fn lobster_lenient<S: Into<String>>(msg: S) -> Component {
+    let st = msg.into();
+    MiniMessageParser::new().lenient(true).parser(&st).parse()
+}
 
-    parser.parse()
+/// Constructs a component from the provided minimessage string, where legacy `&` and `§`
+/// codes may be mixed in with the tags, e.g. in old configs.
+/// See [Adventure MiniMessage](https://docs.adventure.kyori.net/minimessage/index.html) for more info
+///
+/// The codes are translated into the equivalent tags before parsing, so `&c` becomes `<red>`,
+/// `&l` becomes `<bold>`, `&r` becomes `<reset>` and `&x&r&r&g&g&b&b` becomes `<#rrggbb>`.
+/// Codes inside of tags, e.g. in URLs, and ampersands that are not a part of a code are kept as is.
+///
+/// Colors set by codes and tags follow the same rules, so the color written last takes
+/// precedence until it is closed. Codes open tags that are never closed explicitly, so they last
+/// until the next `&r` or `<reset>`, or until a closing tag of the same color, e.g. `</red>` for
+/// `&c`. Unlike in legacy strings, color codes do not reset the formatting.
+///
+/// Like in [`lobster`], the translated message is cut off at the first part crossing
+/// one of the parser limits.
+///
+/// ```rust
+/// use lobsterchat::message::lobster_with_legacy;
+///
+/// let comp = lobster_with_legacy("&cHello <bold>World");
+/// assert!(comp.semantically_eq(&lobsterchat::lobster("<red>Hello <bold>World")));
+/// ```
+pub fn lobster_with_legacy<S: Into<String>>(msg: S) -> Component {
+    let st = legacy::translate(&msg.into()).text;
+    MiniMessageParser::new().parser(&st).parse()
 }
 
 /// Constructs a component from the provided minimessage string and placeholders
 /// See [Adventure MiniMessage](https://docs.adventure.kyori.net/minimessage/index.html) for more info
+///
+/// The text of the placeholders counts towards the parser limits, and the message is cut off
+/// at the first placeholder, tag or run of text crossing them, the same way as in [`lobster`].
 pub fn placeholder_lobster<S: Into<String>, C: AsComponent + Sized, const N: usize>(
     msg: S,
     placeholders: [(S, C); N],
 ) -> Component {
-    use logos::Logos;
     let st = msg.into();
-    let lexer: Lexer<tokens::MessageToken> = tokens::MessageToken::lexer(&st);
-    let mut parser = Parser::new(lexer);
+    let mut options = MiniMessageParser::new();
     for (k, v) in placeholders {
-        parser.placeholder(k, v)
+        options = options.placeholder(k, v)
+    }
+
+    options.parser(&st).parse()
+}
+
+/// Maximum depth of placeholders referencing other placeholders in [`placeholder_lobster_str`].
+/// Placeholders nested deeper than this are kept as literal text.
+pub const MAX_PLACEHOLDER_DEPTH: usize = 8;
+
+/// Constructs a component from the provided minimessage string and placeholders,
+/// where the placeholder values are minimessage strings themselves. Placeholder values
+/// are parsed with access to all the other placeholders, so a placeholder can reference another one,
+/// up to [`MAX_PLACEHOLDER_DEPTH`] levels deep. Both the message and the placeholder values
+/// are cut off at the parser limits, see [`placeholder_lobster`].
+/// See [Adventure MiniMessage](https://docs.adventure.kyori.net/minimessage/index.html) for more info
+pub fn placeholder_lobster_str<S: Into<String>, const N: usize>(
+    msg: S,
+    placeholders: [(&str, &str); N],
+) -> Component {
+    parse_str_placeholders(&msg.into(), &placeholders, 0)
+}
+
+fn parse_str_placeholders(msg: &str, placeholders: &[(&str, &str)], depth: usize) -> Component {
+    let mut options = MiniMessageParser::new();
+    if depth < MAX_PLACEHOLDER_DEPTH {
+        for (name, value) in placeholders {
+            // only expanding the placeholders that are actually used
+            if msg.contains(&format!("<{}>", name)) {
+                options = options.placeholder(
+                    *name,
+                    parse_str_placeholders(value, placeholders, depth + 1),
+                );
+            }
+        }
+    } else {
+        options = options.lenient(true);
     }
 
-    parser.parse()
+    options.parser(msg).parse()
 }
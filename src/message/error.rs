@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// A limit on the size of the parsed component, see [`MiniMessageParser`](crate::message::MiniMessageParser)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// Maximum total count of leaf components
+    Leaves,
+    /// Maximum nesting depth of the component tree
+    Depth,
+    /// Maximum total length of the text, in characters
+    Length,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Limit::Leaves => "leaf count",
+            Limit::Depth => "nesting depth",
+            Limit::Length => "text length",
+        })
+    }
+}
+
+/// An error that occurred while parsing a minimessage string.
+///
+/// Every variant carries the byte offset of the offending tag
+/// or text in the source string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A tag that is neither a known tag nor a registered placeholder
+    UnknownTag {
+        /// The tag as written in the source, including the angle brackets
+        tag: String,
+        /// Byte offset of the tag in the source
+        offset: usize,
+    },
+    /// A `<name>` tag that matches no placeholder, when the parser has any
+    /// placeholders or placeholder resolvers registered
+    UndefinedPlaceholder {
+        /// The tag as written in the source, including the angle brackets
+        tag: String,
+        /// Byte offset of the tag in the source
+        offset: usize,
+    },
+    /// A hex color tag that is not in the `<#rrggbb>` format
+    MalformedHex {
+        /// The tag as written in the source, including the angle brackets
+        tag: String,
+        /// Byte offset of the tag in the source
+        offset: usize,
+    },
+    /// A known tag with an invalid argument, e.g. an unknown `<pride>` flag
+    InvalidArgument {
+        /// The tag as written in the source, including the angle brackets
+        tag: String,
+        /// Byte offset of the tag in the source
+        offset: usize,
+    },
+    /// A translation tag with an argument that starts with a quote which is never closed
+    UnbalancedQuote {
+        /// The tag as written in the source, including the angle brackets
+        tag: String,
+        /// Byte offset of the tag in the source
+        offset: usize,
+    },
+    /// A closing tag that does not close any currently open tag
+    UnbalancedTag {
+        /// The tag as written in the source, including the angle brackets
+        tag: String,
+        /// Byte offset of the tag in the source
+        offset: usize,
+    },
+    /// The message could not be read from its source, e.g. because of an IO error or invalid UTF-8
+    Read {
+        /// Description of the underlying error
+        message: String,
+        /// Byte offset in the source up to which the message was read
+        offset: usize,
+    },
+    /// The parsed component grew past one of the limits of the parser
+    LimitExceeded {
+        /// The limit that was exceeded
+        limit: Limit,
+        /// Byte offset of the tag or text in the source that exceeded the limit
+        offset: usize,
+    },
+}
+
+impl ParseError {
+    /// Gets the byte offset of the tag that caused this error
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::UnknownTag { offset, .. }
+            | ParseError::UndefinedPlaceholder { offset, .. }
+            | ParseError::MalformedHex { offset, .. }
+            | ParseError::InvalidArgument { offset, .. }
+            | ParseError::UnbalancedQuote { offset, .. }
+            | ParseError::UnbalancedTag { offset, .. }
+            | ParseError::Read { offset, .. }
+            | ParseError::LimitExceeded { offset, .. } => *offset,
+        }
+    }
+
+    /// Moves the offset of this error by the provided amount, for errors
+    /// in a part of a message that does not start at its beginning
+    pub(crate) fn shifted(mut self, by: isize) -> Self {
+        match &mut self {
+            ParseError::UnknownTag { offset, .. }
+            | ParseError::UndefinedPlaceholder { offset, .. }
+            | ParseError::MalformedHex { offset, .. }
+            | ParseError::InvalidArgument { offset, .. }
+            | ParseError::UnbalancedQuote { offset, .. }
+            | ParseError::UnbalancedTag { offset, .. }
+            | ParseError::Read { offset, .. }
+            | ParseError::LimitExceeded { offset, .. } => {
+                *offset = offset.saturating_add_signed(by)
+            }
+        }
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnknownTag { tag, offset } => {
+                write!(f, "Unknown tag '{}' at offset {}", tag, offset)
+            }
+            ParseError::UndefinedPlaceholder { tag, offset } => {
+                write!(f, "Undefined placeholder '{}' at offset {}", tag, offset)
+            }
+            ParseError::MalformedHex { tag, offset } => {
+                write!(f, "Malformed hex color '{}' at offset {}", tag, offset)
+            }
+            ParseError::InvalidArgument { tag, offset } => {
+                write!(f, "Invalid argument in tag '{}' at offset {}", tag, offset)
+            }
+            ParseError::UnbalancedQuote { tag, offset } => {
+                write!(f, "Unbalanced quote in tag '{}' at offset {}", tag, offset)
+            }
+            ParseError::UnbalancedTag { tag, offset } => {
+                write!(f, "Unbalanced closing tag '{}' at offset {}", tag, offset)
+            }
+            ParseError::Read { message, offset } => {
+                write!(
+                    f,
+                    "Failed to read the message at offset {}: {}",
+                    offset, message
+                )
+            }
+            ParseError::LimitExceeded { limit, offset } => {
+                write!(f, "Exceeded the maximum {} at offset {}", limit, offset)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,37 @@
+//! Color stops of the flags available in the `<pride>` tag
+
+/// Name of the flag used by `<pride>` when no flag is provided
+pub(crate) const DEFAULT_FLAG: &str = "pride";
+
+const FLAGS: &[(&str, &[u32])] = &[
+    (
+        "pride",
+        &[0xE50000, 0xFF8D00, 0xFFEE00, 0x028121, 0x004CFF, 0x770088],
+    ),
+    (
+        "progress",
+        &[
+            0xFFFFFF, 0xFFAFC8, 0x74D7EE, 0x613915, 0x000000, 0xE40303, 0xFF8C00, 0xFFED00,
+            0x008026, 0x24408E, 0x732982,
+        ],
+    ),
+    ("trans", &[0x5BCFFB, 0xF5ABB9, 0xFFFFFF, 0xF5ABB9, 0x5BCFFB]),
+    ("bi", &[0xD60270, 0x9B4F96, 0x0038A8]),
+    ("pan", &[0xFF1C8D, 0xFFD700, 0x1AB3FF]),
+    ("nb", &[0xFCF431, 0xFCFCFC, 0x9D59D2, 0x282828]),
+    (
+        "lesbian",
+        &[0xD62800, 0xFF9B56, 0xFFFFFF, 0xD462A6, 0xA40062],
+    ),
+    ("gay", &[0x078D70, 0x98E8C1, 0xFFFFFF, 0x7BADE2, 0x3D1A78]),
+    ("ace", &[0x000000, 0xA4A4A4, 0xFFFFFF, 0x810081]),
+    ("aro", &[0x3BA740, 0xA8D47A, 0xFFFFFF, 0xABABAB, 0x000000]),
+];
+
+/// Gets the gradient color stops of the flag with the provided name
+pub(crate) fn flag(name: &str) -> Option<&'static [u32]> {
+    FLAGS
+        .iter()
+        .find(|(flag, _)| flag.eq_ignore_ascii_case(name))
+        .map(|(_, stops)| *stops)
+}
@@ -0,0 +1,98 @@
+//! Translation of legacy `&` and `§` codes mixed into minimessage strings into tags
+
+use crate::component::legacy::{read_bungee_hex, SECTION_SIGN};
+use crate::component::{Formatting, NamedColor};
+use crate::message::tokens::arguments_len;
+use crate::message::ParseError;
+
+/// Maximum length of a legacy code in characters, which is the `&x&r&r&g&g&b&b` hex color
+pub(crate) const MAX_CODE_LEN: usize = 14;
+
+/// A message with its legacy codes translated into tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Translated {
+    pub(crate) text: String,
+    /// Offsets in the translated text and the original message where every
+    /// translated tag and every run of untouched text starts
+    segments: Vec<(usize, usize)>,
+}
+
+impl Translated {
+    /// Gets the offset in the original message matching the offset in the translated text
+    pub(crate) fn original_offset(&self, offset: usize) -> usize {
+        let idx = self
+            .segments
+            .partition_point(|(translated, _)| *translated <= offset);
+        match idx.checked_sub(1).map(|idx| self.segments[idx]) {
+            Some((translated, original)) => original + (offset - translated),
+            None => offset,
+        }
+    }
+
+    /// Moves the offset of the error from the translated text to the original message
+    pub(crate) fn restore(&self, err: ParseError) -> ParseError {
+        let offset = err.offset();
+        err.shifted(self.original_offset(offset) as isize - offset as isize)
+    }
+}
+
+/// Checks whether the character starts a legacy code
+pub(crate) fn is_code_char(c: char) -> bool {
+    c == '&' || c == SECTION_SIGN
+}
+
+/// Translates the legacy codes outside of tags into the equivalent tags.
+///
+/// Color codes become color tags, formatting codes become formatting tags, `r` becomes
+/// `<reset>` and BungeeCord hex colors become hex color tags. Anything else, e.g. the
+/// ampersand in `Tom & Jerry`, is kept as is.
+pub(crate) fn translate(msg: &str) -> Translated {
+    let mut text = String::with_capacity(msg.len());
+    let mut segments = Vec::new();
+    let mut idx = 0;
+    while let Some(c) = msg[idx..].chars().next() {
+        let rest = &msg[idx + c.len_utf8()..];
+        if c == '<' {
+            // tags are copied as is, as their arguments may contain ampersands, e.g. in URLs
+            let len = arguments_len(rest).map_or(1, |len| len + 2);
+            text.push_str(&msg[idx..idx + len]);
+            idx += len;
+            continue;
+        }
+        let Some((tag, len)) = is_code_char(c).then(|| code_tag(c, rest)).flatten() else {
+            text.push(c);
+            idx += c.len_utf8();
+            continue;
+        };
+        segments.push((text.len(), idx));
+        text.push_str(&tag);
+        idx += len;
+        segments.push((text.len(), idx));
+    }
+    Translated { text, segments }
+}
+
+/// Gets the tag equivalent to the legacy code starting with the `code` character and
+/// followed by the rest of the message, along with the length of the code in bytes
+fn code_tag(code: char, rest: &str) -> Option<(String, usize)> {
+    let mut chars = rest.chars();
+    let next = chars.next()?;
+    let len = code.len_utf8() + next.len_utf8();
+    if next.eq_ignore_ascii_case(&'x') {
+        let hex = read_bungee_hex(&mut chars, code)?;
+        let len = code.len_utf8() + rest.len() - chars.as_str().len();
+        return Some((format!("<#{:06x}>", hex), len));
+    }
+    if let Some(color) = NamedColor::from_legacy_code(next) {
+        return Some((format!("<{}>", color), len));
+    }
+    let name = match Formatting::from_legacy_code(next)? {
+        Formatting::Obfuscated => "obfuscated",
+        Formatting::Bold => "bold",
+        Formatting::Strikethrough => "strikethrough",
+        Formatting::Underline => "underline",
+        Formatting::Italic => "italic",
+        Formatting::Reset => "reset",
+    };
+    Some((format!("<{}>", name), len))
+}
@@ -0,0 +1,255 @@
+use crate::component::{AsComponent, Component};
+use crate::message::legacy;
+use crate::message::tokens::{is_builtin_tag, MessageToken, Parser};
+use crate::message::{ParseError, DEFAULT_MAX_DEPTH, DEFAULT_MAX_LEAVES, DEFAULT_MAX_LENGTH};
+use logos::{Lexer, Logos};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// A custom tag resolver, receiving the colon-separated arguments of the tag
+type TagResolver = Arc<dyn Fn(&[String]) -> Component + Send + Sync>;
+
+/// Resolves placeholders lazily, when they are encountered while parsing
+pub trait PlaceholderResolver {
+    /// Resolves the placeholder with the provided name,
+    /// returning [None] if this resolver doesn't know it
+    fn resolve(&self, name: &str) -> Option<Component>;
+}
+
+impl PlaceholderResolver for HashMap<String, Component> {
+    fn resolve(&self, name: &str) -> Option<Component> {
+        self.get(name).cloned()
+    }
+}
+
+impl<F> PlaceholderResolver for F
+where
+    F: Fn(&str) -> Option<Component>,
+{
+    fn resolve(&self, name: &str) -> Option<Component> {
+        self(name)
+    }
+}
+
+/// Limits on the size of the components produced by a parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Limits {
+    pub(crate) leaves: usize,
+    pub(crate) depth: usize,
+    pub(crate) length: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            leaves: DEFAULT_MAX_LEAVES,
+            depth: DEFAULT_MAX_DEPTH,
+            length: DEFAULT_MAX_LENGTH,
+        }
+    }
+}
+
+/// A reusable and configurable minimessage parser.
+///
+/// The free functions like [`lobster`](crate::message::lobster) are shortcuts
+/// for parsing with a default or slightly adjusted instance of this parser.
+///
+/// ```rust
+/// use lobsterchat::component::{Colored, Component, NamedColor};
+/// use lobsterchat::message::MiniMessageParser;
+///
+/// let parser = MiniMessageParser::new()
+///     .strict(true)
+///     .placeholder("player", Component::text("Maxus").color(NamedColor::Gold));
+///
+/// let joined = parser.parse("<player> <gray>joined the game").unwrap();
+/// let left = parser.parse("<player> <gray>left the game").unwrap();
+/// ```
+///
+/// The size of the parsed components is limited, so that untrusted messages can not
+/// exhaust the memory, e.g. with thousands of nested tags or a gradient over a huge text.
+/// Crossing any of the limits fails [`Self::parse()`] with [`ParseError::LimitExceeded`],
+/// regardless of [`Self::strict()`], while the infallible functions such as
+/// [`crate::lobster`] cut the message off at the limit instead.
+#[derive(Clone, Default)]
+pub struct MiniMessageParser {
+    pub(crate) strict: bool,
+    pub(crate) lenient: bool,
+    pub(crate) ignore_events: bool,
+    pub(crate) legacy_codes: bool,
+    pub(crate) limits: Limits,
+    pub(crate) placeholders: HashMap<String, Component>,
+    pub(crate) tags: HashMap<String, TagResolver>,
+    pub(crate) resolvers: Vec<Arc<dyn PlaceholderResolver + Send + Sync>>,
+}
+
+impl Debug for MiniMessageParser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MiniMessageParser")
+            .field("strict", &self.strict)
+            .field("lenient", &self.lenient)
+            .field("ignore_events", &self.ignore_events)
+            .field("legacy_codes", &self.legacy_codes)
+            .field("limits", &self.limits)
+            .field("placeholders", &self.placeholders)
+            .field("tags", &self.tags.keys().collect::<Vec<_>>())
+            .field("resolvers", &self.resolvers.len())
+            .finish()
+    }
+}
+
+impl MiniMessageParser {
+    /// Constructs a new parser with the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether unknown tags, malformed hex colors and unbalanced closing tags
+    /// should fail the parsing with a [`ParseError`]. Takes precedence over [`Self::lenient()`]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets whether unknown tags, stray angle brackets and unbalanced closing tags
+    /// should be kept as literal text
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Sets whether `<click>` and `<hover>` tags should be ignored, so that the resulting
+    /// components have no events. Useful when parsing messages written by untrusted players,
+    /// see also [`Component::strip_events()`]
+    pub fn ignore_events(mut self, ignore_events: bool) -> Self {
+        self.ignore_events = ignore_events;
+        self
+    }
+
+    /// Sets whether legacy `&` and `§` codes outside of tags should be translated into
+    /// the equivalent tags before parsing, see [`lobster_with_legacy`](crate::message::lobster_with_legacy)
+    pub fn legacy_codes(mut self, legacy_codes: bool) -> Self {
+        self.legacy_codes = legacy_codes;
+        self
+    }
+
+    /// Sets the maximum count of leaf components in the parsed component, where every
+    /// character of a gradient counts as a separate leaf. Defaults to [`DEFAULT_MAX_LEAVES`]
+    pub fn max_leaves(mut self, max_leaves: usize) -> Self {
+        self.limits.leaves = max_leaves;
+        self
+    }
+
+    /// Sets the maximum nesting depth of the parsed component, as measured by
+    /// [`Component::depth()`]. Defaults to [`DEFAULT_MAX_DEPTH`]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.limits.depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum total length of the text in the parsed component, in characters,
+    /// including the text of placeholders. Defaults to [`DEFAULT_MAX_LENGTH`]
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.limits.length = max_length;
+        self
+    }
+
+    /// Adds a placeholder, replacing `<name>` tags with the provided component
+    pub fn placeholder<S: Into<String>, C: AsComponent>(mut self, name: S, placeholder: C) -> Self {
+        self.placeholders
+            .insert(name.into(), placeholder.as_component());
+        self
+    }
+
+    /// Adds a placeholder resolver, which is queried for placeholders not added with
+    /// [`Self::placeholder()`] when they are encountered. Resolvers are queried in the order they were added.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    /// use lobsterchat::message::MiniMessageParser;
+    ///
+    /// let parser = MiniMessageParser::new().placeholder_resolver(|name: &str| {
+    ///     name.strip_prefix("stat_").map(|stat| Component::text(stat.len().to_string()))
+    /// });
+    ///
+    /// let mut stats = parser.parse("Kills: <stat_kills>").unwrap();
+    /// assert_eq!(stats.flatten(), "Kills: 5");
+    /// ```
+    pub fn placeholder_resolver<R>(mut self, resolver: R) -> Self
+    where
+        R: PlaceholderResolver + Send + Sync + 'static,
+    {
+        self.resolvers.push(Arc::new(resolver));
+        self
+    }
+
+    /// Adds a custom tag, replacing `<name>` and `<name:arg1:arg2...>` tags with the
+    /// component returned by the resolver. The resolver receives the colon-separated
+    /// arguments of the tag.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    /// use lobsterchat::message::MiniMessageParser;
+    ///
+    /// let parser = MiniMessageParser::new().tag("time", |args| {
+    ///     let format = args.join(":");
+    ///     Component::text(format.replace("%H", "13").replace("%M", "37"))
+    /// });
+    ///
+    /// let mut time = parser.parse("It is <time:%H:%M> now").unwrap();
+    /// assert_eq!(time.flatten(), "It is 13:37 now");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name collides with a built-in tag, like `<red>` or `<bold>`
+    pub fn tag<S, F>(mut self, name: S, resolver: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&[String]) -> Component + Send + Sync + 'static,
+    {
+        let name = name.into();
+        assert!(
+            !is_builtin_tag(&name),
+            "Custom tag '{}' collides with a built-in tag!",
+            name
+        );
+        self.tags.insert(name, Arc::new(resolver));
+        self
+    }
+
+    /// Parses the provided minimessage string into a component.
+    ///
+    /// Fails when the message crosses one of the limits of the parser, and in strict
+    /// mode on invalid tags. Otherwise invalid tags either stop the parsing
+    /// or are kept as literal text in lenient mode.
+    pub fn parse(&self, msg: &str) -> Result<Component, ParseError> {
+        if !self.legacy_codes {
+            return self.parser(msg).try_parse();
+        }
+        let translated = legacy::translate(msg);
+        self.parser(&translated.text)
+            .try_parse()
+            .map_err(|err| translated.restore(err))
+    }
+
+    /// Resolves the inner contents of a tag either as a placeholder or a custom tag
+    pub(crate) fn resolve(&self, tag: &str) -> Option<Component> {
+        if let Some(placeholder) = self.placeholders.get(tag) {
+            return Some(placeholder.clone());
+        }
+        if let Some(placeholder) = self.resolvers.iter().find_map(|it| it.resolve(tag)) {
+            return Some(placeholder);
+        }
+        let mut parts = tag.split(':');
+        let resolver = self.tags.get(parts.next()?)?;
+        let args = parts.map(String::from).collect::<Vec<_>>();
+        Some(resolver(&args))
+    }
+
+    pub(crate) fn parser<'a>(&'a self, msg: &'a str) -> Parser<'a> {
+        let lexer: Lexer<MessageToken> = MessageToken::lexer(msg);
+        Parser::new(lexer, self)
+    }
+}
@@ -0,0 +1,204 @@
+//! Parsing of minimessage strings read incrementally from a reader
+
+use crate::component::Component;
+use crate::message::legacy::{self, is_code_char, MAX_CODE_LEN};
+use crate::message::{MiniMessageParser, ParseError};
+use std::io::BufRead;
+
+/// Amount of bytes read before the message read so far is parsed
+pub(crate) const CHUNK_SIZE: usize = 8192;
+
+impl MiniMessageParser {
+    /// Parses a minimessage string read from the reader, without reading it into memory whole.
+    ///
+    /// The message is parsed in chunks, which are only ever split outside of tags. The tags
+    /// open at the end of a chunk are reopened for the next one, except for gradients, which
+    /// need their whole text at once, so the text in their scope is always kept in memory.
+    /// The resulting component is displayed the same way as the one from [`Self::parse()`],
+    /// but it may be structured differently.
+    ///
+    /// ```rust
+    /// use lobsterchat::component::Component;
+    /// use lobsterchat::message::MiniMessageParser;
+    ///
+    /// let source = "<red>Hello, <bold>World!".as_bytes();
+    /// let parsed = MiniMessageParser::new().parse_reader(source).unwrap();
+    /// assert!(parsed.semantically_eq(&"<red>Hello, <bold>World!".parse::<Component>().unwrap()));
+    /// ```
+    pub fn parse_reader<R: BufRead>(&self, reader: R) -> Result<Component, ParseError> {
+        parse_chunked(self, reader, CHUNK_SIZE)
+    }
+}
+
+/// Parses a message read from the reader in chunks of at least `chunk_size` bytes
+pub(crate) fn parse_chunked<R: BufRead>(
+    options: &MiniMessageParser,
+    mut reader: R,
+    chunk_size: usize,
+) -> Result<Component, ParseError> {
+    let mut state = ChunkedParse {
+        options,
+        root: Component::default(),
+        reopen: String::new(),
+        counts: (0, 0),
+        offset: 0,
+    };
+    // text that is read, but not parsed yet
+    let mut pending = String::new();
+    // bytes of a character that is not read whole yet
+    let mut undecoded = Vec::new();
+    let mut target = chunk_size;
+    loop {
+        let read_offset = state.offset + pending.len() + undecoded.len();
+        let read = reader.fill_buf().map_err(|err| ParseError::Read {
+            message: err.to_string(),
+            offset: read_offset,
+        })?;
+        if read.is_empty() {
+            break;
+        }
+        let len = read.len();
+        undecoded.extend_from_slice(read);
+        reader.consume(len);
+        decode(&mut undecoded, &mut pending, state.offset)?;
+
+        if pending.len() < target {
+            continue;
+        }
+        let mut cut = safe_cut(&pending);
+        if options.legacy_codes {
+            cut = before_codes(&pending[..cut]);
+        }
+        let parsed = match cut {
+            0 => None,
+            _ => state.parse_part(&pending[..cut], false)?,
+        };
+        match parsed {
+            Some(true) => {
+                pending.drain(..cut);
+                target = chunk_size;
+            }
+            // parsing stopped at an invalid tag, so the rest of the message is ignored
+            Some(false) => return Ok(state.root),
+            // waiting for the end of the open tag or gradient
+            None => target = pending.len() * 2,
+        }
+    }
+    if !undecoded.is_empty() {
+        return Err(ParseError::Read {
+            message: "incomplete UTF-8 character at the end of the message".into(),
+            offset: state.offset + pending.len(),
+        });
+    }
+    state.parse_part(&pending, true)?;
+    Ok(state.root)
+}
+
+/// State of a message parsed in chunks
+struct ChunkedParse<'a> {
+    options: &'a MiniMessageParser,
+    root: Component,
+    /// Source of the tags open at the end of the previous chunk
+    reopen: String,
+    /// Leaf count and text length of the chunks parsed so far
+    counts: (usize, usize),
+    /// Byte offset of the next chunk in the message
+    offset: usize,
+}
+
+impl ChunkedParse<'_> {
+    /// Parses the next chunk of the message, returning whether the whole chunk was parsed,
+    /// or [None] if the chunk can not end here, because a gradient is still open
+    fn parse_part(&mut self, part: &str, last: bool) -> Result<Option<bool>, ParseError> {
+        let translated = self.options.legacy_codes.then(|| legacy::translate(part));
+        let translated_part = translated.as_ref().map_or(part, |it| &it.text);
+        let text = format!("{}{}", self.reopen, translated_part);
+        let mut parser = self.options.parser(&text).resume_counts(self.counts);
+        let complete = parser.advance_all().map_err(|err| {
+            let offset = err.offset();
+            let in_part = offset.saturating_sub(self.reopen.len());
+            let original = translated
+                .as_ref()
+                .map_or(in_part, |it| it.original_offset(in_part));
+            err.shifted((self.offset + original) as isize - offset as isize)
+        })?;
+        let reopen = match parser.reopening_tags() {
+            Some(reopen) => reopen,
+            None if complete && !last => return Ok(None),
+            None => String::new(),
+        };
+        self.counts = parser.counts();
+        for child in parser.finish() {
+            self.root.push_extra(child);
+        }
+        self.reopen = reopen;
+        self.offset += part.len();
+        Ok(Some(complete))
+    }
+}
+
+/// Moves the valid UTF-8 from the start of the bytes to the text, keeping an incomplete
+/// character at the end. `offset` is the offset of the text in the message.
+fn decode(bytes: &mut Vec<u8>, text: &mut String, offset: usize) -> Result<(), ParseError> {
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(decoded) => decoded.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(err) => {
+            return Err(ParseError::Read {
+                message: err.to_string(),
+                offset: offset + text.len() + err.valid_up_to(),
+            })
+        }
+    };
+    if let Ok(decoded) = std::str::from_utf8(&bytes[..valid]) {
+        text.push_str(decoded);
+    }
+    bytes.drain(..valid);
+    Ok(())
+}
+
+/// Moves the end of the text before any legacy code that may continue past it
+fn before_codes(text: &str) -> usize {
+    // the text after the last tag, so that the codes inside of tags are not considered
+    let tail = text.rfind('>').map_or(0, |idx| idx + 1);
+    text[tail..]
+        .char_indices()
+        .rev()
+        .take(MAX_CODE_LEN - 1)
+        .filter(|(_, c)| is_code_char(*c))
+        .last()
+        .map_or(text.len(), |(idx, _)| tail + idx)
+}
+
+/// Gets the last position in the text outside of any tag, where it is safe to split it.
+///
+/// Quotes inside of tags are skipped over, as they may contain angle brackets.
+/// Unclosed tags, like in `<3`, are treated as tags until the next `>`,
+/// so the text is never split where the lexer would not split it.
+fn safe_cut(text: &str) -> usize {
+    let mut last = 0;
+    let mut in_tag = false;
+    let mut quote = None;
+    let mut chars = text.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match (in_tag, quote) {
+            (false, _) if c == '<' => {
+                last = idx;
+                in_tag = true;
+            }
+            (false, _) => last = idx + c.len_utf8(),
+            (true, Some(q)) if c == q => quote = None,
+            (true, Some(_)) if c == '\\' => {
+                chars.next();
+            }
+            (true, Some(_)) => {}
+            (true, None) if c == '\'' || c == '"' => quote = Some(c),
+            (true, None) if c == '>' => {
+                last = idx + 1;
+                in_tag = false;
+            }
+            (true, None) => {}
+        }
+    }
+    last
+}
@@ -1,47 +1,117 @@
 #![allow(clippy::manual_strip)]
 
-use crate::component::{AsComponent, Colored, Component, Formatting, NamedColor};
-use anyhow::bail;
+use crate::component::gradient;
+use crate::component::{
+    ClickEvent, ColorSpace, Colored, Component, DisplayEntityData, DisplayItemData, Formatting,
+    HoverEvent, NamedColor, TextColor,
+};
+use crate::message::{flags, Limit, MiniMessageParser, ParseError};
 use logos::{Lexer, Logos};
-use std::collections::{HashMap, VecDeque};
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::str::FromStr;
+use uuid::Uuid;
 
-fn grab_placeholder(lex: &mut Lexer<MessageToken>) -> Option<String> {
+fn grab_placeholder<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<&'a str> {
     let slice: &str = lex.slice();
     // skipping begin tags
-    Some(slice[1..slice.len() - 1].to_string())
+    Some(&slice[1..slice.len() - 1])
 }
 
-fn grab_named_color(lex: &mut Lexer<MessageToken>) -> Option<NamedColor> {
+fn grab_named_color<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<NamedColor> {
     let slice: &str = lex.slice();
     let inner = &slice[1..slice.len() - 1];
     NamedColor::from_str(inner).ok()
 }
 
-fn grab_formatting(lex: &mut Lexer<MessageToken>) -> Option<(Formatting, bool)> {
+fn grab_formatting<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<(Formatting, bool)> {
     let slice: &str = lex.slice();
     let inner = &slice[1..slice.len() - 1];
     if inner.starts_with('/') {
         Some((Formatting::from_str(&inner[1..]).ok()?, false))
+    } else if let Some((name, value)) = inner.split_once(':') {
+        // explicit value, e.g. `<bold:false>`
+        Some((
+            Formatting::from_str(name).ok()?,
+            bool::from_str(value).ok()?,
+        ))
     } else {
         Some((Formatting::from_str(inner).ok()?, true))
     }
 }
 
-fn grab_string(lex: &mut Lexer<MessageToken>) -> Option<String> {
+fn grab_closing_tag<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<&'a str> {
     let slice: &str = lex.slice();
-    Some(slice.into())
+    // skipping `</` and `>`
+    Some(&slice[2..slice.len() - 1])
 }
 
-fn grab_hex(lex: &mut Lexer<MessageToken>) -> Option<u32> {
+fn grab_rainbow<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> &'a str {
+    let slice: &str = lex.slice();
+    // the arguments without the leading colon, which are empty without any arguments
+    slice["<rainbow".len()..slice.len() - 1].trim_start_matches(':')
+}
+
+fn grab_pride<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<&'a str> {
+    let slice: &str = lex.slice();
+    let inner = &slice[1..slice.len() - 1];
+    match inner.split_once(':') {
+        Some((_, flag)) => Some(flag),
+        None => Some(flags::DEFAULT_FLAG),
+    }
+}
+
+fn grab_arguments<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<&'a str> {
+    let slice: &str = lex.slice();
+    // skipping the tag name, keeping the arguments
+    slice[1..slice.len() - 1]
+        .split_once(':')
+        .map(|(_, args)| args)
+}
+
+/// Grabs the arguments of a tag that may contain quoted arguments. The tag is matched
+/// only up to its first `>` or just by its name, so it is extended up to its actual end here.
+fn grab_quoted_arguments<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<&'a str> {
+    let source: &'a str = lex.source();
+    let start = lex.span().start;
+    let args_start = start + source[start..].find(':')? + 1;
+    let args_end = args_start + arguments_len(&source[args_start..])?;
+    lex.bump((args_end + 1).saturating_sub(lex.span().end));
+    Some(&source[args_start..args_end])
+}
+
+/// Gets the length of tag arguments up to the closing `>`, skipping over quoted arguments
+pub(crate) fn arguments_len(args: &str) -> Option<usize> {
+    let mut idx = 0;
+    loop {
+        let rest = &args[idx..];
+        if let Some((_, len)) = read_quoted(rest) {
+            idx += len;
+            continue;
+        }
+        let end = rest.find([':', '<', '>'])?;
+        idx += end;
+        match &rest[end..end + 1] {
+            ":" => idx += 1,
+            ">" => return Some(idx),
+            _ => return None,
+        }
+    }
+}
+
+fn grab_string<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<&'a str> {
+    Some(lex.slice())
+}
+
+fn grab_hex<'a>(lex: &mut Lexer<'a, MessageToken<'a>>) -> Option<u32> {
     let slice: &str = lex.slice();
     let inner = &slice[2..slice.len() - 1];
     u32::from_str_radix(inner, 16).ok()
 }
 
 #[derive(Debug, Clone, Logos)]
-pub(crate) enum MessageToken {
+pub(crate) enum MessageToken<'a> {
     #[regex("<#[\\da-fA-F]+>", grab_hex)]
     HexColor(u32),
 
@@ -49,49 +119,415 @@ pub(crate) enum MessageToken {
     NamedColor(NamedColor),
 
     #[regex(
-        "</?(obfuscated|bold|strikethrough|underline|italic|reset)>",
-        grab_formatting
+        "</(obfuscated|obf|magic|bold|b|strikethrough|st|underline|u|italic|i|reset)>|<(obfuscated|obf|magic|bold|b|strikethrough|st|underline|u|italic|i|reset)(:(true|false))?>",
+        grab_formatting,
+        priority = 10
     )]
     Formatting((Formatting, bool)),
 
-    // #[regex("<hover:(show_text|show_item|show_entity):.*>")]
-    // HoverEvent(HoverEvent),
-    //
-    // #[regex("<click:(change_page|copy_to_clipboard|open_file|open_url|run_command|suggest_command):.*>")]
-    // ClickEvent(ClickEvent),
+    #[regex("<gradient:[^<>]*>", grab_arguments)]
+    Gradient(&'a str),
+
+    #[regex("<rainbow(:[^<>:]*)*>", grab_rainbow)]
+    Rainbow(&'a str),
+
+    #[regex("<pride(:[^<>:]+)?>", grab_pride)]
+    Pride(&'a str),
+
+    #[regex("<(lang|tr|translate):[^<>]*>", grab_quoted_arguments)]
+    #[token("<lang:", grab_quoted_arguments)]
+    #[token("<tr:", grab_quoted_arguments)]
+    #[token("<translate:", grab_quoted_arguments)]
+    Lang(&'a str),
+
+    #[regex("<(lang_or|tr_or|translate_or):[^<>]*>", grab_quoted_arguments)]
+    #[token("<lang_or:", grab_quoted_arguments)]
+    #[token("<tr_or:", grab_quoted_arguments)]
+    #[token("<translate_or:", grab_quoted_arguments)]
+    LangOr(&'a str),
+
+    #[regex("<selector:[^<>]*>", grab_quoted_arguments)]
+    #[token("<selector:", grab_quoted_arguments)]
+    Selector(&'a str),
+
+    #[regex("<score:[^<>]*>", grab_quoted_arguments)]
+    #[token("<score:", grab_quoted_arguments)]
+    Score(&'a str),
+
+    #[regex("<nbt:[^<>]*>", grab_quoted_arguments)]
+    #[token("<nbt:", grab_quoted_arguments)]
+    Nbt(&'a str),
+
+    #[regex("<click:[^<>]*>", grab_quoted_arguments)]
+    #[token("<click:", grab_quoted_arguments)]
+    Click(&'a str),
+
+    #[regex("<hover:[^<>]*>", grab_quoted_arguments)]
+    #[token("<hover:", grab_quoted_arguments)]
+    Hover(&'a str),
+
+    #[regex("<insert:[^<>]*>", grab_quoted_arguments)]
+    #[token("<insert:", grab_quoted_arguments)]
+    Insert(&'a str),
+
+    #[regex("<space:[^<>]*>", grab_arguments)]
+    Space(&'a str),
+
+    /// A resolved `<click>` tag, never produced by the lexer
+    ClickEvent(ClickEvent),
+
+    /// A resolved `<hover>` tag, never produced by the lexer
+    HoverEvent(Box<HoverEvent>),
+
+    /// A resolved `<insert>` tag, never produced by the lexer
+    Insertion(String),
+
+    /// A tag that is ignored because of the parser options, never produced by the lexer
+    Ignored,
+
     #[regex("<[^\\\\/\\s^<>#]+>", grab_placeholder)]
-    PlaceholderTag(String),
+    PlaceholderTag(&'a str),
+
+    #[regex("</[^<>]*>", grab_closing_tag)]
+    ClosingTag(&'a str),
+
+    #[regex("<[^<>]*>", grab_placeholder)]
+    UnknownTag(&'a str),
 
     #[regex("[^<>]+", grab_string)]
-    Contents(String),
+    Contents(&'a str),
 
     #[error]
     Error,
 }
 
+/// Checks whether the tag with the provided name is handled by the parser itself
+pub(crate) fn is_builtin_tag(name: &str) -> bool {
+    name.starts_with('#')
+        || NamedColor::from_str(name).is_ok()
+        || Formatting::from_str(name).is_ok()
+        || matches!(
+            name,
+            "gradient"
+                | "rainbow"
+                | "pride"
+                | "lang"
+                | "lang_or"
+                | "tr"
+                | "tr_or"
+                | "translate"
+                | "translate_or"
+                | "selector"
+                | "score"
+                | "nbt"
+                | "click"
+                | "hover"
+                | "insert"
+                | "space"
+        )
+}
+
+/// Checks whether any of the tag arguments starts with a quote that is never closed
+fn has_unbalanced_quote(args: &str) -> bool {
+    split_arguments(args).iter().any(|arg| match arg {
+        // quoted arguments are unquoted into owned strings
+        Cow::Borrowed(raw) => raw.starts_with(['\'', '"']) && read_quoted(raw).is_none(),
+        Cow::Owned(_) => false,
+    })
+}
+
+/// Splits tag arguments on colons, except for the colons inside of brackets, braces or quotes.
+///
+/// Selectors and NBT paths may contain colons themselves, e.g. in `@e[type=minecraft:zombie]`.
+/// An argument wrapped in single or double quotes is unquoted, and may contain any characters,
+/// with `\` escaping the quote character or a backslash, e.g. `'It\'s here: now'`.
+fn split_arguments(args: &str) -> Vec<Cow<'_, str>> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while start <= args.len() {
+        let rest = &args[start..];
+        if let Some((unquoted, len)) = read_quoted(rest) {
+            // anything after the closing quote makes this a regular argument instead
+            if rest.len() == len || rest[len..].starts_with(':') {
+                parts.push(Cow::Owned(unquoted));
+                start += len + 1;
+                continue;
+            }
+        }
+        let len = unquoted_len(rest);
+        parts.push(Cow::Borrowed(&rest[..len]));
+        start += len + 1;
+    }
+    parts
+}
+
+/// Reads a quoted argument at the start of the string,
+/// returning it unescaped along with its length in the source
+fn read_quoted(args: &str) -> Option<(String, usize)> {
+    let mut chars = args.char_indices();
+    let quote = chars
+        .next()
+        .map(|(_, c)| c)
+        .filter(|c| matches!(c, '\'' | '"'))?;
+    let mut buf = String::new();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next()?.1 {
+                escaped if escaped == quote || escaped == '\\' => buf.push(escaped),
+                other => {
+                    buf.push('\\');
+                    buf.push(other);
+                }
+            },
+            c if c == quote => return Some((buf, idx + 1)),
+            c => buf.push(c),
+        }
+    }
+    None
+}
+
+/// Gets the length of an unquoted argument, which ends at a colon outside of brackets
+fn unquoted_len(args: &str) -> usize {
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for (idx, c) in args.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '{' if !quoted => depth += 1,
+            ']' | '}' if !quoted => depth = depth.saturating_sub(1),
+            ':' if !quoted && depth == 0 => return idx,
+            _ => {}
+        }
+    }
+    args.len()
+}
+
+/// Builds a click event from the arguments of a `<click:action:value>` tag
+fn click_event(args: &str) -> Option<ClickEvent> {
+    let args = split_arguments(args);
+    let [action, value] = &args[..] else {
+        return None;
+    };
+    let value = value.to_string();
+    Some(match action.as_ref() {
+        "open_url" => ClickEvent::OpenUrl(value),
+        "run_command" => ClickEvent::RunCommand(value),
+        "suggest_command" => ClickEvent::SuggestCommand(value),
+        "change_page" => ClickEvent::ChangePage(value),
+        "copy_to_clipboard" => ClickEvent::CopyToClipboard(value),
+        _ => return None,
+    })
+}
+
+/// Parses the stops from the arguments of a `<gradient:stop:stop...[:phase][:!][:word]>` tag,
+/// where each stop is either a named or a hex color, along with the phase of the gradient
+/// and whether the gradient colors whole words. Fails if there are fewer than two stops.
+fn gradient_stops(args: &str) -> Option<(Vec<u32>, f32, bool)> {
+    let (args, words) = match args.strip_suffix(":word") {
+        Some(args) => (args, true),
+        None => (args, false),
+    };
+    // reversing a gradient is the same as swapping the order of its stops
+    let (args, reverse) = match args.strip_suffix(":!") {
+        Some(args) => (args, true),
+        None => (args, false),
+    };
+    // colors are never numbers, so a number can only be the phase
+    let (args, phase) = match args.rsplit_once(':') {
+        Some((stops, phase)) => match phase.parse::<f32>() {
+            Ok(phase) if phase.is_finite() => (stops, phase),
+            _ => (args, 0.0),
+        },
+        None => (args, 0.0),
+    };
+    let mut stops = args
+        .split(':')
+        .map(|stop| TextColor::from_str(stop).ok()?.to_rgb())
+        .collect::<Option<Vec<_>>>()
+        .filter(|stops| stops.len() >= 2)?;
+    if reverse {
+        stops.reverse();
+    }
+    Some((stops, phase, words))
+}
+
+/// Options of a rainbow from the arguments of a `<rainbow[:!][:s=saturation][:v=value][:word]>` tag
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RainbowArgs {
+    reverse: bool,
+    saturation: f32,
+    value: f32,
+    words: bool,
+}
+
+/// Parses the arguments of a rainbow tag, in any order. Fails on unknown arguments,
+/// and on a saturation or value outside of range of `0.0..=1.0`.
+fn rainbow_args(args: &str) -> Option<RainbowArgs> {
+    let mut rainbow = RainbowArgs {
+        reverse: false,
+        saturation: 1.0,
+        value: 1.0,
+        words: false,
+    };
+    let fraction = |arg: &str| {
+        arg.parse::<f32>()
+            .ok()
+            .filter(|it| (0.0..=1.0).contains(it))
+    };
+    for arg in args.split(':').filter(|arg| !arg.is_empty()) {
+        match arg {
+            "!" => rainbow.reverse = true,
+            "word" => rainbow.words = true,
+            _ => match arg.split_once('=')? {
+                ("s", saturation) => rainbow.saturation = fraction(saturation)?,
+                ("v", value) => rainbow.value = fraction(value)?,
+                _ => return None,
+            },
+        }
+    }
+    Some(rainbow)
+}
+
+/// Builds an entity component from the arguments of a `<selector:selector[:separator]>` tag
+fn selector(args: &str) -> Option<Component> {
+    let args = split_arguments(args);
+    if args[0].is_empty() {
+        return None;
+    }
+    // the separator is allowed to contain colons
+    let separator = Some(&args[1..])
+        .filter(|rest| !rest.is_empty())
+        .map(|rest| Component::text(rest.join(":")));
+    Some(Component::entity(args[0].as_ref(), separator))
+}
+
+/// Builds an NBT component from the arguments of a `<nbt:kind:source:path[:interpret]>` tag,
+/// where the kind is either `entity`, `block` or `storage`
+fn nbt(args: &str) -> Option<Component> {
+    let mut args = split_arguments(args);
+    let interpret = match args.last().map(Cow::as_ref) {
+        Some("interpret") => {
+            args.pop();
+            Some(true)
+        }
+        _ => None,
+    };
+    if args.len() < 3 || args.iter().any(|arg| arg.is_empty()) {
+        return None;
+    }
+    let path = args.pop()?;
+    // storage IDs are namespaced, so the source may have been split on its colon
+    let (path, source) = (path.as_ref(), args[1..].join(":"));
+    let separator = None::<Component>;
+    match args[0].as_ref() {
+        "entity" => Some(Component::entity_nbt(path, &source, interpret, separator)),
+        "block" => Some(Component::block_nbt(path, &source, interpret, separator)),
+        "storage" => Some(Component::storage_nbt(path, &source, interpret, separator)),
+        _ => None,
+    }
+}
+
+/// Builds a scoreboard component from the arguments of a `<score:name:objective[:value]>` tag
+fn score(args: &str) -> Option<Component> {
+    let args = split_arguments(args);
+    if args.iter().any(|arg| arg.is_empty()) {
+        return None;
+    }
+    match &args[..] {
+        [name, objective] => Some(Component::score(name.as_ref(), objective, None)),
+        [name, objective, value] => Some(Component::score(name.as_ref(), objective, Some(value))),
+        _ => None,
+    }
+}
+
+/// Gets the length of the text of a component, in characters
+fn text_len(comp: &Component) -> usize {
+    comp.runs().map(|(text, _)| text.chars().count()).sum()
+}
+
+/// Reason for the parser to stop advancing through the message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Halt {
+    /// The whole message was parsed
+    End,
+    /// An invalid tag was found, and the parser is neither strict nor lenient
+    Invalid,
+    /// An error was found in strict mode, or one of the limits was exceeded
+    Error(ParseError),
+}
+
+impl From<ParseError> for Halt {
+    fn from(err: ParseError) -> Self {
+        Halt::Error(err)
+    }
+}
+
+/// A tag that is currently open
+#[derive(Debug, Clone)]
+struct OpenTag<'a> {
+    name: &'a str,
+    /// The tag as written in the source
+    source: &'a str,
+    token: MessageToken<'a>,
+    /// Index of the scope in [`Parser::gradients`] if this tag is a gradient
+    gradient: Option<usize>,
+}
+
+/// Colors of a gradient scope
+#[derive(Debug, Clone)]
+enum GradientColors {
+    /// Interpolated between the stops, starting at the phase
+    Stops(Vec<u32>, f32),
+    /// Going around the hue circle, optionally in reverse, with the saturation and value
+    Rainbow(bool, f32, f32),
+}
+
+/// Text runs colored by a single gradient tag
+#[derive(Debug, Clone)]
+struct GradientScope {
+    colors: GradientColors,
+    /// Whether every word is colored with a single color, instead of every character
+    words: bool,
+    /// Positions of the runs in [`Parser::children`], as chain and node indices
+    runs: Vec<(usize, usize)>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Parser<'a> {
-    tokens: Lexer<'a, MessageToken>,
-    stack: VecDeque<MessageToken>,
-    placeholders: HashMap<String, Component>,
-    current: Component,
+    tokens: Lexer<'a, MessageToken<'a>>,
+    stack: VecDeque<MessageToken<'a>>,
+    options: &'a MiniMessageParser,
+    /// Tags that are currently open, along with the token that opened them
+    open: Vec<OpenTag<'a>>,
+    /// Gradients are applied once the whole message is parsed, so that
+    /// they are spread evenly over all the differently formatted runs in their scope
+    gradients: Vec<GradientScope>,
+    /// Top level children of the resulting component. Each of them is stored
+    /// as a chain of nodes, where every node is a child of the previous one.
+    /// The chains are only folded into an actual tree in [`Parser::finish`].
+    children: Vec<Vec<Component>>,
+    /// Count of leaf components produced so far
+    leaves: usize,
+    /// Length of the text produced so far, in characters
+    length: usize,
 }
 
 impl<'a> Parser<'a> {
-    pub(crate) fn new(lexer: Lexer<'a, MessageToken>) -> Self {
+    pub(crate) fn new(lexer: Lexer<'a, MessageToken<'a>>, options: &'a MiniMessageParser) -> Self {
         Self {
             tokens: lexer,
             stack: VecDeque::new(),
-            placeholders: HashMap::default(),
-            current: Component::default(),
+            options,
+            open: Vec::new(),
+            gradients: Vec::new(),
+            children: Vec::new(),
+            leaves: 0,
+            length: 0,
         }
     }
 
-    pub(crate) fn placeholder<S: Into<String>, P: AsComponent>(&mut self, name: S, placeholder: P) {
-        self.placeholders
-            .insert(name.into(), placeholder.as_component());
-    }
-
+    /// Parses as much of the message as possible, stopping at the first invalid tag
+    /// or at the first part of the message that would cross one of the limits
     pub(crate) fn parse(mut self) -> Component {
         while let Ok(()) = self.advance() {
             // no-op
@@ -99,53 +535,548 @@ impl<'a> Parser<'a> {
         self.finish()
     }
 
-    pub(crate) fn advance(&mut self) -> anyhow::Result<()> {
-        if let Some(tk) = self.tokens.next() {
-            return match tk {
-                MessageToken::PlaceholderTag(placeholder) => {
-                    if !self.placeholders.contains_key(&placeholder) {
-                        bail!("Undefined placeholder: '{}'!", placeholder)
+    /// Parses the whole message, returning the first [`ParseError`] encountered.
+    /// Only reports invalid tags when the parser is in strict mode, but always reports crossed limits.
+    pub(crate) fn try_parse(mut self) -> Result<Component, ParseError> {
+        self.advance_all()?;
+        Ok(self.finish())
+    }
+
+    /// Parses as much of the message as possible without finishing the component,
+    /// returning whether the whole message was parsed, instead of stopping at an invalid tag
+    pub(crate) fn advance_all(&mut self) -> Result<bool, ParseError> {
+        loop {
+            if self.tokens.remainder().is_empty() {
+                return Ok(true);
+            }
+            match self.advance() {
+                Ok(()) => {}
+                Err(Halt::Error(err)) => return Err(err),
+                Err(_) => return Ok(self.tokens.remainder().is_empty()),
+            }
+        }
+    }
+
+    /// Continues counting towards the limits of the parser from the provided counts,
+    /// for when a message is parsed in multiple parts
+    pub(crate) fn resume_counts(mut self, (leaves, length): (usize, usize)) -> Self {
+        self.leaves = leaves;
+        self.length = length;
+        self
+    }
+
+    /// Gets the count of the leaves and the length of the text produced so far
+    pub(crate) fn counts(&self) -> (usize, usize) {
+        (self.leaves, self.length)
+    }
+
+    /// Gets the source of all the currently open tags, which reopens them when parsed.
+    /// Returns [None] if any gradient is open, as those need the whole text in their scope.
+    pub(crate) fn reopening_tags(&self) -> Option<String> {
+        self.open
+            .iter()
+            .map(|tag| tag.gradient.is_none().then_some(tag.source))
+            .collect()
+    }
+
+    /// Removes the last open tag matching the predicate, returning whether such tag was open
+    fn close<F>(&mut self, predicate: F) -> bool
+    where
+        F: Fn(&str, &MessageToken) -> bool,
+    {
+        let Some(idx) = self
+            .open
+            .iter()
+            .rposition(|tag| predicate(tag.name, &tag.token))
+        else {
+            return false;
+        };
+        self.open.remove(idx);
+        true
+    }
+
+    pub(crate) fn advance(&mut self) -> Result<(), Halt> {
+        let Some(tk) = self.tokens.next() else {
+            return Err(Halt::End);
+        };
+        let slice = self.tokens.slice();
+        let offset = self.tokens.span().start;
+        match tk {
+            MessageToken::PlaceholderTag(tag) | MessageToken::UnknownTag(tag) => {
+                let Some(resolved) = self.options.resolve(tag) else {
+                    return self.unknown_tag(slice, offset);
+                };
+                let len = text_len(&resolved);
+                self.check_limits(len, resolved.count_leaves(), 1 + resolved.depth())?;
+                // placeholders and custom tags get their own top level subtree so that
+                // their style doesn't leak, and the following text resumes the open styles
+                self.children.push(vec![resolved]);
+                self.restart_chain();
+                Ok(())
+            }
+            MessageToken::Contents(contents) => self.push_text(contents),
+            MessageToken::Pride(name) => {
+                let Some(stops) = flags::flag(name) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                let colors = GradientColors::Stops(stops.to_vec(), 0.0);
+                self.open_gradient("pride", tk, colors, false);
+                Ok(())
+            }
+            MessageToken::Lang(args) | MessageToken::LangOr(args)
+                if self.options.strict && has_unbalanced_quote(args) =>
+            {
+                Err(ParseError::UnbalancedQuote {
+                    tag: slice.into(),
+                    offset,
+                }
+                .into())
+            }
+            MessageToken::Lang(args) | MessageToken::LangOr(args) => {
+                let with_fallback = matches!(tk, MessageToken::LangOr(_));
+                let Some(translated) = self.translatable(args, with_fallback) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.push_styled(translated)
+            }
+            MessageToken::Selector(args) => {
+                let Some(entity) = selector(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.push_styled(entity)
+            }
+            MessageToken::Score(args) => {
+                let Some(score) = score(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.push_styled(score)
+            }
+            MessageToken::Nbt(args) => {
+                let Some(nbt) = nbt(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.push_styled(nbt)
+            }
+            MessageToken::Space(args) => {
+                let Ok(pixels) = args.parse::<i32>() else {
+                    return self.invalid_argument(slice, offset);
+                };
+                // the space takes at least one character for every 1024 pixels, so spaces
+                // too long for the length limit are rejected before building their text
+                let min_len = pixels.unsigned_abs() as usize / 1024;
+                if self.length + min_len > self.options.limits.length {
+                    return self.check_limits(min_len, 0, 0);
+                }
+                self.push_styled(Component::pixel_space(pixels))
+            }
+            MessageToken::HexColor(_) if self.options.strict && slice.len() != 9 => {
+                Err(ParseError::MalformedHex {
+                    tag: slice.into(),
+                    offset,
+                }
+                .into())
+            }
+            MessageToken::Formatting((Formatting::Reset, true)) => {
+                // closing every open tag, so that the following text inherits nothing.
+                // gradients end here as well, spreading their colors over the text before it
+                self.open.clear();
+                self.restart_chain();
+                Ok(())
+            }
+            MessageToken::Formatting((fmt, false)) if slice.starts_with("</") => {
+                // matching on the formatting itself, so that aliases close each other
+                let closed = self.close(
+                    |_, tk| matches!(tk, MessageToken::Formatting((open, _)) if *open == fmt),
+                );
+                if !closed && self.options.strict {
+                    return Err(ParseError::UnbalancedTag {
+                        tag: slice.into(),
+                        offset,
                     }
-                    let ph = self.placeholders.get(&placeholder).unwrap();
-                    self.current = self
-                        .current
-                        .append(ph.clone())
-                        .append(Component::text("").reset(true));
-                    Ok(())
-                }
-                MessageToken::Contents(contents) => {
-                    let mut text = Component::text(&contents);
-                    while let Some(stacked) = self.stack.pop_front() {
-                        match stacked {
-                            MessageToken::HexColor(hex) => text = text.color(hex),
-                            MessageToken::NamedColor(color) => {
-                                text = text.color(color);
-                            }
-                            MessageToken::Formatting((fmt, enable)) => {
-                                text = text.formatted(fmt, Some(enable));
-                            }
-                            invalid => {
-                                bail!("Invalid token found in stack: {:?}!", invalid)
-                            }
-                        }
+                    .into());
+                }
+                self.stack.push_back(MessageToken::Formatting((fmt, false)));
+                Ok(())
+            }
+            MessageToken::ClosingTag(name) => {
+                if self.close(|open, _| open.eq_ignore_ascii_case(name)) {
+                    // a style can not be unset in the children of a component,
+                    // so the following text starts a new top level child instead
+                    self.restart_chain();
+                } else if self.options.strict {
+                    return Err(ParseError::UnbalancedTag {
+                        tag: slice.into(),
+                        offset,
                     }
-                    self.current = self.current.append_to_last_child(text);
-                    Ok(())
+                    .into());
+                } else if self.options.lenient {
+                    return self.push_text(slice);
                 }
-                MessageToken::Error => {
-                    bail!("Unexpected parsing error!")
+                Ok(())
+            }
+            MessageToken::Error => self.unknown_tag(slice, offset),
+            MessageToken::Gradient(args) => {
+                let Some((stops, phase, words)) = gradient_stops(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.open_gradient("gradient", tk, GradientColors::Stops(stops, phase), words);
+                Ok(())
+            }
+            MessageToken::Rainbow(args) => {
+                let Some(rainbow) = rainbow_args(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                let colors =
+                    GradientColors::Rainbow(rainbow.reverse, rainbow.saturation, rainbow.value);
+                self.open_gradient("rainbow", tk, colors, rainbow.words);
+                Ok(())
+            }
+            MessageToken::Click(_) | MessageToken::Hover(_) if self.options.ignore_events => {
+                let name = if matches!(tk, MessageToken::Click(_)) {
+                    "click"
+                } else {
+                    "hover"
+                };
+                self.open_style(name, MessageToken::Ignored);
+                Ok(())
+            }
+            MessageToken::Click(args) => {
+                let Some(event) = click_event(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.open_style("click", MessageToken::ClickEvent(event));
+                Ok(())
+            }
+            MessageToken::Hover(args) => {
+                let Some(event) = self.hover_event(args) else {
+                    return self.invalid_argument(slice, offset);
+                };
+                self.open_style("hover", MessageToken::HoverEvent(Box::new(event)));
+                Ok(())
+            }
+            MessageToken::Insert(args) => {
+                let text = split_arguments(args).join(":");
+                self.open_style("insert", MessageToken::Insertion(text));
+                Ok(())
+            }
+            other => {
+                let name = slice[1..slice.len() - 1]
+                    .split(':')
+                    .next()
+                    .unwrap_or_default();
+                self.open_style(name, other);
+                Ok(())
+            }
+        }
+    }
+
+    /// Handles a tag that is neither a built-in tag, a placeholder nor a custom tag
+    fn unknown_tag(&mut self, slice: &str, offset: usize) -> Result<(), Halt> {
+        if self.options.strict {
+            // a plain `<name>` tag is most likely a missing placeholder, if any are in use
+            let is_placeholder = slice
+                .strip_prefix('<')
+                .and_then(|tag| tag.strip_suffix('>'))
+                .is_some_and(|name| {
+                    !name.is_empty()
+                        && !name.contains(|c: char| c.is_whitespace() || ":/<>".contains(c))
+                });
+            let options = self.options;
+            if slice.starts_with("<#") {
+                Err(ParseError::MalformedHex {
+                    tag: slice.into(),
+                    offset,
                 }
-                other => {
-                    self.stack.push_back(other);
-                    Ok(())
+                .into())
+            } else if is_placeholder
+                && (!options.placeholders.is_empty() || !options.resolvers.is_empty())
+            {
+                Err(ParseError::UndefinedPlaceholder {
+                    tag: slice.into(),
+                    offset,
                 }
-            };
+                .into())
+            } else {
+                Err(ParseError::UnknownTag {
+                    tag: slice.into(),
+                    offset,
+                }
+                .into())
+            }
+        } else if self.options.lenient {
+            self.push_text(slice)
+        } else {
+            Err(Halt::Invalid)
+        }
+    }
+
+    /// Handles a known tag with invalid arguments
+    fn invalid_argument(&mut self, slice: &str, offset: usize) -> Result<(), Halt> {
+        if self.options.strict {
+            Err(ParseError::InvalidArgument {
+                tag: slice.into(),
+                offset,
+            }
+            .into())
+        } else if self.options.lenient {
+            self.push_text(slice)
         } else {
-            bail!("EOF Reached!")
+            Err(Halt::Invalid)
         }
     }
 
-    pub fn finish(self) -> Component {
-        self.current
+    /// Opens a tag that styles all the following text until it is closed
+    fn open_style(&mut self, name: &'a str, token: MessageToken<'a>) {
+        self.open.push(OpenTag {
+            name,
+            source: self.tokens.slice(),
+            token: token.clone(),
+            gradient: None,
+        });
+        self.stack.push_back(token);
+    }
+
+    /// Builds a hover event from the arguments of a `<hover:action:value...>` tag.
+    ///
+    /// The text of `show_text` is parsed as MiniMessage with the same options,
+    /// `show_item` takes the item ID and an optional count,
+    /// and `show_entity` takes the entity type, its UUID and an optional name.
+    fn hover_event(&self, args: &str) -> Option<HoverEvent> {
+        let args = split_arguments(args);
+        match (args[0].as_ref(), &args[1..]) {
+            ("show_text", text) if !text.is_empty() => {
+                let text = self.options.parse(&text.join(":")).ok()?;
+                Some(HoverEvent::show_text(text))
+            }
+            ("show_item", [id, rest @ ..]) if rest.len() <= 1 => {
+                let count = match rest.first() {
+                    Some(count) => Some(count.parse().ok()?),
+                    None => None,
+                };
+                let item = DisplayItemData::try_new(id.as_ref(), count, None).ok()?;
+                Some(HoverEvent::show_item(item))
+            }
+            ("show_entity", [entity_type, id, rest @ ..]) if rest.len() <= 1 => {
+                let name = match rest.first() {
+                    Some(name) => Some(self.options.parse(name).ok()?),
+                    None => None,
+                };
+                let id = Uuid::parse_str(id).ok()?;
+                let entity = DisplayEntityData::try_new(name, entity_type.as_ref(), id).ok()?;
+                Some(HoverEvent::show_entity(entity))
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds a translatable component from the arguments of a `<lang:key:args...>` tag,
+    /// or of a `<lang_or:key:fallback:args...>` tag if `with_fallback` is set.
+    ///
+    /// Every argument is parsed as MiniMessage with the same options, so it may contain
+    /// tags and placeholders of its own, while the key and the fallback are kept as they are.
+    fn translatable(&self, args: &str, with_fallback: bool) -> Option<Component> {
+        let mut args = split_arguments(args).into_iter();
+        let key = args.next().filter(|key| !key.is_empty())?;
+        let fallback = match with_fallback {
+            true => Some(args.next()?),
+            false => None,
+        };
+        let with = args
+            .map(|arg| {
+                let parsed = self.options.parse(&arg).ok()?;
+                // a lone child is unwrapped, so that plain arguments stay plain text components
+                Some(match parsed.children().len() {
+                    1 => parsed.into_iter().next()?,
+                    _ => parsed,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let mut translated = Component::translate(key, Some(with).filter(|it| !it.is_empty()));
+        if let Some(fallback) = fallback {
+            translated = translated.fallback(fallback);
+        }
+        Some(translated)
+    }
+
+    /// Opens a new gradient scope
+    fn open_gradient(
+        &mut self,
+        name: &'a str,
+        token: MessageToken<'a>,
+        colors: GradientColors,
+        words: bool,
+    ) {
+        self.open.push(OpenTag {
+            name,
+            source: self.tokens.slice(),
+            token,
+            gradient: Some(self.gradients.len()),
+        });
+        self.gradients.push(GradientScope {
+            colors,
+            words,
+            runs: Vec::new(),
+        });
+    }
+
+    /// Makes the following text start a new top level child,
+    /// with only the currently open styles applied
+    fn restart_chain(&mut self) {
+        self.children.push(Vec::new());
+        self.stack = self.open.iter().map(|tag| tag.token.clone()).collect();
+    }
+
+    /// Checks that appending a component of the provided text length, leaf count
+    /// and total depth in the resulting tree would not exceed any of the parser limits
+    fn check_limits(&mut self, len: usize, leaves: usize, depth: usize) -> Result<(), Halt> {
+        let limits = &self.options.limits;
+        self.length += len;
+        self.leaves += leaves;
+        let exceeded = if self.length > limits.length {
+            Limit::Length
+        } else if self.leaves > limits.leaves {
+            Limit::Leaves
+        } else if depth > limits.depth {
+            Limit::Depth
+        } else {
+            return Ok(());
+        };
+        Err(ParseError::LimitExceeded {
+            limit: exceeded,
+            offset: self.tokens.span().start,
+        }
+        .into())
+    }
+
+    /// Gets the depth of a component appended to the current chain, if it has the provided depth itself
+    fn chain_depth(&self, depth: usize) -> usize {
+        // the root component is not a part of any chain
+        1 + self.children.last().map_or(0, Vec::len) + depth
+    }
+
+    /// Appends a text component with all the pending styles applied
+    fn push_text(&mut self, contents: &str) -> Result<(), Halt> {
+        let len = contents.chars().count();
+        let Some(scope) = self.open.iter().rev().find_map(|tag| tag.gradient) else {
+            self.check_limits(len, 1, self.chain_depth(1))?;
+            return self.apply_styles(Component::text(contents));
+        };
+        // the colors are only placeholders until the gradient is applied in `finish`
+        let gradient = if self.gradients[scope].words {
+            let words = gradient::words(contents);
+            self.check_limits(len, words.len().max(1), self.chain_depth(2))?;
+            Component::gradient_words(contents, &[])
+        } else {
+            // checking before building the gradient, which has a child for every character
+            self.check_limits(len, len.max(1), self.chain_depth(2))?;
+            Component::gradient(contents, &[])
+        };
+        self.apply_styles(gradient)?;
+        let chain = self.children.len() - 1;
+        let node = self.children[chain].len() - 1;
+        self.gradients[scope].runs.push((chain, node));
+        Ok(())
+    }
+
+    /// Appends a component with all the pending styles applied
+    fn push_styled(&mut self, comp: Component) -> Result<(), Halt> {
+        let depth = self.chain_depth(comp.depth());
+        self.check_limits(text_len(&comp), comp.count_leaves(), depth)?;
+        self.apply_styles(comp)
+    }
+
+    /// Appends a component with all the pending styles applied, without checking the limits
+    fn apply_styles(&mut self, mut comp: Component) -> Result<(), Halt> {
+        while let Some(stacked) = self.stack.pop_front() {
+            match stacked {
+                MessageToken::HexColor(hex) => comp = comp.color(hex),
+                MessageToken::NamedColor(color) => {
+                    comp = comp.color(color);
+                }
+                MessageToken::Formatting((fmt, enable)) => {
+                    comp = comp.formatted(fmt, Some(enable));
+                }
+                MessageToken::ClickEvent(event) => {
+                    comp = comp.click_event(event);
+                }
+                MessageToken::HoverEvent(event) => {
+                    comp = comp.hover_event(*event);
+                }
+                MessageToken::Insertion(text) => {
+                    comp = comp.insert_text(text);
+                }
+                MessageToken::Ignored => {}
+                // gradients are applied per gradient scope instead
+                MessageToken::Gradient(_) | MessageToken::Rainbow(_) | MessageToken::Pride(_) => {}
+                // only styles are ever pushed to the stack
+                _ => return Err(Halt::Invalid),
+            }
+        }
+        match self.children.last_mut() {
+            Some(chain) => chain.push(comp),
+            None => self.children.push(vec![comp]),
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Component {
+        for scope in &self.gradients {
+            let parts = scope
+                .runs
+                .iter()
+                .flat_map(|(chain, node)| self.children[*chain][*node].children());
+            // index of the color of every part of the runs
+            let (indices, len) = if scope.words {
+                // words may be split between the runs, so their parts have to share the color
+                let mut indices = Vec::new();
+                let mut word = None;
+                let mut after_whitespace = true;
+                for part in parts {
+                    let text = part.get_text_content().unwrap_or_default();
+                    if gradient::starts_word(&text, after_whitespace) {
+                        word = Some(word.map_or(0, |word| word + 1));
+                    }
+                    indices.push(word.unwrap_or_default());
+                    if let Some(last) = text.chars().last() {
+                        after_whitespace = last.is_whitespace();
+                    }
+                }
+                (indices, word.map_or(0, |word| word + 1))
+            } else {
+                let len = parts.count();
+                ((0..len).collect(), len)
+            };
+            let colors: Vec<u32> = match &scope.colors {
+                GradientColors::Stops(stops, phase) => {
+                    gradient::colors_phased(stops, len, ColorSpace::default(), *phase).collect()
+                }
+                GradientColors::Rainbow(false, saturation, value) => {
+                    gradient::rainbow_hsv(len, *saturation, *value).collect()
+                }
+                GradientColors::Rainbow(true, saturation, value) => {
+                    gradient::rainbow_hsv(len, *saturation, *value)
+                        .rev()
+                        .collect()
+                }
+            };
+            let mut colors = indices
+                .into_iter()
+                .map_while(|idx| colors.get(idx).copied());
+            for (chain, node) in &scope.runs {
+                self.children[*chain][*node].recolor_children(&mut colors);
+            }
+        }
+
+        let mut root = Component::default();
+        for chain in self.children {
+            let folded = chain.into_iter().rev().reduce(|child, mut parent| {
+                parent.push_extra(child);
+                parent
+            });
+            if let Some(child) = folded {
+                root.push_extra(child);
+            }
+        }
+        root
     }
 }
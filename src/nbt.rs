@@ -0,0 +1,343 @@
+//!
+//! Network NBT serialization of components, used by Minecraft 1.20.3 and newer
+//!
+//! Components are converted through their JSON representation, so the NBT structure
+//! mirrors the JSON one: booleans become byte tags, arrays become list tags,
+//! objects become compound tags, and strings stay string tags.
+//!
+
+use crate::component::Component;
+use serde_json::{Map, Number, Value};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+
+/// Maximum nesting depth of lists and compounds, the same as in vanilla
+pub const MAX_DEPTH: usize = 512;
+
+/// A single NBT tag
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    /// A signed byte, also used for booleans
+    Byte(i8),
+    /// A signed 16-bit integer
+    Short(i16),
+    /// A signed 32-bit integer
+    Int(i32),
+    /// A signed 64-bit integer
+    Long(i64),
+    /// A 32-bit floating point number
+    Float(f32),
+    /// A 64-bit floating point number
+    Double(f64),
+    /// A string, encoded in modified UTF-8
+    String(String),
+    /// A list of tags, all of the same type
+    List(Vec<NbtTag>),
+    /// Named tags, kept in their insertion order
+    Compound(Vec<(String, NbtTag)>),
+}
+
+/// An error that occurred while reading or writing a component as NBT
+#[derive(Debug)]
+pub enum NbtError {
+    /// The input ended in the middle of a tag
+    UnexpectedEof,
+    /// A tag type that is unknown or can not be used in components
+    InvalidTag(u8),
+    /// A string that is not valid modified UTF-8
+    InvalidString,
+    /// The NBT structure does not describe a valid component
+    InvalidComponent(serde_json::Error),
+    /// Lists and compounds are nested deeper than [`MAX_DEPTH`]
+    TooDeep,
+    /// A string is longer than the 65535 bytes its length prefix can hold, in modified UTF-8
+    StringTooLong(usize),
+}
+
+impl Display for NbtError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NbtError::UnexpectedEof => write!(f, "Unexpected end of NBT data"),
+            NbtError::InvalidTag(id) => write!(f, "Invalid NBT tag type {}", id),
+            NbtError::InvalidString => write!(f, "Invalid modified UTF-8 string"),
+            NbtError::InvalidComponent(err) => write!(f, "Invalid component: {}", err),
+            NbtError::TooDeep => write!(f, "NBT is nested deeper than {} levels", MAX_DEPTH),
+            NbtError::StringTooLong(len) => write!(
+                f,
+                "String of {} bytes is longer than the maximum of {} bytes",
+                len,
+                u16::MAX
+            ),
+        }
+    }
+}
+
+impl Error for NbtError {}
+
+impl NbtTag {
+    fn id(&self) -> u8 {
+        match self {
+            NbtTag::Byte(_) => TAG_BYTE,
+            NbtTag::Short(_) => TAG_SHORT,
+            NbtTag::Int(_) => TAG_INT,
+            NbtTag::Long(_) => TAG_LONG,
+            NbtTag::Float(_) => TAG_FLOAT,
+            NbtTag::Double(_) => TAG_DOUBLE,
+            NbtTag::String(_) => TAG_STRING,
+            NbtTag::List(_) => TAG_LIST,
+            NbtTag::Compound(_) => TAG_COMPOUND,
+        }
+    }
+
+    /// Gets the tag with the provided name, if this is a compound tag
+    pub fn get(&self, name: &str) -> Option<&NbtTag> {
+        match self {
+            NbtTag::Compound(tags) => tags.iter().find(|(key, _)| key == name).map(|(_, tag)| tag),
+            _ => None,
+        }
+    }
+
+    /// Writes this tag in the network format, which is the tag type
+    /// followed by its payload, without a root name.
+    ///
+    /// Fails if any string is too long to be written, see [`NbtError::StringTooLong`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        let mut buf = vec![self.id()];
+        self.write_payload(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads a tag in the network format, as written by [`Self::to_bytes()`].
+    ///
+    /// Fails if lists and compounds are nested deeper than [`MAX_DEPTH`],
+    /// so that untrusted input can not overflow the stack.
+    pub fn from_bytes(bytes: &[u8]) -> Result<NbtTag, NbtError> {
+        let mut reader = Reader { bytes, depth: 0 };
+        let id = reader.u8()?;
+        reader.payload(id)
+    }
+
+    fn write_payload(&self, buf: &mut Vec<u8>) -> Result<(), NbtError> {
+        match self {
+            NbtTag::Byte(value) => buf.extend(value.to_be_bytes()),
+            NbtTag::Short(value) => buf.extend(value.to_be_bytes()),
+            NbtTag::Int(value) => buf.extend(value.to_be_bytes()),
+            NbtTag::Long(value) => buf.extend(value.to_be_bytes()),
+            NbtTag::Float(value) => buf.extend(value.to_be_bytes()),
+            NbtTag::Double(value) => buf.extend(value.to_be_bytes()),
+            NbtTag::String(value) => write_string(value, buf)?,
+            NbtTag::List(tags) => {
+                buf.push(tags.first().map(NbtTag::id).unwrap_or(TAG_END));
+                buf.extend((tags.len() as i32).to_be_bytes());
+                for tag in tags {
+                    tag.write_payload(buf)?;
+                }
+            }
+            NbtTag::Compound(tags) => {
+                for (name, tag) in tags {
+                    buf.push(tag.id());
+                    write_string(name, buf)?;
+                    tag.write_payload(buf)?;
+                }
+                buf.push(TAG_END);
+            }
+        }
+        Ok(())
+    }
+
+    fn from_json(value: Value) -> NbtTag {
+        match value {
+            // not produced by components, their empty fields are skipped instead
+            Value::Null => NbtTag::Compound(Vec::new()),
+            Value::Bool(value) => NbtTag::Byte(value as i8),
+            Value::Number(number) => match number.as_i64() {
+                Some(int) => match i32::try_from(int) {
+                    Ok(int) => NbtTag::Int(int),
+                    Err(_) => NbtTag::Long(int),
+                },
+                None => NbtTag::Double(number.as_f64().unwrap_or_default()),
+            },
+            Value::String(value) => NbtTag::String(value),
+            Value::Array(values) => {
+                NbtTag::List(values.into_iter().map(NbtTag::from_json).collect())
+            }
+            Value::Object(fields) => NbtTag::Compound(
+                fields
+                    .into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(name, value)| (name, NbtTag::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn into_json(self) -> Value {
+        match self {
+            NbtTag::Byte(value) => Value::Bool(value != 0),
+            NbtTag::Short(value) => Value::from(value),
+            NbtTag::Int(value) => Value::from(value),
+            NbtTag::Long(value) => Value::from(value),
+            NbtTag::Float(value) => {
+                Number::from_f64(value as f64).map_or(Value::Null, Value::Number)
+            }
+            NbtTag::Double(value) => Number::from_f64(value).map_or(Value::Null, Value::Number),
+            NbtTag::String(value) => Value::String(value),
+            NbtTag::List(tags) => Value::Array(tags.into_iter().map(NbtTag::into_json).collect()),
+            NbtTag::Compound(tags) => Value::Object(
+                tags.into_iter()
+                    .map(|(name, tag)| (name, tag.into_json()))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+impl Component {
+    /// Converts this component into an NBT tag, as sent over the network
+    /// by Minecraft 1.20.3 and newer
+    pub fn to_nbt(&self) -> NbtTag {
+        NbtTag::from_json(serde_json::to_value(self).unwrap_or_default())
+    }
+
+    /// Converts this component into raw network NBT bytes, failing
+    /// if any of its strings is too long, see [`NbtError::StringTooLong`]
+    pub fn to_nbt_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        self.to_nbt().to_bytes()
+    }
+
+    /// Reads a component from an NBT tag.
+    ///
+    /// A plain string tag is read as a literal text component.
+    pub fn from_nbt(tag: NbtTag) -> Result<Component, NbtError> {
+        match tag {
+            NbtTag::String(text) => Ok(Component::text(text)),
+            tag => serde_json::from_value(tag.into_json()).map_err(NbtError::InvalidComponent),
+        }
+    }
+
+    /// Reads a component from raw network NBT bytes, see [`NbtTag::from_bytes()`]
+    pub fn from_nbt_bytes(bytes: &[u8]) -> Result<Component, NbtError> {
+        Self::from_nbt(NbtTag::from_bytes(bytes)?)
+    }
+}
+
+/// Writes a length-prefixed modified UTF-8 string, as used by Java's `DataOutput`
+fn write_string(value: &str, buf: &mut Vec<u8>) -> Result<(), NbtError> {
+    let mut encoded = Vec::with_capacity(value.len());
+    for unit in value.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => encoded.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                encoded.push(0xC0 | (unit >> 6) as u8);
+                encoded.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                encoded.push(0xE0 | (unit >> 12) as u8);
+                encoded.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                encoded.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    let len = u16::try_from(encoded.len()).map_err(|_| NbtError::StringTooLong(encoded.len()))?;
+    buf.extend(len.to_be_bytes());
+    buf.extend(encoded);
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    /// Amount of lists and compounds the reader is currently inside of
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], NbtError> {
+        Ok(self.take_slice(N)?.try_into().unwrap())
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8], NbtError> {
+        if self.bytes.len() < len {
+            return Err(NbtError::UnexpectedEof);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, NbtError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn string(&mut self) -> Result<String, NbtError> {
+        let len = u16::from_be_bytes(self.take()?) as usize;
+        let mut bytes = self.take_slice(len)?.iter().copied();
+        let mut units = Vec::with_capacity(len);
+        while let Some(byte) = bytes.next() {
+            let mut continuation = || match bytes.next() {
+                Some(byte) if byte & 0xC0 == 0x80 => Ok((byte & 0x3F) as u16),
+                _ => Err(NbtError::InvalidString),
+            };
+            let unit = match byte {
+                0x01..=0x7F => byte as u16,
+                0xC0..=0xDF => ((byte & 0x1F) as u16) << 6 | continuation()?,
+                0xE0..=0xEF => {
+                    ((byte & 0x0F) as u16) << 12 | continuation()? << 6 | continuation()?
+                }
+                _ => return Err(NbtError::InvalidString),
+            };
+            units.push(unit);
+        }
+        String::from_utf16(&units).map_err(|_| NbtError::InvalidString)
+    }
+
+    fn payload(&mut self, id: u8) -> Result<NbtTag, NbtError> {
+        Ok(match id {
+            TAG_BYTE => NbtTag::Byte(i8::from_be_bytes(self.take()?)),
+            TAG_SHORT => NbtTag::Short(i16::from_be_bytes(self.take()?)),
+            TAG_INT => NbtTag::Int(i32::from_be_bytes(self.take()?)),
+            TAG_LONG => NbtTag::Long(i64::from_be_bytes(self.take()?)),
+            TAG_FLOAT => NbtTag::Float(f32::from_be_bytes(self.take()?)),
+            TAG_DOUBLE => NbtTag::Double(f64::from_be_bytes(self.take()?)),
+            TAG_STRING => NbtTag::String(self.string()?),
+            TAG_LIST | TAG_COMPOUND if self.depth == MAX_DEPTH => return Err(NbtError::TooDeep),
+            TAG_LIST => {
+                self.depth += 1;
+                let element = self.u8()?;
+                let len = i32::from_be_bytes(self.take()?).max(0);
+                let mut tags = Vec::new();
+                for _ in 0..len {
+                    tags.push(self.payload(element)?);
+                }
+                self.depth -= 1;
+                NbtTag::List(tags)
+            }
+            TAG_COMPOUND => {
+                self.depth += 1;
+                let mut tags = Vec::new();
+                loop {
+                    match self.u8()? {
+                        TAG_END => break,
+                        id => {
+                            let name = self.string()?;
+                            tags.push((name, self.payload(id)?));
+                        }
+                    }
+                }
+                self.depth -= 1;
+                NbtTag::Compound(tags)
+            }
+            other => return Err(NbtError::InvalidTag(other)),
+        })
+    }
+}